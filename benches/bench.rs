@@ -40,18 +40,28 @@ fn key_heavy_json_value(b: &mut Bencher) {
 #[bench]
 #[cfg(feature = "serde")]
 fn key_heavy_json_serde_struct(b: &mut Bencher) {
-  #[derive(serde::Deserialize)]
-  #[allow(dead_code)]
-  struct Item {
-    id: u32,
-    name: String,
-    kind: String,
-    enabled: bool,
-    count: u32,
-    tag: String,
-  }
   let json = get_key_heavy_json();
-  b.iter(|| jsonc_parser::parse_to_serde_value::<Vec<Item>>(&json, &Default::default()).unwrap());
+  b.iter(|| jsonc_parser::parse_to_serde_value::<Vec<KeyHeavyItem>>(&json, &Default::default()).unwrap());
+}
+
+// the default last wins policy forwards every key to the visitor without
+// tracking which keys were seen, while the other policies track them
+#[bench]
+#[cfg(feature = "serde")]
+fn key_heavy_json_serde_struct_first_wins(b: &mut Bencher) {
+  let options = jsonc_parser::ParseOptions {
+    duplicate_key_policy: jsonc_parser::DuplicateKeyPolicy::FirstWins,
+    ..Default::default()
+  };
+  let json = get_key_heavy_json();
+  b.iter(|| jsonc_parser::parse_to_serde_value::<Vec<KeyHeavyItem>>(&json, &options).unwrap());
+}
+
+#[bench]
+#[cfg(feature = "serde")]
+fn key_heavy_json_from_reader(b: &mut Bencher) {
+  let json = get_key_heavy_json();
+  b.iter(|| jsonc_parser::from_reader::<_, Vec<KeyHeavyItem>>(json.as_bytes(), &Default::default()).unwrap());
 }
 
 #[bench]
@@ -88,6 +98,18 @@ fn package_json_serde_value(b: &mut Bencher) {
 
 // bench helpers
 
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[allow(dead_code)]
+struct KeyHeavyItem {
+  id: u32,
+  name: String,
+  kind: String,
+  enabled: bool,
+  count: u32,
+  tag: String,
+}
+
 fn bench_ast(b: &mut Bencher, json_text: &str) {
   b.iter(|| parse_to_ast(json_text, &Default::default(), &Default::default()).unwrap());
 }
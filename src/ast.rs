@@ -1,9 +1,19 @@
+use super::common::ColumnUnit;
+use super::common::LineAndColumn;
+use super::common::LineIndex;
 use super::common::Range;
 use super::common::Ranged;
+use crate::number::impl_number_conversions;
+use crate::pointer::parse_array_index;
+use crate::pointer::parse_pointer;
 use std::borrow::Cow;
 
 /// JSON value.
+///
+/// This is non-exhaustive so that nodes such as `MissingValue`, which only
+/// appear in error-recovering parses, can be added without breaking matches.
 #[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub enum Value<'a> {
   StringLit(StringLit<'a>),
   NumberLit(NumberLit<'a>),
@@ -11,6 +21,10 @@ pub enum Value<'a> {
   Object(Object<'a>),
   Array(Array<'a>),
   NullKeyword(NullKeyword),
+  /// Placeholder for a value that could not be parsed.
+  ///
+  /// Only created by `parse_to_ast_recovering`.
+  MissingValue(MissingValue),
 }
 
 impl<'a> Value<'a> {
@@ -55,19 +69,115 @@ impl<'a> Value<'a> {
       _ => None,
     }
   }
+
+  pub fn as_missing_value(&self) -> Option<&MissingValue> {
+    match self {
+      Value::MissingValue(node) => Some(node),
+      _ => None,
+    }
+  }
+
+  /// Gets the value at the JSON Pointer (RFC 6901) such as `/compilerOptions/paths/0`.
+  /// Its range can be used to report a location in the text.
+  ///
+  /// A duplicate key resolves to its last occurrence, the same as for `JsonValue`.
+  /// Returns `None` when the pointer is malformed or doesn't refer to a value.
+  pub fn pointer(&self, pointer: &str) -> Option<&Value<'a>> {
+    parse_pointer(pointer)?
+      .iter()
+      .try_fold(self, |value, token| match value {
+        Value::Object(obj) => obj
+          .properties
+          .iter()
+          .rfind(|prop| prop.name.as_str() == token)
+          .map(|prop| &prop.value),
+        Value::Array(arr) => arr.elements.get(parse_array_index(token)?),
+        _ => None,
+      })
+  }
+
+  /// Gets the deepest node that contains the byte index, which includes a
+  /// node that ends at it such as when a cursor is after a value.
+  pub fn node_at_offset<'b>(&'b self, byte_index: usize) -> Option<Node<'a, 'b>> {
+    let contains = |range: Range| range.start <= byte_index && byte_index <= range.end;
+    if !contains(self.range()) {
+      return None;
+    }
+    match self {
+      Value::Object(obj) => {
+        if let Some(prop) = obj.properties.iter().find(|prop| contains(prop.range)) {
+          if contains(prop.name.range()) {
+            return Some((&prop.name).into());
+          }
+          return Some(prop.value.node_at_offset(byte_index).unwrap_or(Node::ObjectProp(prop)));
+        }
+      }
+      Value::Array(arr) => {
+        if let Some(node) = arr
+          .elements
+          .iter()
+          .find_map(|element| element.node_at_offset(byte_index))
+        {
+          return Some(node);
+        }
+      }
+      _ => {}
+    }
+    Some(self.into())
+  }
+
+  /// Gets the deepest node at the 0-indexed line and column, such as a
+  /// cursor position from an editor that uses `ColumnUnit::Utf16`.
+  pub fn node_at_position<'b>(
+    &'b self,
+    line_index: &LineIndex,
+    position: LineAndColumn,
+    unit: ColumnUnit,
+  ) -> Option<Node<'a, 'b>> {
+    self.node_at_offset(line_index.byte_index(position, unit)?)
+  }
+
+  /// Gets a mutable reference to the value at the JSON Pointer (RFC 6901).
+  ///
+  /// A duplicate key resolves to its last occurrence, the same as for `JsonValue`.
+  /// Returns `None` when the pointer is malformed or doesn't refer to a value.
+  pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value<'a>> {
+    parse_pointer(pointer)?
+      .iter()
+      .try_fold(self, |value, token| match value {
+        Value::Object(obj) => obj
+          .properties
+          .iter_mut()
+          .rfind(|prop| prop.name.as_str() == token)
+          .map(|prop| &mut prop.value),
+        Value::Array(arr) => arr.elements.get_mut(parse_array_index(token)?),
+        _ => None,
+      })
+  }
 }
 
+/// Converts the value to a `serde_json::Value`.
+///
+/// Errors with the first `MissingValue` found rather than hiding the parse
+/// error it represents.
 #[cfg(feature = "serde_json")]
-impl<'a> From<Value<'a>> for serde_json::Value {
-  fn from(value: Value<'a>) -> Self {
+impl<'a> TryFrom<Value<'a>> for serde_json::Value {
+  type Error = MissingValue;
+
+  fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
     use std::str::FromStr;
-    match value {
+    Ok(match value {
       Value::Array(arr) => {
-        let vec = arr.elements.into_iter().map(|v| v.into()).collect();
+        let vec = arr
+          .elements
+          .into_iter()
+          .map(serde_json::Value::try_from)
+          .collect::<Result<_, _>>()?;
         serde_json::Value::Array(vec)
       }
       Value::BooleanLit(b) => serde_json::Value::Bool(b.value),
       Value::NullKeyword(_) => serde_json::Value::Null,
+      Value::MissingValue(node) => return Err(node),
       Value::NumberLit(num) => {
         // check if this is a hexadecimal literal (0x or 0X prefix)
         let num_str = num.value.trim_start_matches(['-', '+']);
@@ -90,24 +200,35 @@ impl<'a> From<Value<'a>> for serde_json::Value {
           let num_for_parsing = num.value.trim_start_matches('+');
           match serde_json::Number::from_str(num_for_parsing) {
             Ok(number) => serde_json::Value::Number(number),
-            Err(_) => serde_json::Value::String(num.value.to_string()),
+            // numbers like .5 and 5. are rejected by serde_json, so fall back to an f64
+            Err(_) => match crate::number::to_closest_f64(num_for_parsing) {
+              // same as the serde path, where serde_json deserializes
+              // numbers like Infinity and NaN to null
+              Ok(value) => serde_json::Number::from_f64(value)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+              Err(_) => serde_json::Value::String(num.value.to_string()),
+            },
           }
         }
       }
       Value::Object(obj) => {
         let mut map = serde_json::map::Map::new();
         for prop in obj.properties {
-          map.insert(prop.name.into_string(), prop.value.into());
+          map.insert(prop.name.into_string(), prop.value.try_into()?);
         }
         serde_json::Value::Object(map)
       }
       Value::StringLit(s) => serde_json::Value::String(s.value.into_owned()),
-    }
+    })
   }
 }
 
 /// Node that can appear in the AST.
+///
+/// This is non-exhaustive for the same reason as `Value`.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
 pub enum Node<'a, 'b> {
   StringLit(&'b StringLit<'a>),
   NumberLit(&'b NumberLit<'a>),
@@ -117,6 +238,7 @@ pub enum Node<'a, 'b> {
   Array(&'b Array<'a>),
   NullKeyword(&'b NullKeyword),
   WordLit(&'b WordLit<'a>),
+  MissingValue(&'b MissingValue),
 }
 
 impl<'a, 'b> Node<'a, 'b> {
@@ -131,6 +253,7 @@ impl<'a, 'b> Node<'a, 'b> {
       Node::Array(_) => NodeKind::Array,
       Node::NullKeyword(_) => NodeKind::NullKeyword,
       Node::WordLit(_) => NodeKind::WordLit,
+      Node::MissingValue(_) => NodeKind::MissingValue,
     }
   }
 
@@ -189,10 +312,20 @@ impl<'a, 'b> Node<'a, 'b> {
       _ => None,
     }
   }
+
+  pub fn as_missing_value(&self) -> Option<&'b MissingValue> {
+    match self {
+      Node::MissingValue(node) => Some(node),
+      _ => None,
+    }
+  }
 }
 
 /// Kind of AST node.
+///
+/// This is non-exhaustive for the same reason as `Value`.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
 pub enum NodeKind {
   StringLit,
   NumberLit,
@@ -202,6 +335,7 @@ pub enum NodeKind {
   Array,
   NullKeyword,
   WordLit,
+  MissingValue,
 }
 
 /// Node surrounded in double quotes (ex. `"my string"`).
@@ -216,7 +350,8 @@ pub struct StringLit<'a> {
 #[derive(Debug, PartialEq, Clone)]
 pub struct WordLit<'a> {
   pub range: Range,
-  pub value: &'a str,
+  /// The word with any `\uXXXX` escapes decoded.
+  pub value: Cow<'a, str>,
 }
 
 /// Represents a number (ex. `123`, `99.99`, `-1.2e+2`).
@@ -226,6 +361,8 @@ pub struct NumberLit<'a> {
   pub value: &'a str,
 }
 
+impl_number_conversions!(NumberLit<'_>, |self| self.value);
+
 /// Represents a boolean (ex. `true` or `false`).
 #[derive(Debug, PartialEq, Clone)]
 pub struct BooleanLit {
@@ -239,6 +376,16 @@ pub struct NullKeyword {
   pub range: Range,
 }
 
+/// Placeholder for a value that was expected, but could not be parsed
+/// (ex. the value in `{ "prop": }`).
+///
+/// The range is empty when nothing was in the text or covers the
+/// unexpected text that was skipped.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MissingValue {
+  pub range: Range,
+}
+
 /// Represents an object that may contain properties (ex. `{}`, `{ "prop": 4 }`).
 #[derive(Debug, PartialEq, Clone)]
 pub struct Object<'a> {
@@ -380,7 +527,7 @@ impl<'a> ObjectPropName<'a> {
   pub fn into_string(self) -> String {
     match self {
       ObjectPropName::String(lit) => lit.value.into_owned(),
-      ObjectPropName::Word(lit) => lit.value.to_string(),
+      ObjectPropName::Word(lit) => lit.value.into_owned(),
     }
   }
 
@@ -388,7 +535,7 @@ impl<'a> ObjectPropName<'a> {
   pub fn as_str(&'a self) -> &'a str {
     match self {
       ObjectPropName::String(lit) => lit.value.as_ref(),
-      ObjectPropName::Word(lit) => lit.value,
+      ObjectPropName::Word(lit) => lit.value.as_ref(),
     }
   }
 }
@@ -489,7 +636,7 @@ macro_rules! impl_ranged {
   };
 }
 
-impl_ranged![BooleanLit, NullKeyword];
+impl_ranged![BooleanLit, NullKeyword, MissingValue];
 
 macro_rules! impl_ranged_lifetime {
   ($($node_name:ident),*) => {
@@ -523,6 +670,7 @@ impl<'a> Ranged for Value<'a> {
       Value::NumberLit(node) => node.range(),
       Value::Object(node) => node.range(),
       Value::StringLit(node) => node.range(),
+      Value::MissingValue(node) => node.range(),
     }
   }
 }
@@ -538,6 +686,7 @@ impl<'a, 'b> Ranged for Node<'a, 'b> {
       Node::Array(node) => node.range(),
       Node::Object(node) => node.range(),
       Node::ObjectProp(node) => node.range(),
+      Node::MissingValue(node) => node.range(),
     }
   }
 }
@@ -554,7 +703,7 @@ macro_rules! generate_node {
     };
 }
 
-generate_node![BooleanLit, NullKeyword];
+generate_node![BooleanLit, NullKeyword, MissingValue];
 
 macro_rules! generate_node_lifetime {
     ($($node_name:ident),*) => {
@@ -580,6 +729,7 @@ impl<'a, 'b> From<&'b Value<'a>> for Node<'a, 'b> {
       Value::NumberLit(node) => Node::NumberLit(node),
       Value::Object(node) => Node::Object(node),
       Value::StringLit(node) => Node::StringLit(node),
+      Value::MissingValue(node) => Node::MissingValue(node),
     }
   }
 }
@@ -590,6 +740,80 @@ mod test {
   use crate::ParseOptions;
   use crate::parse_to_ast;
 
+  #[test]
+  fn it_should_get_node_at_offset_and_position() {
+    let text = "{\n  \"😀日本\": [1, \"a😀\", true],\n  b: null\n}";
+    let ast = parse_to_ast(text, &Default::default(), &ParseOptions::default()).unwrap();
+    let value = ast.value.unwrap();
+    let node_text = |node: Option<Node>| node.map(|node| node.text(text));
+
+    assert_eq!(node_text(value.node_at_offset(0)), Some(text));
+    assert_eq!(
+      node_text(value.node_at_offset(text.find('日').unwrap())),
+      Some("\"😀日本\"")
+    );
+    assert_eq!(node_text(value.node_at_offset(text.find('1').unwrap() + 1)), Some("1"));
+    assert_eq!(
+      node_text(value.node_at_offset(text.find(':').unwrap() + 1)),
+      Some("\"😀日本\": [1, \"a😀\", true]")
+    );
+    assert_eq!(node_text(value.node_at_offset(text.find('b').unwrap())), Some("b"));
+    assert_eq!(
+      node_text(value.node_at_offset(text.find("null").unwrap())),
+      Some("null")
+    );
+    assert_eq!(node_text(value.node_at_offset(text.len() + 1)), None);
+
+    let line_index = LineIndex::new(text);
+    // the emoji is two UTF-16 code units
+    let position = LineAndColumn { line: 1, column: 16 };
+    assert_eq!(
+      node_text(value.node_at_position(&line_index, position, ColumnUnit::Utf16)),
+      Some("\"a😀\"")
+    );
+    let position = LineAndColumn { line: 1, column: 21 };
+    assert_eq!(
+      node_text(value.node_at_position(&line_index, position, ColumnUnit::Utf16)),
+      Some("true")
+    );
+    let node = value
+      .node_at_position(&line_index, position, ColumnUnit::Utf16)
+      .unwrap();
+    assert_eq!(
+      node.range_line_and_column(&line_index, ColumnUnit::Utf16),
+      (
+        LineAndColumn { line: 1, column: 21 },
+        LineAndColumn { line: 1, column: 25 }
+      )
+    );
+    assert_eq!(
+      node.range_line_and_column(&line_index, ColumnUnit::Char),
+      (
+        LineAndColumn { line: 1, column: 19 },
+        LineAndColumn { line: 1, column: 23 }
+      )
+    );
+    // within the surrogate pair
+    let position = LineAndColumn { line: 1, column: 4 };
+    assert!(
+      value
+        .node_at_position(&line_index, position, ColumnUnit::Utf16)
+        .is_none()
+    );
+
+    let text = "[\"😀日本\", \"😀\", }]";
+    let err = crate::parse_to_value(text, &ParseOptions::strict_json()).unwrap_err();
+    let line_index = LineIndex::new(text);
+    assert_eq!(
+      err.range_line_and_column(&line_index, ColumnUnit::Utf16).0,
+      LineAndColumn { line: 0, column: 15 }
+    );
+    assert_eq!(
+      err.range_line_and_column(&line_index, ColumnUnit::Byte).0,
+      LineAndColumn { line: 0, column: 23 }
+    );
+  }
+
   #[test]
   fn it_should_take() {
     let ast = parse_to_ast(
@@ -631,6 +855,36 @@ mod test {
     assert_eq!(obj.properties.len(), 1);
   }
 
+  #[test]
+  fn it_should_get_by_pointer() {
+    let text = r#"{ "a": [{ "b/c": true }], "~": null }"#;
+    let mut value = parse_to_ast(text, &Default::default(), &ParseOptions::default())
+      .unwrap()
+      .value
+      .unwrap();
+
+    let node = value.pointer("/a/0/b~1c").unwrap();
+    assert_eq!(node.range(), Range::new(17, 21));
+    assert_eq!(node.text(text), "true");
+    assert!(value.pointer("/~0").unwrap().as_null_keyword().is_some());
+    assert_eq!(value.pointer("").unwrap().range(), Range::new(0, text.len()));
+    assert!(value.pointer("/a/1").is_none());
+    assert!(value.pointer("/a/0/b~1c/d").is_none());
+
+    let node = value.pointer_mut("/a/0/b~1c").unwrap();
+    *node = Value::NullKeyword(NullKeyword { range: node.range() });
+    assert!(value.pointer("/a/0/b~1c").unwrap().as_null_keyword().is_some());
+
+    // a duplicate key resolves to its last occurrence
+    let text = r#"{ "a": { "b": 1 }, "a": { "b": 2, "b": 3 } }"#;
+    let mut value = parse_to_ast(text, &Default::default(), &ParseOptions::default())
+      .unwrap()
+      .value
+      .unwrap();
+    assert_eq!(value.pointer("/a/b").unwrap().text(text), "3");
+    assert_eq!(value.pointer_mut("/a/b").unwrap().range(), Range::new(39, 40));
+  }
+
   #[cfg(feature = "serde_json")]
   #[test]
   fn it_should_coerce_to_serde_value() {
@@ -641,7 +895,7 @@ mod test {
     )
     .unwrap();
     let value = ast.value.unwrap();
-    let serde_value: serde_json::Value = value.into();
+    let serde_value = serde_json::Value::try_from(value).unwrap();
 
     assert_eq!(
       serde_value,
@@ -656,6 +910,14 @@ mod test {
     );
   }
 
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn it_should_error_coercing_missing_value_to_serde_value() {
+    let result = crate::parse_to_ast_recovering(r#"{"a": [1, ], "b": }"#, &Default::default(), &Default::default());
+    let err = serde_json::Value::try_from(result.value.unwrap()).unwrap_err();
+    assert_eq!(err.range, Range::new(17, 17));
+  }
+
   #[cfg(feature = "serde_json")]
   #[test]
   fn handle_weird_data() {
@@ -666,14 +928,19 @@ mod test {
     )
     .unwrap();
     let value = ast.value.unwrap();
-    let serde_value: serde_json::Value = value.into();
+    let serde_value = serde_json::Value::try_from(value).unwrap();
 
+    // serde_json keeps numbers out of the f64 range as text with arbitrary precision
+    #[cfg(feature = "arbitrary_precision")]
+    let yy = serde_json::Value::Number(std::str::FromStr::from_str("6000e000615").unwrap());
+    #[cfg(not(feature = "arbitrary_precision"))]
+    let yy = serde_json::Value::String("6000e000615".to_string());
     assert_eq!(
       serde_value,
       // this output is fine because the input is bad
       serde_json::json!({
         "eyyyyyyy": 6,
-        "yy": "6000e000615",
+        "yy": yy,
         "yyyk": 6
       })
     );
@@ -1,3 +1,5 @@
+use crate::number;
+
 /// Positional information about a start and end point in the text.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Range {
@@ -49,4 +51,276 @@ pub trait Ranged {
     let range = self.range();
     range.end - range.start
   }
+
+  /// Gets the 0-indexed lines and columns of the start and end of the range,
+  /// such as UTF-16 positions for the Language Server Protocol.
+  fn range_line_and_column(&self, line_index: &LineIndex, unit: ColumnUnit) -> (LineAndColumn, LineAndColumn) {
+    line_index.range_line_and_column(self.range(), unit)
+  }
+}
+
+/// Unit to measure columns in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnUnit {
+  /// UTF-8 bytes.
+  #[default]
+  Byte,
+  /// Unicode scalar values (`char`s).
+  Char,
+  /// UTF-16 code units, which editors using the Language Server Protocol use by default.
+  Utf16,
+  /// Display width in a terminal, which is the same as `Char` unless the
+  /// `error_unicode_width` cargo feature is enabled.
+  DisplayWidth,
+}
+
+impl ColumnUnit {
+  fn char_len(&self, c: char) -> usize {
+    match self {
+      ColumnUnit::Byte => c.len_utf8(),
+      ColumnUnit::Char => 1,
+      ColumnUnit::Utf16 => c.len_utf16(),
+      ColumnUnit::DisplayWidth => char_display_width(c),
+    }
+  }
+}
+
+/// Gets the width of the character when displayed, which is used for the
+/// column in error messages.
+pub(crate) fn char_display_width(c: char) -> usize {
+  #[cfg(feature = "error_unicode_width")]
+  {
+    unicode_width::UnicodeWidthChar::width_cjk(c).unwrap_or(0)
+  }
+  #[cfg(not(feature = "error_unicode_width"))]
+  {
+    let _ = c;
+    1
+  }
+}
+
+/// A 0-indexed line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineAndColumn {
+  pub line: usize,
+  pub column: usize,
+}
+
+/// Converts between byte indexes and lines and columns in a text.
+///
+/// The start of each line is found once on creation, so each conversion
+/// only needs to look at the text of a single line. Lines are separated by
+/// `\n`, so a `\r` before it is the last character on the line.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::common::ColumnUnit;
+/// use jsonc_parser::common::LineAndColumn;
+/// use jsonc_parser::common::LineIndex;
+///
+/// let text = "{\n  \"😀\": 1\n}";
+/// let line_index = LineIndex::new(text);
+/// let byte_index = text.find('1').unwrap();
+/// let position = line_index.line_and_column(byte_index, ColumnUnit::Utf16);
+/// assert_eq!(position, LineAndColumn { line: 1, column: 8 });
+/// assert_eq!(line_index.byte_index(position, ColumnUnit::Utf16), Some(byte_index));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+  text: &'a str,
+  line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+  pub fn new(text: &'a str) -> Self {
+    let line_starts = std::iter::once(0)
+      .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+      .collect();
+    LineIndex { text, line_starts }
+  }
+
+  /// Gets the number of lines, which is always at least one.
+  pub fn line_count(&self) -> usize {
+    self.line_starts.len()
+  }
+
+  /// Gets the byte index of the start of the 0-indexed line.
+  pub fn line_start(&self, line: usize) -> Option<usize> {
+    self.line_starts.get(line).copied()
+  }
+
+  /// Gets the 0-indexed line the byte index is on.
+  ///
+  /// Byte indexes past the end of the text are on the last line.
+  pub fn line(&self, byte_index: usize) -> usize {
+    self.line_starts.partition_point(|&start| start <= byte_index) - 1
+  }
+
+  /// Gets the 0-indexed line and column of the byte index.
+  ///
+  /// Byte indexes past the end of the text are treated as the end of the
+  /// text and ones within a character as the start of that character.
+  pub fn line_and_column(&self, byte_index: usize, unit: ColumnUnit) -> LineAndColumn {
+    let mut byte_index = byte_index.min(self.text.len());
+    while !self.text.is_char_boundary(byte_index) {
+      byte_index -= 1;
+    }
+    let line = self.line(byte_index);
+    let line_text = &self.text[self.line_starts[line]..byte_index];
+    let column = match unit {
+      ColumnUnit::Byte => line_text.len(),
+      _ => line_text.chars().map(|c| unit.char_len(c)).sum(),
+    };
+    LineAndColumn { line, column }
+  }
+
+  /// Gets the 0-indexed lines and columns of the start and end of the range.
+  pub fn range_line_and_column(&self, range: Range, unit: ColumnUnit) -> (LineAndColumn, LineAndColumn) {
+    (
+      self.line_and_column(range.start, unit),
+      self.line_and_column(range.end, unit),
+    )
+  }
+
+  /// Gets the byte index of the 0-indexed line and column.
+  ///
+  /// Returns `None` when the line doesn't exist or the column is past the end
+  /// of the line or within a character.
+  pub fn byte_index(&self, position: LineAndColumn, unit: ColumnUnit) -> Option<usize> {
+    let line_start = self.line_start(position.line)?;
+    let line_end = match self.line_starts.get(position.line + 1) {
+      Some(next_line_start) => next_line_start - 1,
+      None => self.text.len(),
+    };
+    let mut column = 0;
+    for (index, c) in self.text[line_start..line_end].char_indices() {
+      if column >= position.column {
+        return (column == position.column).then_some(line_start + index);
+      }
+      column += unit.char_len(c);
+    }
+    (column == position.column).then_some(line_end)
+  }
+
+  /// Gets the byte range of the 0-indexed start and end lines and columns.
+  pub fn range(&self, start: LineAndColumn, end: LineAndColumn, unit: ColumnUnit) -> Option<Range> {
+    Some(Range::new(self.byte_index(start, unit)?, self.byte_index(end, unit)?))
+  }
+}
+
+/// Mode to use for trailing commas.
+#[derive(Default, Debug, Clone, Copy)]
+pub enum TrailingCommaMode {
+  /// Never use trailing commas.
+  #[default]
+  Never,
+  /// Use trailing commas when the object is on multiple lines.
+  IfMultiline,
+}
+
+/// Kind of newline.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CstNewlineKind {
+  #[default]
+  LineFeed,
+  CarriageReturnLineFeed,
+}
+
+/// Gets if the number literals have the same value (ex. `1`, `1.0`, and `0x1`).
+///
+/// Finite numbers are compared exactly rather than as `f64`s.
+pub(crate) fn numbers_equal(a: &str, b: &str) -> bool {
+  if a == b {
+    return true;
+  }
+  match (number::to_decimal(a), number::to_decimal(b)) {
+    (Ok(a), Ok(b)) => a == b,
+    // Infinity is only equal to itself and NaN is never equal
+    _ => matches!((number::to_f64(a), number::to_f64(b)), (Ok(a), Ok(b)) if a.is_infinite() && a == b),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn position(line: usize, column: usize) -> LineAndColumn {
+    LineAndColumn { line, column }
+  }
+
+  #[test]
+  fn it_should_compare_numbers_exactly() {
+    assert!(numbers_equal("1", "1.0"));
+    assert!(numbers_equal("0x1F", "+31"));
+    assert!(numbers_equal("1.5e2", "150"));
+    assert!(numbers_equal("-0", "0"));
+    assert!(numbers_equal("Infinity", "+Infinity"));
+    assert!(!numbers_equal("Infinity", "-Infinity"));
+    assert!(!numbers_equal("9007199254740993", "9007199254740992"));
+    assert!(!numbers_equal("0.1", "0.10000000000000001"));
+  }
+
+  #[test]
+  fn it_should_convert_byte_indexes_to_lines_and_columns() {
+    let text = "a\r\nb😀c\n\nd";
+    let line_index = LineIndex::new(text);
+    assert_eq!(line_index.line_count(), 4);
+    assert_eq!(line_index.line_start(1), Some(3));
+    assert_eq!(line_index.line_start(4), None);
+
+    let c_index = text.find('c').unwrap();
+    assert_eq!(line_index.line_and_column(c_index, ColumnUnit::Byte), position(1, 5));
+    assert_eq!(line_index.line_and_column(c_index, ColumnUnit::Char), position(1, 2));
+    assert_eq!(line_index.line_and_column(c_index, ColumnUnit::Utf16), position(1, 3));
+    #[cfg(not(feature = "error_unicode_width"))]
+    assert_eq!(
+      line_index.line_and_column(c_index, ColumnUnit::DisplayWidth),
+      position(1, 2)
+    );
+    #[cfg(feature = "error_unicode_width")]
+    assert_eq!(
+      line_index.line_and_column(c_index, ColumnUnit::DisplayWidth),
+      position(1, 3)
+    );
+
+    // the \r is at the end of the first line
+    assert_eq!(line_index.line_and_column(1, ColumnUnit::Char), position(0, 1));
+    assert_eq!(line_index.line_and_column(2, ColumnUnit::Char), position(0, 2));
+    assert_eq!(line_index.line_and_column(9, ColumnUnit::Char), position(1, 3));
+    assert_eq!(line_index.line_and_column(10, ColumnUnit::Char), position(2, 0));
+    // within the emoji
+    assert_eq!(line_index.line_and_column(5, ColumnUnit::Char), position(1, 1));
+    // past the end
+    assert_eq!(line_index.line_and_column(100, ColumnUnit::Char), position(3, 1));
+
+    assert_eq!(
+      line_index.range_line_and_column(Range::new(0, text.len()), ColumnUnit::Byte),
+      (position(0, 0), position(3, 1))
+    );
+  }
+
+  #[test]
+  fn it_should_convert_lines_and_columns_to_byte_indexes() {
+    let text = "a\r\nb😀c\n\nd";
+    let line_index = LineIndex::new(text);
+    for byte_index in (0..=text.len()).filter(|index| text.is_char_boundary(*index)) {
+      for unit in [ColumnUnit::Byte, ColumnUnit::Char, ColumnUnit::Utf16] {
+        let position = line_index.line_and_column(byte_index, unit);
+        assert_eq!(line_index.byte_index(position, unit), Some(byte_index));
+      }
+    }
+
+    assert_eq!(line_index.byte_index(position(1, 2), ColumnUnit::Utf16), None);
+    assert_eq!(line_index.byte_index(position(1, 3), ColumnUnit::Byte), None);
+    assert_eq!(line_index.byte_index(position(0, 3), ColumnUnit::Char), None);
+    assert_eq!(line_index.byte_index(position(4, 0), ColumnUnit::Char), None);
+    assert_eq!(
+      line_index.range(position(1, 0), position(3, 1), ColumnUnit::Utf16),
+      Some(Range::new(3, text.len()))
+    );
+    assert_eq!(LineIndex::new("").byte_index(position(0, 0), ColumnUnit::Byte), Some(0));
+  }
 }
@@ -1,3 +1,6 @@
+use crate::JsonValue;
+use crate::ast;
+
 /// API user provided value for inserts and replaces.
 #[derive(Debug, Clone)]
 pub enum CstInputValue {
@@ -102,6 +105,59 @@ impl From<Vec<(String, CstInputValue)>> for CstInputValue {
   }
 }
 
+impl From<&JsonValue<'_>> for CstInputValue {
+  fn from(value: &JsonValue<'_>) -> Self {
+    match value {
+      JsonValue::Null => CstInputValue::Null,
+      JsonValue::Boolean(b) => CstInputValue::Bool(*b),
+      JsonValue::Number(n) => CstInputValue::Number(n.to_string()),
+      JsonValue::String(s) => CstInputValue::String(s.to_string()),
+      JsonValue::Array(elements) => CstInputValue::Array(elements.iter().map(Into::into).collect()),
+      JsonValue::Object(props) => CstInputValue::Object(
+        props
+          .iter()
+          .map(|(name, value)| (name.to_string(), value.into()))
+          .collect(),
+      ),
+    }
+  }
+}
+
+impl From<&ast::Value<'_>> for CstInputValue {
+  fn from(value: &ast::Value<'_>) -> Self {
+    match value {
+      ast::Value::NullKeyword(_) | ast::Value::MissingValue(_) => CstInputValue::Null,
+      ast::Value::BooleanLit(lit) => CstInputValue::Bool(lit.value),
+      ast::Value::NumberLit(lit) => CstInputValue::Number(lit.value.to_string()),
+      ast::Value::StringLit(lit) => CstInputValue::String(lit.value.to_string()),
+      ast::Value::Array(arr) => CstInputValue::Array(arr.elements.iter().map(Into::into).collect()),
+      ast::Value::Object(obj) => CstInputValue::Object(
+        obj
+          .properties
+          .iter()
+          .map(|prop| (prop.name.as_str().to_string(), (&prop.value).into()))
+          .collect(),
+      ),
+    }
+  }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Value> for CstInputValue {
+  fn from(value: serde_json::Value) -> Self {
+    match value {
+      serde_json::Value::Null => CstInputValue::Null,
+      serde_json::Value::Bool(b) => CstInputValue::Bool(b),
+      serde_json::Value::Number(n) => CstInputValue::Number(n.to_string()),
+      serde_json::Value::String(s) => CstInputValue::String(s),
+      serde_json::Value::Array(elements) => CstInputValue::Array(elements.into_iter().map(Into::into).collect()),
+      serde_json::Value::Object(props) => {
+        CstInputValue::Object(props.into_iter().map(|(name, value)| (name, value.into())).collect())
+      }
+    }
+  }
+}
+
 #[macro_export]
 macro_rules! json {
   (null) => {
@@ -30,22 +30,32 @@
 //! ```
 //!
 
+use std::borrow::Cow;
+use std::cell::Ref;
 use std::cell::RefCell;
+use std::cell::RefMut;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fmt::Display;
 use std::iter::Peekable;
 use std::rc::Rc;
 use std::rc::Weak;
 
+use super::common::Range;
 use super::common::Ranged;
 use crate::ParseOptions;
 use crate::ast;
 use crate::errors::ParseError;
+use crate::number::impl_number_conversions;
 use crate::parse_to_ast;
 use crate::string::ParseStringErrorKind;
 
 mod input;
+pub mod patch;
 
+pub use crate::common::CstNewlineKind;
+pub use crate::common::TrailingCommaMode;
 pub use input::*;
 
 macro_rules! add_root_node_method {
@@ -134,9 +144,67 @@ macro_rules! add_parent_info_methods {
     pub fn uses_trailing_commas(&self) -> bool {
       uses_trailing_commas(self.clone().into())
     }
+
+    /// Byte range of the node within the current text of the tree.
+    ///
+    /// This is computed from the tree on each call so it stays correct
+    /// after edits. For a node that has been removed from the tree, the
+    /// range is relative to the start of the removed subtree.
+    pub fn range(&self) -> Range {
+      node_range(&self.clone().into())
+    }
   };
 }
 
+fn node_range(node: &CstNode) -> Range {
+  let mut start = 0;
+  let mut current = node.clone();
+  while let Some(parent_info) = current.parent_info() {
+    let parent = parent_info.parent.as_container_node();
+    start += parent
+      .children()
+      .iter()
+      .take(parent_info.child_index)
+      .map(text_len)
+      .sum::<usize>();
+    current = parent.into();
+  }
+  Range::new(start, start + text_len(node))
+}
+
+fn descendant_leaves(node: CstNode) -> Vec<CstLeafNode> {
+  fn collect(node: CstNode, leaves: &mut Vec<CstLeafNode>) {
+    match node {
+      CstNode::Container(container) => {
+        for child in container.children() {
+          collect(child, leaves);
+        }
+      }
+      CstNode::Leaf(leaf) => leaves.push(leaf),
+    }
+  }
+
+  let mut leaves = Vec::new();
+  collect(node, &mut leaves);
+  leaves
+}
+
+/// Gets the length of the node's text without allocating it.
+fn text_len(node: &CstNode) -> usize {
+  struct LenCounter(usize);
+
+  impl std::fmt::Write for LenCounter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+      self.0 += s.len();
+      Ok(())
+    }
+  }
+
+  let mut counter = LenCounter(0);
+  let _ = std::fmt::Write::write_fmt(&mut counter, format_args!("{}", node));
+  counter.0
+}
+
 fn find_trailing_comma(node: &CstNode) -> Option<CstToken> {
   for next_sibling in node.next_siblings() {
     match next_sibling {
@@ -175,6 +243,11 @@ macro_rules! add_parent_methods {
     fn set_parent(&self, parent: Option<ParentInfo>) {
       self.0.borrow_mut().parent = parent;
     }
+
+    /// Address that identifies the node.
+    fn ptr(&self) -> *const () {
+      Rc::as_ptr(&self.0) as *const ()
+    }
   };
 }
 
@@ -224,6 +297,9 @@ macro_rules! impl_container_methods {
       }
 
       fn remove_child_set_no_parent(&self, index: usize) {
+        if let Some(child) = self.child_at_index(index) {
+          CstContainerNode::from(self.clone()).record_change(&[child]);
+        }
         let mut inner = self.0.borrow_mut();
         if index < inner.value.len() {
           let container = self.clone().into();
@@ -250,6 +326,26 @@ macro_rules! impl_leaf_methods {
     impl $node_name {
       add_parent_methods!();
       add_root_node_method!();
+
+      fn original_range(&self) -> Option<Range> {
+        self.0.borrow().original_range
+      }
+
+      fn set_original_range(&self, range: Option<Range>) {
+        self.0.borrow_mut().original_range = range;
+      }
+
+      fn detached_copy(&self) -> Self {
+        Self(CstValueInner::new(self.0.borrow().value.clone()))
+      }
+
+      fn restore_from(&self, copy: &Self) {
+        let value = copy.0.borrow().value.clone();
+        let mut inner = self.0.borrow_mut();
+        if inner.value != value {
+          inner.set_value(value);
+        }
+      }
     }
   };
 }
@@ -295,12 +391,24 @@ struct ParentInfo {
 #[derive(Debug)]
 struct CstValueInner<T> {
   parent: Option<ParentInfo>,
+  /// Range of the node in the text as of the last time text edits were
+  /// taken or `None` if the node was created or changed since then.
+  original_range: Option<Range>,
   value: T,
 }
 
 impl<T> CstValueInner<T> {
   fn new(value: T) -> Rc<RefCell<Self>> {
-    Rc::new(RefCell::new(CstValueInner { parent: None, value }))
+    Rc::new(RefCell::new(CstValueInner {
+      parent: None,
+      original_range: None,
+      value,
+    }))
+  }
+
+  fn set_value(&mut self, value: T) {
+    self.value = value;
+    self.original_range = None;
   }
 }
 
@@ -333,6 +441,39 @@ impl CstNode {
     }
   }
 
+  /// Gets the descendant value at the JSON Pointer (RFC 6901) such as `/compilerOptions/paths/0`,
+  /// which is relative to this node's value for the root node and object properties.
+  ///
+  /// A duplicate key resolves to its last occurrence, the same as for `JsonValue`.
+  /// Returns `None` when the pointer is malformed or doesn't refer to a value.
+  pub fn pointer(&self, pointer: &str) -> Option<CstNode> {
+    self.pointer_tokens(&crate::pointer::parse_pointer(pointer)?)
+  }
+
+  /// Resolves already unescaped JSON Pointer reference tokens.
+  fn pointer_tokens(&self, tokens: &[Cow<str>]) -> Option<CstNode> {
+    let mut node = match self {
+      CstNode::Container(CstContainerNode::Root(root)) => root.value()?,
+      CstNode::Container(CstContainerNode::ObjectProp(prop)) => prop.value()?,
+      _ => self.clone(),
+    };
+    for token in tokens {
+      node = match &node {
+        CstNode::Container(CstContainerNode::Object(obj)) => obj
+          .properties()
+          .into_iter()
+          .rfind(|prop| prop.name().and_then(|name| name.decoded_value().ok()).as_deref() == Some(token.as_ref()))?
+          .value()?,
+        CstNode::Container(CstContainerNode::Array(arr)) => arr
+          .elements()
+          .into_iter()
+          .nth(crate::pointer::parse_array_index(token)?)?,
+        _ => return None,
+      };
+    }
+    Some(node)
+  }
+
   /// Comments that become before this one on the same line.
   pub fn leading_comments_same_line(&self) -> impl Iterator<Item = CstComment> {
     self
@@ -566,6 +707,25 @@ impl CstNode {
     }
   }
 
+  fn ptr(&self) -> *const () {
+    match self {
+      CstNode::Container(node) => node.ptr(),
+      CstNode::Leaf(node) => node.ptr(),
+    }
+  }
+
+  /// Records the node's state in the history before it's modified.
+  fn record_change(&self) {
+    match self {
+      CstNode::Container(node) => node.record_change(&[]),
+      CstNode::Leaf(node) => {
+        if let Some(root) = node.root_node() {
+          root.record_change(self, &[]);
+        }
+      }
+    }
+  }
+
   /// Removes the node from the tree without making adjustments to any siblings.
   fn remove_raw(self) {
     let Some(parent_info) = self.parent_info() else {
@@ -616,12 +776,27 @@ impl Display for CstNode {
   }
 }
 
+impl Ranged for CstNode {
+  fn range(&self) -> Range {
+    node_range(self)
+  }
+}
+
 #[derive(Default, Debug, Clone)]
 struct StyleInfo {
   pub uses_trailing_commas: bool,
   pub newline_kind: CstNewlineKind,
 }
 
+/// Replacement of a range in the original text with new text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+  /// Byte range in the original text to replace.
+  pub range: Range,
+  /// Text to replace the range with.
+  pub new_text: String,
+}
+
 /// Enumeration of a node that has children.
 #[derive(Debug, Clone)]
 pub enum CstContainerNode {
@@ -754,6 +929,27 @@ impl CstContainerNode {
     }
   }
 
+  fn ptr(&self) -> *const () {
+    match self {
+      CstContainerNode::Root(node) => node.ptr(),
+      CstContainerNode::Object(node) => node.ptr(),
+      CstContainerNode::ObjectProp(node) => node.ptr(),
+      CstContainerNode::Array(node) => node.ptr(),
+    }
+  }
+
+  /// Records the state of the container and the children being removed
+  /// from it in the history before they're modified.
+  fn record_change(&self, removed: &[CstNode]) {
+    let root = match self {
+      CstContainerNode::Root(node) => Some(node.clone()),
+      _ => self.root_node(),
+    };
+    if let Some(root) = root {
+      root.record_change(&self.clone().into(), removed);
+    }
+  }
+
   #[inline(always)]
   fn raw_append_child(&self, child: CstNode) {
     self.raw_insert_child(None, child);
@@ -773,6 +969,7 @@ impl CstContainerNode {
     if children.is_empty() {
       return;
     }
+    self.record_change(&[]);
 
     let weak_parent = WeakParent::from_container(self);
     let mut container = match self {
@@ -796,6 +993,23 @@ impl CstContainerNode {
     }
   }
 
+  fn raw_set_children(&self, children: Vec<CstNode>) {
+    let weak_parent = WeakParent::from_container(self);
+    for (i, child) in children.iter().enumerate() {
+      child.set_parent(Some(ParentInfo {
+        parent: weak_parent.clone(),
+        child_index: i,
+      }));
+    }
+    let mut container = match self {
+      CstContainerNode::Root(node) => node.0.borrow_mut(),
+      CstContainerNode::Object(node) => node.0.borrow_mut(),
+      CstContainerNode::ObjectProp(node) => node.0.borrow_mut(),
+      CstContainerNode::Array(node) => node.0.borrow_mut(),
+    };
+    container.value = children;
+  }
+
   fn raw_insert_value_with_internal_indent(
     &self,
     insert_index: Option<&mut usize>,
@@ -1008,6 +1222,77 @@ impl CstLeafNode {
     }
   }
 
+  fn ptr(&self) -> *const () {
+    match self {
+      CstLeafNode::BooleanLit(node) => node.ptr(),
+      CstLeafNode::NullKeyword(node) => node.ptr(),
+      CstLeafNode::NumberLit(node) => node.ptr(),
+      CstLeafNode::StringLit(node) => node.ptr(),
+      CstLeafNode::WordLit(node) => node.ptr(),
+      CstLeafNode::Token(node) => node.ptr(),
+      CstLeafNode::Whitespace(node) => node.ptr(),
+      CstLeafNode::Newline(node) => node.ptr(),
+      CstLeafNode::Comment(node) => node.ptr(),
+    }
+  }
+
+  fn original_range(&self) -> Option<Range> {
+    match self {
+      CstLeafNode::BooleanLit(node) => node.original_range(),
+      CstLeafNode::NullKeyword(node) => node.original_range(),
+      CstLeafNode::NumberLit(node) => node.original_range(),
+      CstLeafNode::StringLit(node) => node.original_range(),
+      CstLeafNode::WordLit(node) => node.original_range(),
+      CstLeafNode::Token(node) => node.original_range(),
+      CstLeafNode::Whitespace(node) => node.original_range(),
+      CstLeafNode::Newline(node) => node.original_range(),
+      CstLeafNode::Comment(node) => node.original_range(),
+    }
+  }
+
+  fn set_original_range(&self, range: Option<Range>) {
+    match self {
+      CstLeafNode::BooleanLit(node) => node.set_original_range(range),
+      CstLeafNode::NullKeyword(node) => node.set_original_range(range),
+      CstLeafNode::NumberLit(node) => node.set_original_range(range),
+      CstLeafNode::StringLit(node) => node.set_original_range(range),
+      CstLeafNode::WordLit(node) => node.set_original_range(range),
+      CstLeafNode::Token(node) => node.set_original_range(range),
+      CstLeafNode::Whitespace(node) => node.set_original_range(range),
+      CstLeafNode::Newline(node) => node.set_original_range(range),
+      CstLeafNode::Comment(node) => node.set_original_range(range),
+    }
+  }
+
+  fn detached_copy(&self) -> CstLeafNode {
+    match self {
+      CstLeafNode::BooleanLit(node) => CstLeafNode::BooleanLit(node.detached_copy()),
+      CstLeafNode::NullKeyword(node) => CstLeafNode::NullKeyword(node.detached_copy()),
+      CstLeafNode::NumberLit(node) => CstLeafNode::NumberLit(node.detached_copy()),
+      CstLeafNode::StringLit(node) => CstLeafNode::StringLit(node.detached_copy()),
+      CstLeafNode::WordLit(node) => CstLeafNode::WordLit(node.detached_copy()),
+      CstLeafNode::Token(node) => CstLeafNode::Token(node.detached_copy()),
+      CstLeafNode::Whitespace(node) => CstLeafNode::Whitespace(node.detached_copy()),
+      CstLeafNode::Newline(node) => CstLeafNode::Newline(node.detached_copy()),
+      CstLeafNode::Comment(node) => CstLeafNode::Comment(node.detached_copy()),
+    }
+  }
+
+  fn restore_from(&self, copy: &CstLeafNode) {
+    match (self, copy) {
+      (CstLeafNode::BooleanLit(node), CstLeafNode::BooleanLit(copy)) => node.restore_from(copy),
+      (CstLeafNode::NullKeyword(node), CstLeafNode::NullKeyword(copy)) => node.restore_from(copy),
+      (CstLeafNode::NumberLit(node), CstLeafNode::NumberLit(copy)) => node.restore_from(copy),
+      (CstLeafNode::StringLit(node), CstLeafNode::StringLit(copy)) => node.restore_from(copy),
+      (CstLeafNode::WordLit(node), CstLeafNode::WordLit(copy)) => node.restore_from(copy),
+      (CstLeafNode::Token(node), CstLeafNode::Token(copy)) => node.restore_from(copy),
+      (CstLeafNode::Whitespace(node), CstLeafNode::Whitespace(copy)) => node.restore_from(copy),
+      (CstLeafNode::Newline(node), CstLeafNode::Newline(copy)) => node.restore_from(copy),
+      (CstLeafNode::Comment(node), CstLeafNode::Comment(copy)) => node.restore_from(copy),
+      _ => unreachable!("programming error restoring a leaf from a different kind of leaf"),
+    }
+  }
+
   /// Converts a CST leaf node to a `serde_json::Value`.
   ///
   /// Returns `None` if the node is trivia or cannot be converted to a value.
@@ -1049,17 +1334,161 @@ impl From<CstLeafNode> for CstNode {
   }
 }
 
-/// Mode to use for trailing commas.
-#[derive(Default, Debug, Clone, Copy)]
-pub enum TrailingCommaMode {
-  /// Never use trailing commas.
-  #[default]
-  Never,
-  /// Use trailing commas when the object is on multiple lines.
-  IfMultiline,
+#[derive(Debug)]
+struct CstRootNodeInner {
+  node: RefCell<CstChildrenInner>,
+  history: RefCell<CstHistory>,
+}
+
+impl CstRootNodeInner {
+  fn new(node: CstChildrenInner) -> Self {
+    Self {
+      node: RefCell::new(node),
+      history: Default::default(),
+    }
+  }
+
+  fn borrow(&self) -> Ref<'_, CstChildrenInner> {
+    self.node.borrow()
+  }
+
+  fn borrow_mut(&self) -> RefMut<'_, CstChildrenInner> {
+    self.node.borrow_mut()
+  }
+}
+
+const NO_TRANSACTION_PANIC_MSG: &str = "Programming error. No transaction is open.";
+const OPEN_TRANSACTION_PANIC_MSG: &str = "Programming error. Commit or roll back the open transaction first.";
+
+#[derive(Debug, Default)]
+struct CstHistory {
+  /// States of the nodes modified since the start of each open (possibly
+  /// nested) transaction from before they were modified.
+  transactions: Vec<CstSnapshot>,
+  /// States of the nodes modified outside a transaction since the last
+  /// entry was committed, undone, or redone.
+  untracked: CstSnapshot,
+  undo_stack: Vec<CstHistoryEntry>,
+  redo_stack: Vec<CstHistoryEntry>,
+}
+
+impl CstHistory {
+  /// Snapshots to record a node in before it's modified.
+  fn recording_snapshots(&mut self) -> &mut [CstSnapshot] {
+    if !self.transactions.is_empty() {
+      &mut self.transactions
+    } else if !self.undo_stack.is_empty() || !self.redo_stack.is_empty() {
+      std::slice::from_mut(&mut self.untracked)
+    } else {
+      &mut []
+    }
+  }
+}
+
+#[derive(Debug)]
+struct CstHistoryEntry {
+  before: CstSnapshot,
+  after: CstSnapshot,
+}
+
+/// States of nodes at a point in time.
+///
+/// This keeps the nodes themselves rather than a copy of the text so that
+/// restoring it keeps any node references held by the caller attached.
+#[derive(Debug, Default)]
+struct CstSnapshot {
+  /// Addresses of the recorded nodes so each is only recorded once.
+  recorded: HashSet<*const ()>,
+  states: Vec<CstNodeState>,
+}
+
+#[derive(Debug, Clone)]
+enum CstNodeState {
+  Container(CstContainerNode, Vec<CstNode>),
+  /// The leaf along with a detached copy of it.
+  Leaf(CstLeafNode, CstLeafNode),
+}
+
+impl CstNodeState {
+  fn take(node: &CstNode) -> Self {
+    match node {
+      CstNode::Container(container) => CstNodeState::Container(container.clone(), container.children()),
+      CstNode::Leaf(leaf) => CstNodeState::Leaf(leaf.clone(), leaf.detached_copy()),
+    }
+  }
+
+  fn node(&self) -> CstNode {
+    match self {
+      CstNodeState::Container(container, _) => container.clone().into(),
+      CstNodeState::Leaf(leaf, _) => leaf.clone().into(),
+    }
+  }
 }
 
-type CstRootNodeInner = RefCell<CstChildrenInner>;
+impl CstSnapshot {
+  /// Takes a snapshot of every node in the tree.
+  fn take(root: &CstRootNode) -> Self {
+    let mut snapshot = CstSnapshot::default();
+    snapshot.add_subtree(&root.clone().into());
+    snapshot
+  }
+
+  /// Records the current state of the node unless it's already recorded.
+  fn add_node(&mut self, node: &CstNode) {
+    if self.recorded.insert(node.ptr()) {
+      self.states.push(CstNodeState::take(node));
+    }
+  }
+
+  /// Records the node along with its descendants, which is necessary when
+  /// it's removed from the tree because its descendants may then be
+  /// modified without the tree knowing.
+  fn add_subtree(&mut self, node: &CstNode) {
+    self.add_node(node);
+    if let CstNode::Container(container) = node {
+      for child in container.children() {
+        self.add_subtree(&child);
+      }
+    }
+  }
+
+  fn add_state(&mut self, state: CstNodeState) {
+    if self.recorded.insert(state.node().ptr()) {
+      self.states.push(state);
+    }
+  }
+
+  /// Takes the current states of the recorded nodes.
+  fn current_states(&self) -> Self {
+    CstSnapshot {
+      recorded: self.recorded.clone(),
+      states: self
+        .states
+        .iter()
+        .map(|state| CstNodeState::take(&state.node()))
+        .collect(),
+    }
+  }
+
+  fn restore(&self) {
+    // disconnect the current children first so nodes that moved between
+    // containers, or were created after the snapshot, don't keep pointing
+    // into the tree
+    for state in &self.states {
+      if let CstNodeState::Container(container, _) = state {
+        for child in container.children() {
+          child.set_parent(None);
+        }
+      }
+    }
+    for state in &self.states {
+      match state {
+        CstNodeState::Container(container, children) => container.raw_set_children(children.clone()),
+        CstNodeState::Leaf(leaf, copy) => leaf.restore_from(copy),
+      }
+    }
+  }
+}
 
 /// Root node in the file.
 ///
@@ -1114,13 +1543,13 @@ impl CstRootNode {
       parse_options,
     )?;
 
-    Ok(
-      CstBuilder {
-        text,
-        tokens: parse_result.tokens.unwrap().into_iter().collect(),
-      }
-      .build(parse_result.value),
-    )
+    let root = CstBuilder {
+      text,
+      tokens: parse_result.tokens.unwrap().into_iter().collect(),
+    }
+    .build(parse_result.value);
+    root.mark_original_ranges();
+    Ok(root)
   }
 
   /// Computes the single indentation text of the file.
@@ -1159,6 +1588,24 @@ impl CstRootNode {
     CstNewlineKind::LineFeed
   }
 
+  /// Gets the value at the JSON Pointer (RFC 6901) such as `/compilerOptions/paths/0`.
+  ///
+  /// Returns `None` when the pointer is malformed or doesn't refer to a value.
+  ///
+  /// ```
+  /// use jsonc_parser::cst::CstRootNode;
+  /// use jsonc_parser::ParseOptions;
+  /// use jsonc_parser::json;
+  ///
+  /// let root = CstRootNode::parse(r#"{ "paths": { "a/b": [1, 2] } }"#, &ParseOptions::default()).unwrap();
+  /// let node = root.pointer("/paths/a~1b/1").unwrap();
+  /// node.as_number_lit().unwrap().replace_with(json!(3));
+  /// assert_eq!(root.to_string(), r#"{ "paths": { "a/b": [1, 3] } }"#);
+  /// ```
+  pub fn pointer(&self, pointer: &str) -> Option<CstNode> {
+    CstNode::from(self.clone()).pointer(pointer)
+  }
+
   /// Gets the root value found in the file.
   pub fn value(&self) -> Option<CstNode> {
     for child in &self.0.borrow().value {
@@ -1299,6 +1746,7 @@ impl CstRootNode {
 
   /// Clears all the children from the root node making it empty.
   pub fn clear_children(&self) {
+    CstContainerNode::from(self.clone()).record_change(&self.children());
     let children = std::mem::take(&mut self.0.borrow_mut().value);
     for child in children {
       child.set_parent(None);
@@ -1312,6 +1760,324 @@ impl CstRootNode {
   pub fn to_serde_value(&self) -> Option<serde_json::Value> {
     self.value()?.to_serde_value()
   }
+
+  /// Starts a transaction.
+  ///
+  /// Changes made until the matching call to `commit` or `rollback` are
+  /// grouped together. Transactions may be nested, in which case only the
+  /// outermost one is added to the undo history.
+  ///
+  /// Only the nodes modified in a transaction are copied, so the memory
+  /// used by the undo history grows with the size of the changes rather
+  /// than the size of the file. Use `clear_history` to free it.
+  pub fn begin(&self) {
+    self.0.history.borrow_mut().transactions.push(Default::default());
+  }
+
+  /// Records the node's state, and the states of the nodes in the removed
+  /// subtrees, in the history before they're modified.
+  fn record_change(&self, node: &CstNode, removed: &[CstNode]) {
+    let mut history = self.0.history.borrow_mut();
+    for snapshot in history.recording_snapshots() {
+      snapshot.add_node(node);
+      for node in removed {
+        snapshot.add_subtree(node);
+      }
+    }
+  }
+
+  /// Commits the current transaction.
+  ///
+  /// Panics if there is no open transaction.
+  pub fn commit(&self) {
+    let mut history = self.0.history.borrow_mut();
+    let mut before = history.transactions.pop().expect(NO_TRANSACTION_PANIC_MSG);
+    if history.transactions.is_empty() {
+      // undoing the previous entry reverts the changes made outside a transaction
+      // since then, so include those nodes in both entries to keep them consistent
+      let untracked = std::mem::take(&mut history.untracked);
+      if let Some(previous) = history.undo_stack.last_mut() {
+        for state in untracked.states {
+          before.add_node(&state.node());
+          previous.after.add_state(state.clone());
+          previous.before.add_state(state);
+        }
+      }
+      history.undo_stack.push(CstHistoryEntry {
+        after: before.current_states(),
+        before,
+      });
+      history.redo_stack.clear();
+    }
+  }
+
+  /// Reverts the changes made in the current transaction, restoring
+  /// the tree including its comments and whitespace.
+  ///
+  /// Panics if there is no open transaction.
+  pub fn rollback(&self) {
+    let before = self
+      .0
+      .history
+      .borrow_mut()
+      .transactions
+      .pop()
+      .expect(NO_TRANSACTION_PANIC_MSG);
+    before.restore();
+  }
+
+  /// If a transaction is currently open.
+  pub fn in_transaction(&self) -> bool {
+    !self.0.history.borrow().transactions.is_empty()
+  }
+
+  /// Runs the provided closure in a transaction, committing it when
+  /// the closure returns `Ok` and rolling it back when it returns `Err`
+  /// or panics.
+  ///
+  /// ```
+  /// use jsonc_parser::cst::CstRootNode;
+  /// use jsonc_parser::ParseOptions;
+  /// use jsonc_parser::json;
+  ///
+  /// let text = "{\n  // comment\n  \"a\": 1\n}";
+  /// let root = CstRootNode::parse(text, &ParseOptions::default()).unwrap();
+  /// let result = root.transaction(|root| {
+  ///   let root_obj = root.object_value().unwrap();
+  ///   root_obj.get("a").unwrap().remove();
+  ///   root_obj.append("b", json!(2));
+  ///   Err("invalid")
+  /// });
+  /// assert_eq!(result, Err::<(), _>("invalid"));
+  /// assert_eq!(root.to_string(), text);
+  /// ```
+  pub fn transaction<T, E>(&self, f: impl FnOnce(&CstRootNode) -> Result<T, E>) -> Result<T, E> {
+    /// Rolls back the transaction, and any left open inside it, when dropped
+    /// due to the closure panicking.
+    struct RollbackGuard<'a> {
+      root: &'a CstRootNode,
+      depth: usize,
+    }
+
+    impl Drop for RollbackGuard<'_> {
+      fn drop(&mut self) {
+        while self.root.0.history.borrow().transactions.len() > self.depth {
+          self.root.rollback();
+        }
+      }
+    }
+
+    let guard = RollbackGuard {
+      root: self,
+      depth: self.0.history.borrow().transactions.len(),
+    };
+    self.begin();
+    let result = f(self);
+    std::mem::forget(guard);
+    if result.is_ok() {
+      self.commit();
+    } else {
+      self.rollback();
+    }
+    result
+  }
+
+  /// If there is a committed transaction that can be undone.
+  pub fn can_undo(&self) -> bool {
+    !self.0.history.borrow().undo_stack.is_empty()
+  }
+
+  /// If there is an undone transaction that can be redone.
+  pub fn can_redo(&self) -> bool {
+    !self.0.history.borrow().redo_stack.is_empty()
+  }
+
+  /// Clears the undo and redo history, freeing the copies of the tree it keeps.
+  pub fn clear_history(&self) {
+    let mut history = self.0.history.borrow_mut();
+    history.undo_stack.clear();
+    history.redo_stack.clear();
+    history.untracked = Default::default();
+  }
+
+  /// Restores the tree to how it was before the last committed transaction.
+  ///
+  /// Note that this also reverts any changes made outside of a transaction
+  /// since then. Returns `false` if there was nothing to undo.
+  ///
+  /// Panics if a transaction is open.
+  pub fn undo(&self) -> bool {
+    let mut history = self.0.history.borrow_mut();
+    assert!(history.transactions.is_empty(), "{}", OPEN_TRANSACTION_PANIC_MSG);
+    let Some(entry) = history.undo_stack.pop() else {
+      return false;
+    };
+    std::mem::take(&mut history.untracked).restore();
+    entry.before.restore();
+    history.redo_stack.push(entry);
+    true
+  }
+
+  /// Restores the tree to how it was after the last undone transaction.
+  ///
+  /// Returns `false` if there was nothing to redo.
+  ///
+  /// Panics if a transaction is open.
+  pub fn redo(&self) -> bool {
+    let mut history = self.0.history.borrow_mut();
+    assert!(history.transactions.is_empty(), "{}", OPEN_TRANSACTION_PANIC_MSG);
+    let Some(entry) = history.redo_stack.pop() else {
+      return false;
+    };
+    std::mem::take(&mut history.untracked).restore();
+    entry.after.restore();
+    history.undo_stack.push(entry);
+    true
+  }
+
+  /// Takes the changes made to the CST as text edits.
+  ///
+  /// The edits are relative to the text the CST was parsed from or, if edits
+  /// were already taken, the text at the time of the last call. They are
+  /// sorted and non-overlapping, so they can be applied from last to first
+  /// or sent to a client as a single batch of edits.
+  ///
+  /// ```
+  /// use jsonc_parser::cst::CstRootNode;
+  /// use jsonc_parser::ParseOptions;
+  /// use jsonc_parser::json;
+  ///
+  /// let text = r#"{ "a": 1, "b": 2 }"#;
+  /// let root = CstRootNode::parse(text, &ParseOptions::default()).unwrap();
+  /// let root_obj = root.object_value().unwrap();
+  /// root_obj.get("b").unwrap().set_value(json!(true));
+  ///
+  /// let edits = root.take_text_edits();
+  /// assert_eq!(edits.len(), 1);
+  /// assert_eq!(&text[edits[0].range.start..edits[0].range.end], "2");
+  /// assert_eq!(edits[0].new_text, "true");
+  /// assert!(root.take_text_edits().is_empty());
+  /// ```
+  pub fn take_text_edits(&self) -> Vec<TextEdit> {
+    let original_len = self.0.borrow().original_range.map(|r| r.end).unwrap_or(0);
+    let mut edits = Vec::new();
+    let mut last_end = 0;
+    let mut new_text = String::new();
+    for leaf in descendant_leaves(self.clone().into()) {
+      match leaf.original_range() {
+        // an unchanged leaf that hasn't moved before the previous one
+        Some(range) if range.start >= last_end => {
+          if range.start > last_end || !new_text.is_empty() {
+            edits.push(TextEdit {
+              range: Range::new(last_end, range.start),
+              new_text: std::mem::take(&mut new_text),
+            });
+          }
+          last_end = range.end;
+        }
+        _ => new_text.push_str(&leaf.to_string()),
+      }
+    }
+    if original_len > last_end || !new_text.is_empty() {
+      edits.push(TextEdit {
+        range: Range::new(last_end, original_len),
+        new_text,
+      });
+    }
+
+    self.mark_original_ranges();
+    edits
+  }
+
+  fn mark_original_ranges(&self) {
+    // leaves in the history that are no longer in the tree would otherwise
+    // keep a range from the previous text once they're restored
+    {
+      let history = self.0.history.borrow();
+      let snapshots = history
+        .transactions
+        .iter()
+        .chain(std::iter::once(&history.untracked))
+        .chain(
+          history
+            .undo_stack
+            .iter()
+            .chain(history.redo_stack.iter())
+            .flat_map(|entry| [&entry.before, &entry.after]),
+        );
+      for snapshot in snapshots {
+        for state in &snapshot.states {
+          let nodes = match state {
+            CstNodeState::Container(_, children) => children.clone(),
+            CstNodeState::Leaf(leaf, _) => vec![leaf.clone().into()],
+          };
+          for node in nodes {
+            if node.root_node().is_none() {
+              for leaf in descendant_leaves(node) {
+                leaf.set_original_range(None);
+              }
+            }
+          }
+        }
+      }
+    }
+
+    let mut offset = 0;
+    for leaf in descendant_leaves(self.clone().into()) {
+      let end = offset + text_len(&leaf.clone().into());
+      leaf.set_original_range(Some(Range::new(offset, end)));
+      offset = end;
+    }
+    self.0.borrow_mut().original_range = Some(Range::new(0, offset));
+  }
+
+  /// Gets the deepest node at the provided byte offset in the current text.
+  ///
+  /// When the offset is between two nodes, the node that starts at the
+  /// offset is preferred over the one that ends at it.
+  pub fn node_at_offset(&self, offset: usize) -> Option<CstNode> {
+    self.nodes_in_range(Range::new(offset, offset)).into_iter().next()
+  }
+
+  /// Gets the deepest nodes that overlap the provided byte range in the
+  /// current text.
+  ///
+  /// This descends into the deepest node that contains the whole range. If
+  /// that is a leaf, only the leaf is returned. Otherwise, the children of
+  /// that container which overlap the range are returned.
+  pub fn nodes_in_range(&self, range: Range) -> Vec<CstNode> {
+    let mut container: CstContainerNode = self.clone().into();
+    let mut container_start = 0;
+    loop {
+      let mut start = container_start;
+      let mut found = Vec::new();
+      for child in container.children() {
+        let end = start + text_len(&child);
+        let overlaps = if range.start == range.end {
+          start <= range.start && range.start < end
+        } else {
+          start < range.end && range.start < end
+        };
+        if overlaps {
+          found.push((start, end, child));
+        }
+        start = end;
+      }
+      let contains_all = found.len() == 1 && found[0].0 <= range.start && range.end <= found[0].1;
+      if contains_all {
+        let (start, _, child) = found.remove(0);
+        match child {
+          CstNode::Container(child) => {
+            container = child;
+            container_start = start;
+            continue;
+          }
+          CstNode::Leaf(_) => return vec![child],
+        }
+      }
+      return found.into_iter().map(|(_, _, child)| child).collect();
+    }
+  }
 }
 
 impl Display for CstRootNode {
@@ -1323,6 +2089,32 @@ impl Display for CstRootNode {
   }
 }
 
+/// Escapes the value as a string literal surrounded by the provided quote.
+fn escape_string(value: &str, quote: char) -> String {
+  let mut escaped = String::with_capacity(value.len() + 2);
+  escaped.push(quote);
+  for ch in value.chars() {
+    match ch {
+      c if c == quote => {
+        escaped.push('\\');
+        escaped.push(c);
+      }
+      '\\' => escaped.push_str("\\\\"),
+      '\u{08}' => escaped.push_str("\\b"),
+      '\u{0c}' => escaped.push_str("\\f"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      '\t' => escaped.push_str("\\t"),
+      c if c.is_control() => {
+        escaped.push_str(&format!("\\u{:04x}", c as u32));
+      }
+      c => escaped.push(c),
+    }
+  }
+  escaped.push(quote);
+  escaped
+}
+
 /// Text surrounded in double quotes (ex. `"my string"`).
 #[derive(Debug, Clone)]
 pub struct CstStringLit(Rc<RefCell<CstValueInner<String>>>);
@@ -1335,30 +2127,13 @@ impl CstStringLit {
   }
 
   fn new_escaped(value: &str) -> Self {
-    let mut escaped = String::with_capacity(value.len() + 2);
-    escaped.push('"');
-    for ch in value.chars() {
-      match ch {
-        '"' => escaped.push_str("\\\""),
-        '\\' => escaped.push_str("\\\\"),
-        '\u{08}' => escaped.push_str("\\b"),
-        '\u{0c}' => escaped.push_str("\\f"),
-        '\n' => escaped.push_str("\\n"),
-        '\r' => escaped.push_str("\\r"),
-        '\t' => escaped.push_str("\\t"),
-        c if c.is_control() => {
-          escaped.push_str(&format!("\\u{:04x}", c as u32));
-        }
-        c => escaped.push(c),
-      }
-    }
-    escaped.push('"');
-    Self::new(escaped)
+    Self::new(escape_string(value, '"'))
   }
 
   /// Sets the raw value of the string INCLUDING SURROUNDING QUOTES.
   pub fn set_raw_value(&self, value: String) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Gets the raw unescaped value including quotes.
@@ -1410,7 +2185,8 @@ impl CstWordLit {
 
   /// Sets the raw value of the word literal.
   pub fn set_raw_value(&self, value: String) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Replaces this node with a new value.
@@ -1434,6 +2210,7 @@ impl Display for CstWordLit {
 pub struct CstNumberLit(Rc<RefCell<CstValueInner<String>>>);
 
 impl_leaf_methods!(CstNumberLit, NumberLit);
+impl_number_conversions!(CstNumberLit, |self| &self.0.borrow().value);
 
 impl CstNumberLit {
   fn new(value: String) -> Self {
@@ -1442,7 +2219,8 @@ impl CstNumberLit {
 
   /// Sets the raw string value of the number literal.
   pub fn set_raw_value(&self, value: String) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Replaces this node with a new value.
@@ -1482,8 +2260,16 @@ impl CstNumberLit {
       let num_for_parsing = raw.trim_start_matches('+');
       match serde_json::Number::from_str(num_for_parsing) {
         Ok(number) => Some(serde_json::Value::Number(number)),
-        // if the number is invalid, return it as a string (same behavior as AST conversion)
-        Err(_) => Some(serde_json::Value::String(raw)),
+        // numbers like .5 and 5. are rejected by serde_json, so fall back to an f64
+        Err(_) => match num_for_parsing
+          .parse::<f64>()
+          .ok()
+          .and_then(serde_json::Number::from_f64)
+        {
+          Some(number) => Some(serde_json::Value::Number(number)),
+          // if the number is invalid, return it as a string (same behavior as AST conversion)
+          None => Some(serde_json::Value::String(raw)),
+        },
       }
     }
   }
@@ -1513,7 +2299,8 @@ impl CstBooleanLit {
 
   /// Sets the value of the boolean literal.
   pub fn set_value(&self, value: bool) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Replaces this node with a new value.
@@ -1798,6 +2585,175 @@ impl CstObject {
     ensure_multiline(&self.clone().into());
   }
 
+  /// Sorts the properties of the object using the provided comparison.
+  ///
+  /// Each property moves along with the comments on the lines before it, the
+  /// comments after the previous property's comma on the same line, and the
+  /// comments after it on the same line. The whitespace and commas around
+  /// them stay in place, so a property takes on the layout of the position it
+  /// moves to and a trailing comma stays at the end.
+  pub fn sort_properties_by(&self, mut compare: impl FnMut(&CstObjectProp, &CstObjectProp) -> Ordering) {
+    /// A property with its comments, which moves when sorting.
+    struct PropSegment {
+      prop: CstObjectProp,
+      nodes: Vec<CstNode>,
+    }
+
+    /// The whitespace and commas around a property, which stay in place.
+    #[derive(Default)]
+    struct PropSlot {
+      /// Nodes before the property's leading comments.
+      leading: Vec<CstNode>,
+      /// Comma directly before the property along with the whitespace after it.
+      before_prop: Vec<CstNode>,
+      /// Comma directly after the property along with the whitespace before it.
+      after_prop: Vec<CstNode>,
+    }
+
+    let children = self.children();
+    let (Some(open_index), Some(close_index)) = (
+      children.iter().position(|c| c.token_char() == Some('{')),
+      children.iter().rposition(|c| c.token_char() == Some('}')),
+    ) else {
+      return;
+    };
+    let inner = &children[open_index + 1..close_index];
+    let prop_indexes = inner
+      .iter()
+      .enumerate()
+      .filter(|(_, c)| c.as_object_prop().is_some())
+      .map(|(i, _)| i)
+      .collect::<Vec<_>>();
+    if prop_indexes.len() < 2 {
+      return;
+    }
+
+    // anything on the same line as the open brace stays with it
+    let mut segment_start = if prop_indexes[0] > 0 {
+      inner[..prop_indexes[0]]
+        .iter()
+        .position(|c| c.is_newline())
+        .unwrap_or(0)
+    } else {
+      0
+    };
+    let head = &inner[..segment_start];
+    let mut segments = Vec::with_capacity(prop_indexes.len());
+    let mut slots = Vec::with_capacity(prop_indexes.len());
+    for (i, &prop_index) in prop_indexes.iter().enumerate() {
+      let next_prop_index = prop_indexes.get(i + 1).copied().unwrap_or(inner.len());
+      let mut segment_end = prop_index + 1;
+      let mut comma_end = None;
+      let mut is_line_end = false;
+      for (index, node) in inner.iter().enumerate().take(next_prop_index).skip(prop_index + 1) {
+        if node.is_newline() {
+          is_line_end = true;
+          break;
+        } else if !node.is_whitespace() {
+          segment_end = index + 1;
+          if node.is_comma() {
+            comma_end = Some(segment_end);
+          }
+        }
+      }
+      // comments after the comma belong to the next property when it's on the same line
+      if !is_line_end
+        && next_prop_index < inner.len()
+        && let Some(comma_end) = comma_end
+      {
+        segment_end = comma_end;
+      }
+
+      let content_start = (segment_start..prop_index)
+        .find(|&index| inner[index].is_comment())
+        .unwrap_or(prop_index);
+      let mut slot = PropSlot {
+        leading: inner[segment_start..content_start].to_vec(),
+        ..Default::default()
+      };
+      let mut nodes = Vec::new();
+      let mut is_after_comma = false;
+      for (index, node) in inner.iter().enumerate().take(segment_end).skip(content_start) {
+        if node.is_comma() && index < prop_index {
+          slot.before_prop.push(node.clone());
+          is_after_comma = true;
+        } else if node.is_comma() {
+          let whitespace_start = nodes
+            .iter()
+            .rposition(|c: &CstNode| !c.is_whitespace())
+            .map_or(0, |i| i + 1);
+          slot.after_prop.extend(nodes.drain(whitespace_start..));
+          slot.after_prop.push(node.clone());
+        } else if is_after_comma && node.is_whitespace() {
+          slot.before_prop.push(node.clone());
+        } else {
+          is_after_comma = false;
+          nodes.push(node.clone());
+        }
+      }
+      segments.push(PropSegment {
+        prop: inner[prop_index].as_object_prop().unwrap(),
+        nodes,
+      });
+      slots.push(slot);
+      segment_start = segment_end;
+    }
+    let tail = &inner[segment_start..];
+
+    segments.sort_by(|a, b| compare(&a.prop, &b.prop));
+
+    let mut new_children = children[..=open_index].to_vec();
+    new_children.extend(head.iter().cloned());
+    for (mut slot, segment) in slots.into_iter().zip(segments) {
+      new_children.append(&mut slot.leading);
+      for node in segment.nodes {
+        if node.as_object_prop().is_some() {
+          new_children.append(&mut slot.before_prop);
+          new_children.push(node);
+          new_children.append(&mut slot.after_prop);
+        } else {
+          new_children.push(node);
+        }
+      }
+    }
+    new_children.extend(tail.iter().cloned());
+    new_children.extend(children[close_index..].iter().cloned());
+    let container = CstContainerNode::from(self.clone());
+    container.record_change(&[]);
+    container.raw_set_children(new_children);
+  }
+
+  /// Sorts the properties of the object alphabetically by name, optionally
+  /// also sorting the objects nested within the property values.
+  ///
+  /// See `sort_properties_by` for how comments and commas are handled.
+  pub fn sort_properties_alphabetically(&self, recursive: bool) {
+    fn decoded_name(prop: &CstObjectProp) -> Option<String> {
+      prop.name().and_then(|name| name.decoded_value().ok())
+    }
+
+    fn sort_descendants(node: CstNode) {
+      match node {
+        CstNode::Container(CstContainerNode::Object(object)) => object.sort_properties_alphabetically(true),
+        CstNode::Container(CstContainerNode::Array(array)) => {
+          for element in array.elements() {
+            sort_descendants(element);
+          }
+        }
+        _ => {}
+      }
+    }
+
+    self.sort_properties_by(|a, b| decoded_name(a).cmp(&decoded_name(b)));
+    if recursive {
+      for prop in self.properties() {
+        if let Some(value) = prop.value() {
+          sort_descendants(value);
+        }
+      }
+    }
+  }
+
   /// Removes the node from the JSON.
   pub fn remove(self) {
     remove_comma_separated(self.into())
@@ -1856,6 +2812,34 @@ impl CstObjectProp {
     None
   }
 
+  /// Sets the name of the object property, keeping the existing quote style.
+  ///
+  /// A name without quotes stays that way when the new name is still a valid
+  /// word and otherwise falls back to being double quoted. Comments and the
+  /// value are left as-is.
+  pub fn set_name(&self, name: &str) {
+    match self.name() {
+      Some(ObjectPropName::String(lit)) => {
+        let quote = match lit.raw_value().chars().next() {
+          Some('\'') => '\'',
+          _ => '"',
+        };
+        lit.set_raw_value(escape_string(name, quote));
+      }
+      Some(ObjectPropName::Word(word)) if is_valid_word_name(name) => {
+        word.set_raw_value(name.to_string());
+      }
+      Some(ObjectPropName::Word(word)) => {
+        let mut index = word.child_index();
+        self.remove_child_set_no_parent(index);
+        CstContainerNode::from(self.clone()).raw_insert_child(Some(&mut index), CstStringLit::new_escaped(name).into());
+      }
+      None => {
+        // someone may have manipulated this property such that there's no longer a name
+      }
+    }
+  }
+
   pub fn property_index(&self) -> usize {
     let child_index = self.child_index();
     let Some(parent) = self.parent().and_then(|p| p.as_object()) else {
@@ -2004,7 +2988,7 @@ impl CstObjectProp {
   fn name_decoded(&self) -> Option<String> {
     match self.name()? {
       ObjectPropName::String(s) => s.decoded_value().ok(),
-      ObjectPropName::Word(w) => Some(w.0.borrow().value.clone()),
+      ObjectPropName::Word(w) => Some(crate::string::decode_word(&w.0.borrow().value).into_owned()),
     }
   }
 }
@@ -2018,6 +3002,17 @@ impl Display for CstObjectProp {
   }
 }
 
+/// Gets if the name can be used as an object property name without quotes.
+fn is_valid_word_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  let Some(first_char) = chars.next() else {
+    return false;
+  };
+  (first_char.is_alphabetic() || first_char == '_' || first_char == '$')
+    && chars.all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '$')
+    && !matches!(name, "true" | "false" | "null" | "Infinity" | "NaN")
+}
+
 /// An object property name that may or may not be in quotes (ex. `"prop"` in `"prop": 5`).
 #[derive(Debug, Clone)]
 pub enum ObjectPropName {
@@ -2049,7 +3044,7 @@ impl ObjectPropName {
   pub fn decoded_value(&self) -> Result<String, ParseStringErrorKind> {
     match self {
       ObjectPropName::String(n) => n.decoded_value(),
-      ObjectPropName::Word(n) => Ok(n.0.borrow().value.clone()),
+      ObjectPropName::Word(n) => Ok(crate::string::decode_word(&n.0.borrow().value).into_owned()),
     }
   }
 
@@ -2193,7 +3188,8 @@ impl CstToken {
 
   /// Sets the char value of the token.
   pub fn set_value(&self, value: char) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Char value of the token.
@@ -2226,7 +3222,8 @@ impl CstWhitespace {
 
   /// Sets the whitespace value.
   pub fn set_value(&self, value: String) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Whitespace value of the node.
@@ -2246,14 +3243,6 @@ impl Display for CstWhitespace {
   }
 }
 
-/// Kind of newline.
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CstNewlineKind {
-  #[default]
-  LineFeed,
-  CarriageReturnLineFeed,
-}
-
 /// Newline character (Lf or crlf).
 #[derive(Debug, Clone)]
 pub struct CstNewline(Rc<RefCell<CstValueInner<CstNewlineKind>>>);
@@ -2272,7 +3261,8 @@ impl CstNewline {
 
   /// Sets the newline kind.
   pub fn set_kind(&self, kind: CstNewlineKind) {
-    self.0.borrow_mut().value = kind;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(kind);
   }
 
   /// Removes the node from the JSON.
@@ -2311,7 +3301,8 @@ impl CstComment {
   /// This SHOULD include `//` or be surrounded in `/* ... */` or
   /// else you'll be inserting a syntax error.
   pub fn set_raw_value(&self, value: String) {
-    self.0.borrow_mut().value = value;
+    CstNode::from(self.clone()).record_change();
+    self.0.borrow_mut().set_value(value);
   }
 
   /// Raw value of the comment including `//` or `/* ... */`.
@@ -2351,8 +3342,9 @@ struct CstBuilder<'a> {
 
 impl<'a> CstBuilder<'a> {
   pub fn build(&mut self, ast_value: Option<crate::ast::Value<'a>>) -> CstRootNode {
-    let root_node = CstContainerNode::Root(CstRootNode(Rc::new(RefCell::new(CstChildrenInner {
+    let root_node = CstContainerNode::Root(CstRootNode(Rc::new(CstRootNodeInner::new(CstChildrenInner {
       parent: None,
+      original_range: None,
       value: Vec::new(),
     }))));
 
@@ -2434,6 +3426,9 @@ impl<'a> CstBuilder<'a> {
         container.raw_append_child(array.into())
       }
       ast::Value::NullKeyword(_) => container.raw_append_child(CstNullKeyword::new().into()),
+      // CstRootNode::parse uses parse_to_ast, which returns an error rather than
+      // a missing value, so a recovered AST never reaches the builder
+      ast::Value::MissingValue(_) => unreachable!("cst built from a recovered ast"),
     }
   }
 
@@ -2461,7 +3456,9 @@ impl<'a> CstBuilder<'a> {
         self.build_string_lit(&container, string_lit);
       }
       ast::ObjectPropName::Word(word_lit) => {
-        container.raw_append_child(CstWordLit::new(word_lit.value.to_string()).into());
+        // keep the text as written rather than the decoded value
+        let text = &self.text[word_lit.range.start..word_lit.range.end];
+        container.raw_append_child(CstWordLit::new(text.to_string()).into());
       }
     }
 
@@ -3152,6 +4149,7 @@ mod test {
   use crate::cst::TrailingCommaMode;
   use crate::json;
 
+  use super::CstNode;
   use super::CstRootNode;
 
   #[test]
@@ -4062,6 +5060,68 @@ value3: true
     }
   }
 
+  #[test]
+  fn pointer() {
+    let cst = build_cst(r#"{ "a": [1, { "b~c": "d" }], "e/f": true }"#);
+    assert_eq!(
+      cst.pointer("").unwrap().to_string(),
+      r#"{ "a": [1, { "b~c": "d" }], "e/f": true }"#
+    );
+    assert_eq!(cst.pointer("/a/0").unwrap().to_string(), "1");
+    assert_eq!(cst.pointer("/a/1/b~0c").unwrap().to_string(), r#""d""#);
+    assert_eq!(cst.pointer("/e~1f").unwrap().to_string(), "true");
+    assert!(cst.pointer("/a/2").is_none());
+    assert!(cst.pointer("/a/-").is_none());
+    assert!(cst.pointer("/e~1f/g").is_none());
+
+    // relative to the node
+    let array = cst.pointer("/a").unwrap();
+    assert_eq!(array.pointer("/1/b~0c").unwrap().to_string(), r#""d""#);
+    let prop = cst.object_value().unwrap().get("a").unwrap();
+    assert_eq!(CstNode::from(prop).pointer("/0").unwrap().to_string(), "1");
+
+    // a duplicate key resolves to its last occurrence
+    let cst = build_cst(r#"{ "a": { "b": 1 }, "a": { "b": 2, "b": 3 } }"#);
+    assert_eq!(cst.pointer("/a/b").unwrap().to_string(), "3");
+  }
+
+  #[test]
+  fn parse_duplicate_keys() {
+    let text = r#"{ "a": 1, "a": 2 }"#;
+    let cst = build_cst(text);
+    assert_eq!(cst.object_value().unwrap().properties().len(), 2);
+    let options = crate::ParseOptions {
+      duplicate_key_policy: crate::DuplicateKeyPolicy::Error,
+      ..Default::default()
+    };
+    let err = CstRootNode::parse(text, &options).err().unwrap();
+    assert_eq!(err.to_string(), "Duplicate object key 'a' on line 1 column 11");
+  }
+
+  #[test]
+  fn parse_json5() {
+    let text = "{\n  unquoted: 'a\\\n b\\x21',\n  num: .5,\n  inf: -Infinity,\n}";
+    let cst = CstRootNode::parse(text, &crate::ParseOptions::json5()).unwrap();
+    assert_eq!(cst.to_string(), text);
+    let obj = cst.object_value().unwrap();
+    let string_lit = obj.get("unquoted").unwrap().value().unwrap().as_string_lit().unwrap();
+    assert_eq!(string_lit.decoded_value().unwrap(), "a b!");
+    let number_lit = obj.get("num").unwrap().value().unwrap().as_number_lit().unwrap();
+    assert_eq!(number_lit.to_string(), ".5");
+
+    let text = "{\u{FEFF}null: 1, a\\u0062\u{200C}: 2 }";
+    let cst = CstRootNode::parse(text, &crate::ParseOptions::json5()).unwrap();
+    assert_eq!(cst.to_string(), text);
+    let obj = cst.object_value().unwrap();
+    let prop = obj.get("ab\u{200C}").unwrap();
+    assert_eq!(prop.name().unwrap().decoded_value().unwrap(), "ab\u{200C}");
+    assert_eq!(
+      prop.name().unwrap().as_word_lit().unwrap().to_string(),
+      "a\\u0062\u{200C}"
+    );
+    assert_eq!(obj.get("null").unwrap().value().unwrap().to_string(), "1");
+  }
+
   #[track_caller]
   fn build_cst(text: &str) -> CstRootNode {
     CstRootNode::parse(text, &crate::ParseOptions::default()).unwrap()
@@ -4096,6 +5156,18 @@ value3: true
       assert_eq!(value, SerdeValue::Null);
     }
 
+    #[test]
+    fn test_cst_to_serde_value_json5_numbers() {
+      let root = super::CstRootNode::parse("[.5, 5., Infinity]", &crate::ParseOptions::json5()).unwrap();
+      let value = root.to_serde_value().unwrap();
+      let expected = SerdeValue::Array(vec![
+        SerdeValue::Number(serde_json::Number::from_f64(0.5).unwrap()),
+        SerdeValue::Number(serde_json::Number::from_f64(5.0).unwrap()),
+        SerdeValue::String("Infinity".to_string()),
+      ]);
+      assert_eq!(value, expected);
+    }
+
     #[test]
     fn test_cst_to_serde_value_array() {
       let root = build_cst(r#"[1, 2, 3]"#);
@@ -4406,4 +5478,432 @@ value3: true
       .unwrap();
     assert_eq!(decoded, "key\\with\\backslash");
   }
+
+  #[test]
+  fn node_ranges() {
+    let text = "{\n  // comment\n  \"a\": [1, 2],\n  \"b\": \"text\"\n}";
+    let cst = build_cst(text);
+
+    fn assert_ranges(node: CstNode, text: &str) {
+      assert_eq!(&text[node.range().start..node.range().end], node.to_string());
+      if let CstNode::Container(container) = node {
+        for child in container.children() {
+          assert!(child.parent().unwrap().range().start <= child.range().start);
+          assert_ranges(child, text);
+        }
+      }
+    }
+    assert_ranges(CstNode::from(cst.clone()), text);
+
+    let root_obj = cst.object_value().unwrap();
+    let prop_b = root_obj.get("b").unwrap();
+    let start = text.find("\"b\"").unwrap();
+    assert_eq!(prop_b.range(), crate::common::Range::new(start, start + 11));
+
+    // ranges are recomputed after edits
+    root_obj.get("a").unwrap().set_value(json!([1, 2, 3, 4]));
+    let text = cst.to_string();
+    let start = text.find("\"b\"").unwrap();
+    assert_eq!(prop_b.range(), crate::common::Range::new(start, start + 11));
+    assert_ranges(CstNode::from(cst.clone()), &text);
+  }
+
+  #[test]
+  fn node_at_offset() {
+    let text = "{\n  \"a\": [1, 23],\n  \"b\": true\n}";
+    let cst = build_cst(text);
+
+    let node = cst.node_at_offset(text.find("23").unwrap() + 1).unwrap();
+    assert_eq!(node.as_number_lit().unwrap().to_string(), "23");
+    let node = cst.node_at_offset(0).unwrap();
+    assert_eq!(node.to_string(), "{");
+    let node = cst.node_at_offset(text.find("true").unwrap()).unwrap();
+    assert_eq!(node.to_string(), "true");
+    assert!(cst.node_at_offset(text.len()).is_none());
+
+    // a range spanning several array elements gets the elements
+    let start = text.find('1').unwrap();
+    let nodes = cst.nodes_in_range(crate::common::Range::new(start, start + 5));
+    assert_eq!(
+      nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+      vec!["1", ",", " ", "23"]
+    );
+
+    // a range within a single leaf gets only that leaf
+    let start = text.find("true").unwrap();
+    let nodes = cst.nodes_in_range(crate::common::Range::new(start + 1, start + 3));
+    assert_eq!(nodes.iter().map(|n| n.to_string()).collect::<Vec<_>>(), vec!["true"]);
+  }
+
+  #[test]
+  fn take_text_edits() {
+    fn apply_edits(text: &str, edits: &[super::TextEdit]) -> String {
+      let mut text = text.to_string();
+      for edit in edits.iter().rev() {
+        text.replace_range(edit.range.start..edit.range.end, &edit.new_text);
+      }
+      text
+    }
+
+    let text = "{\n  // comment\n  \"a\": 1,\n  \"b\": [1, 2],\n  \"c\": true\n}\n";
+    let cst = build_cst(text);
+    assert_eq!(cst.take_text_edits(), vec![]);
+
+    let root_obj = cst.object_value().unwrap();
+    root_obj.get("a").unwrap().set_value(json!(5));
+    root_obj.get("c").unwrap().remove();
+    let edits = cst.take_text_edits();
+    assert_eq!(
+      edits
+        .iter()
+        .map(|e| (&text[e.range.start..e.range.end], e.new_text.as_str()))
+        .collect::<Vec<_>>(),
+      vec![("1", "5"), (",", ""), ("  \"c\": true\n", "")]
+    );
+    let text = apply_edits(text, &edits);
+    assert_eq!(text, cst.to_string());
+
+    // edits are relative to the text as of the last call
+    let array = root_obj.get("b").unwrap().value().unwrap().as_array().unwrap();
+    array.append(json!(3));
+    root_obj.insert(0, "z", json!({ "nested": null }));
+    let edits = cst.take_text_edits();
+    assert_eq!(edits.len(), 2);
+    let text = apply_edits(&text, &edits);
+    assert_eq!(text, cst.to_string());
+
+    cst.clear_children();
+    let edits = cst.take_text_edits();
+    assert_eq!(apply_edits(&text, &edits), "");
+  }
+
+  #[test]
+  fn transactions() {
+    let text = "{\n  // comment\n  \"a\": 1, /* a */\n  \"b\": [1, 2]\n}\n";
+    let cst = build_cst(text);
+    let root_obj = cst.object_value().unwrap();
+    let prop_a = root_obj.get("a").unwrap();
+
+    cst.begin();
+    prop_a.clone().remove();
+    root_obj.get("b").unwrap().set_value(json!({ "c": true }));
+    assert!(cst.in_transaction());
+    cst.rollback();
+    assert!(!cst.in_transaction());
+    assert_eq!(cst.to_string(), text);
+    assert!(!cst.can_undo());
+    // references to removed nodes are attached again
+    assert!(prop_a.parent().unwrap().is_object());
+    prop_a.set_value(json!(2));
+    let text = cst.to_string();
+
+    let result = cst.transaction(|cst| {
+      let root_obj = cst.object_value().unwrap();
+      root_obj.append("c", json!(3));
+      // nested transactions are only added to the history once
+      cst.transaction(|cst| {
+        cst.object_value().unwrap().get("b").unwrap().remove();
+        Ok::<_, ()>(())
+      })
+    });
+    assert_eq!(result, Ok(()));
+    let changed_text = "{\n  // comment\n  \"a\": 2, /* a */\n  \"c\": 3\n}\n";
+    assert_eq!(cst.to_string(), changed_text);
+
+    assert!(cst.undo());
+    assert_eq!(cst.to_string(), text);
+    assert!(!cst.undo());
+    assert!(cst.redo());
+    assert_eq!(cst.to_string(), changed_text);
+    assert!(!cst.redo());
+    assert!(cst.undo());
+
+    // text edits account for undone changes
+    let edits = cst.take_text_edits();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].new_text, "2");
+    assert!(cst.redo());
+    let edits = cst.take_text_edits();
+    let mut new_text = text.clone();
+    for edit in edits.iter().rev() {
+      new_text.replace_range(edit.range.start..edit.range.end, &edit.new_text);
+    }
+    assert_eq!(new_text, changed_text);
+
+    // committing clears the redo history
+    assert!(cst.undo());
+    cst
+      .transaction(|cst| {
+        cst.object_value().unwrap().append("d", json!(4));
+        Ok::<_, ()>(())
+      })
+      .unwrap();
+    assert!(!cst.can_redo());
+
+    // panicking rolls back the transaction along with any left open inside it
+    let text = cst.to_string();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      cst.transaction(|cst| {
+        cst.object_value().unwrap().append("e", json!(5));
+        cst.begin();
+        cst.object_value().unwrap().get("a").unwrap().remove();
+        panic!("failed");
+        #[allow(unreachable_code)]
+        Ok::<_, ()>(())
+      })
+    }));
+    assert!(result.is_err());
+    assert!(!cst.in_transaction());
+    assert_eq!(cst.to_string(), text);
+    assert!(cst.undo());
+    assert!(cst.redo());
+
+    cst.clear_history();
+    assert!(!cst.can_undo());
+    assert!(!cst.can_redo());
+  }
+
+  #[test]
+  fn transactions_copy_only_modified_nodes() {
+    let text = format!("[{}]", vec!["{ \"a\": [1, 2] }"; 1000].join(", "));
+    let cst = build_cst(&text);
+    let elements = cst.array_value().unwrap().elements();
+    cst
+      .transaction(|_| {
+        let obj = elements[500].as_object().unwrap();
+        obj
+          .get("a")
+          .unwrap()
+          .value()
+          .unwrap()
+          .as_array()
+          .unwrap()
+          .append(json!(3));
+        elements[999].clone().remove();
+        Ok::<_, ()>(())
+      })
+      .unwrap();
+    {
+      let history = cst.0.history.borrow();
+      let entry = &history.undo_stack[0];
+      // the modified array and root array along with the removed
+      // element's nodes rather than every node in the tree
+      assert!(entry.before.states.len() < 50);
+      assert_eq!(entry.before.states.len(), entry.after.states.len());
+    }
+    assert!(cst.undo());
+    assert_eq!(cst.to_string(), text);
+    assert!(cst.redo());
+    assert!(cst.to_string().ends_with("{ \"a\": [1, 2] }]"));
+    assert!(cst.to_string().contains("{ \"a\": [1, 2, 3] }"));
+  }
+
+  #[test]
+  fn transactions_restore_removed_and_moved_nodes() {
+    let text = "{ \"a\": [1, 2], \"b\": [3] }";
+    let cst = build_cst(text);
+    let root_obj = cst.object_value().unwrap();
+    let prop_a = root_obj.get("a").unwrap();
+    let array_a = prop_a.value().unwrap().as_array().unwrap();
+    let array_b = root_obj.get("b").unwrap().value().unwrap().as_array().unwrap();
+
+    cst.begin();
+    // modifying a node after it was removed
+    prop_a.clone().remove();
+    array_a.elements()[0]
+      .as_number_lit()
+      .unwrap()
+      .set_raw_value("5".to_string());
+    array_a.append(json!(6));
+    // moving a node between containers
+    let element = array_b.elements()[0].clone();
+    element.clone().remove_raw();
+    root_obj.append("c", json!(null));
+    let null_node = root_obj.get("c").unwrap().value().unwrap();
+    let parent = null_node.parent().unwrap();
+    let mut index = null_node.child_index();
+    parent.remove_child_set_no_parent(index);
+    parent.raw_insert_child(Some(&mut index), element.clone());
+    assert_eq!(cst.to_string(), "{\n  \"b\": [],\n  \"c\": 3\n}");
+    cst.rollback();
+
+    assert_eq!(cst.to_string(), text);
+    assert_eq!(element.parent().unwrap().as_array().unwrap().elements().len(), 1);
+    assert!(prop_a.parent().unwrap().is_object());
+  }
+
+  #[test]
+  fn transactions_with_changes_outside_transaction() {
+    let text = "{ \"a\": 1, \"b\": 2, \"c\": 3 }";
+    let cst = build_cst(text);
+    let root_obj = cst.object_value().unwrap();
+    let set = |name: &str, value: CstInputValue| root_obj.get(name).unwrap().set_value(value);
+
+    cst
+      .transaction(|_| {
+        set("a", json!(10));
+        Ok::<_, ()>(())
+      })
+      .unwrap();
+    let after_first = cst.to_string();
+    set("b", json!(20));
+    let before_second = cst.to_string();
+    cst
+      .transaction(|_| {
+        set("c", json!(30));
+        set("b", json!(21));
+        Ok::<_, ()>(())
+      })
+      .unwrap();
+    let after_second = cst.to_string();
+
+    assert!(cst.undo());
+    assert_eq!(cst.to_string(), before_second);
+    // the change outside a transaction is undone with the previous one
+    assert!(cst.undo());
+    assert_eq!(cst.to_string(), text);
+    assert!(cst.redo());
+    assert_eq!(cst.to_string(), after_first);
+    assert!(cst.redo());
+    assert_eq!(cst.to_string(), after_second);
+
+    // undoing also reverts changes made outside a transaction since then
+    set("a", json!(40));
+    assert!(cst.undo());
+    assert_eq!(cst.to_string(), before_second);
+    set("c", json!(50));
+    assert!(cst.redo());
+    assert_eq!(cst.to_string(), after_second);
+  }
+
+  #[test]
+  fn object_prop_set_name() {
+    let text = r#"{
+  // comment
+  "a": /* a */ 1,
+  'b': [1, 2],
+  c: {
+    "nested": true
+  },
+  d: 4
+}"#;
+    let cst = CstRootNode::parse(
+      text,
+      &crate::ParseOptions {
+        allow_single_quoted_strings: true,
+        ..Default::default()
+      },
+    )
+    .unwrap();
+    let root_obj = cst.object_value().unwrap();
+    root_obj.get("a").unwrap().set_name("new\"a");
+    root_obj.get("b").unwrap().set_name("new'b");
+    root_obj.get("c").unwrap().set_name("new_c");
+    root_obj.get("d").unwrap().set_name("new d");
+    assert_eq!(
+      cst.to_string(),
+      r#"{
+  // comment
+  "new\"a": /* a */ 1,
+  'new\'b': [1, 2],
+  new_c: {
+    "nested": true
+  },
+  "new d": 4
+}"#
+    );
+    assert_eq!(
+      root_obj
+        .get("new d")
+        .unwrap()
+        .value()
+        .unwrap()
+        .as_number_lit()
+        .unwrap()
+        .to_string(),
+      "4"
+    );
+    assert!(root_obj.get("new'b").is_some());
+  }
+
+  #[test]
+  fn object_sort_properties() {
+    let cst = build_cst(
+      r#"{ // open brace comment
+  // c leading
+  "c": 3, // c trailing
+
+  /* b leading */
+  "b": {
+    "z": 1,
+    "y": [{ "k": 1, "j": 2 }]
+  },
+  "a": 1 // a trailing
+  // end comment
+}"#,
+    );
+    let root_obj = cst.object_value().unwrap();
+    root_obj.sort_properties_alphabetically(false);
+    assert_eq!(
+      cst.to_string(),
+      r#"{ // open brace comment
+  "a": 1, // a trailing
+
+  /* b leading */
+  "b": {
+    "z": 1,
+    "y": [{ "k": 1, "j": 2 }]
+  },
+  // c leading
+  "c": 3 // c trailing
+  // end comment
+}"#
+    );
+
+    root_obj.sort_properties_by(|a, b| b.property_index().cmp(&a.property_index()));
+    assert_eq!(
+      root_obj
+        .properties()
+        .iter()
+        .map(|p| p.name().unwrap().decoded_value().unwrap())
+        .collect::<Vec<_>>(),
+      vec!["c", "b", "a"]
+    );
+  }
+
+  #[test]
+  fn object_sort_properties_layouts() {
+    fn assert_sorted(text: &str, expected: &str) {
+      let cst = build_cst(text);
+      cst.object_value().unwrap().sort_properties_alphabetically(false);
+      let actual = cst.to_string();
+      assert_eq!(actual, expected);
+      assert!(CstRootNode::parse(&actual, &Default::default()).is_ok());
+    }
+
+    // properties take on the whitespace of the position they move to
+    assert_sorted("{\"b\": 1,\n  \"a\": 2\n}", "{\"a\": 2,\n  \"b\": 1\n}");
+    // commas at the start of lines stay there
+    assert_sorted(
+      "{\n  \"c\": 1 // c\n  , \"b\": 2\n  , /* a */ \"a\": 3\n}",
+      "{\n  /* a */ \"a\": 3\n  , \"b\": 2\n  , \"c\": 1 // c\n}",
+    );
+    assert_sorted("{ \"b\": 1 , \"a\": 2, }", "{ \"a\": 2 , \"b\": 1, }");
+    // comments after a comma move with the property that follows on the same line
+    assert_sorted("{ \"b\": 1, /* a */ \"a\": 2, }", "{ /* a */ \"a\": 2, \"b\": 1, }");
+    assert_sorted(
+      "{\n  \"c\": 1, /* b */ \"b\": 2, // b\n  \"a\": 3\n}",
+      "{\n  \"a\": 3, /* b */ \"b\": 2, // b\n  \"c\": 1\n}",
+    );
+  }
+
+  #[test]
+  fn object_sort_properties_recursive() {
+    let cst = build_cst(r#"{ "b": { "z": 1, "y": [{ "k": 1, "j": 2 }] }, "a": 1, }"#);
+    cst.object_value().unwrap().sort_properties_alphabetically(true);
+    assert_eq!(
+      cst.to_string(),
+      r#"{ "a": 1, "b": { "y": [{ "j": 2, "k": 1 }], "z": 1 }, }"#
+    );
+  }
 }
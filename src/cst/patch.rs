@@ -0,0 +1,954 @@
+//! Applying JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7396)
+//! documents to a CST.
+//!
+//! ```
+//! use jsonc_parser::cst::CstRootNode;
+//! use jsonc_parser::cst::patch::PatchOperation;
+//! use jsonc_parser::ParseOptions;
+//! use jsonc_parser::json;
+//!
+//! let json_text = r#"{
+//!   // comment
+//!   "data": [1, 2]
+//! }"#;
+//! let root = CstRootNode::parse(json_text, &ParseOptions::default()).unwrap();
+//! root
+//!   .apply_patch(&[
+//!     PatchOperation::Test {
+//!       path: "/data/0".to_string(),
+//!       value: json!(1),
+//!     },
+//!     PatchOperation::Add {
+//!       path: "/data/-".to_string(),
+//!       value: json!(3),
+//!     },
+//!   ])
+//!   .unwrap();
+//!
+//! assert_eq!(root.to_string(), r#"{
+//!   // comment
+//!   "data": [1, 2, 3]
+//! }"#);
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+use super::CstContainerNode;
+use super::CstInputValue;
+use super::CstLeafNode;
+use super::CstNode;
+use super::CstObject;
+use super::CstObjectProp;
+use super::CstRootNode;
+use super::CstSnapshot;
+use super::CstWhitespace;
+use super::InsertValue;
+use super::compute_indents;
+use super::replace_with;
+use crate::common::numbers_equal;
+use crate::pointer::parse_array_index;
+use crate::pointer::parse_pointer;
+
+/// A JSON Patch (RFC 6902) operation.
+///
+/// Paths are JSON Pointers (RFC 6901).
+#[derive(Debug, Clone)]
+pub enum PatchOperation {
+  /// Adds a value to an object, inserts it into an array, or replaces the root.
+  Add { path: String, value: CstInputValue },
+  /// Removes the value at the path.
+  Remove { path: String },
+  /// Replaces the existing value at the path.
+  Replace { path: String, value: CstInputValue },
+  /// Removes the value at `from` and adds it at `path`.
+  Move { from: String, path: String },
+  /// Adds a copy of the value at `from` to `path`.
+  Copy { from: String, path: String },
+  /// Fails the patch when the value at the path doesn't equal the provided value.
+  Test { path: String, value: CstInputValue },
+}
+
+impl TryFrom<CstInputValue> for PatchOperation {
+  type Error = PatchErrorKind;
+
+  /// Converts an operation object such as `{ "op": "add", "path": "/a", "value": 1 }`.
+  fn try_from(value: CstInputValue) -> Result<Self, Self::Error> {
+    fn take_string(field: Option<CstInputValue>, name: &str) -> Result<String, PatchErrorKind> {
+      match field {
+        Some(CstInputValue::String(value)) => Ok(value),
+        Some(_) => Err(PatchErrorKind::InvalidOperation(format!(
+          "Expected '{}' to be a string",
+          name
+        ))),
+        None => Err(PatchErrorKind::InvalidOperation(format!("Missing '{}'", name))),
+      }
+    }
+
+    let CstInputValue::Object(members) = value else {
+      return Err(PatchErrorKind::InvalidOperation("Expected an object".to_string()));
+    };
+    let mut op = None;
+    let mut path = None;
+    let mut from = None;
+    let mut value = None;
+    for (name, member_value) in members {
+      match name.as_str() {
+        "op" => op = Some(member_value),
+        "path" => path = Some(member_value),
+        "from" => from = Some(member_value),
+        "value" => value = Some(member_value),
+        _ => {} // other members are ignored per the RFC
+      }
+    }
+
+    let op = take_string(op, "op")?;
+    let path = take_string(path, "path")?;
+    let take_value = |value: Option<CstInputValue>| {
+      value.ok_or_else(|| PatchErrorKind::InvalidOperation("Missing 'value'".to_string()))
+    };
+    Ok(match op.as_str() {
+      "add" => PatchOperation::Add {
+        path,
+        value: take_value(value)?,
+      },
+      "remove" => PatchOperation::Remove { path },
+      "replace" => PatchOperation::Replace {
+        path,
+        value: take_value(value)?,
+      },
+      "move" => PatchOperation::Move {
+        from: take_string(from, "from")?,
+        path,
+      },
+      "copy" => PatchOperation::Copy {
+        from: take_string(from, "from")?,
+        path,
+      },
+      "test" => PatchOperation::Test {
+        path,
+        value: take_value(value)?,
+      },
+      _ => {
+        return Err(PatchErrorKind::InvalidOperation(format!("Unknown operation '{}'", op)));
+      }
+    })
+  }
+}
+
+/// Parses a JSON Patch document, which is an array of operation objects.
+///
+/// A `serde_json::Value` may be provided by converting it with `.into()`
+/// when the `serde_json` feature is enabled.
+pub fn parse_patch(document: CstInputValue) -> Result<Vec<PatchOperation>, PatchError> {
+  let CstInputValue::Array(elements) = document else {
+    return Err(PatchError {
+      operation_index: 0,
+      kind: PatchErrorKind::InvalidOperation("Expected an array of operations".to_string()),
+    });
+  };
+  elements
+    .into_iter()
+    .enumerate()
+    .map(|(operation_index, element)| {
+      PatchOperation::try_from(element).map_err(|kind| PatchError { operation_index, kind })
+    })
+    .collect()
+}
+
+/// Error applying a JSON Patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+  /// Index of the operation that failed.
+  pub operation_index: usize,
+  pub kind: PatchErrorKind,
+}
+
+impl std::error::Error for PatchError {}
+
+impl fmt::Display for PatchError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} (operation {})", self.kind, self.operation_index)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchErrorKind {
+  /// The operation object is malformed.
+  InvalidOperation(String),
+  /// The path is not a valid JSON Pointer.
+  InvalidPointer(String),
+  /// The path, or the container it refers into, doesn't exist.
+  PathNotFound(String),
+  /// A move's `from` location is an ancestor of its `path`.
+  MoveIntoDescendant { from: String, path: String },
+  /// The value at the path didn't equal the expected value.
+  TestFailed(String),
+}
+
+impl std::error::Error for PatchErrorKind {}
+
+impl fmt::Display for PatchErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PatchErrorKind::InvalidOperation(message) => write!(f, "Invalid patch operation. {}", message),
+      PatchErrorKind::InvalidPointer(path) => write!(f, "Invalid JSON Pointer '{}'", path),
+      PatchErrorKind::PathNotFound(path) => write!(f, "Path not found '{}'", path),
+      PatchErrorKind::MoveIntoDescendant { from, path } => {
+        write!(f, "Cannot move '{}' into its own descendant '{}'", from, path)
+      }
+      PatchErrorKind::TestFailed(path) => write!(f, "Test failed for path '{}'", path),
+    }
+  }
+}
+
+impl CstRootNode {
+  /// Applies the JSON Patch (RFC 6902) operations to the document.
+  ///
+  /// The patch is applied atomically. When any operation fails, including
+  /// a `test` operation, an error is returned and the document is left
+  /// unchanged. Comments and formatting outside the modified values are kept,
+  /// along with those within a moved value.
+  ///
+  /// Note: Comments within a copied value are not preserved.
+  pub fn apply_patch(&self, operations: &[PatchOperation]) -> Result<(), PatchError> {
+    // validate against a plain model of the document first so
+    // the CST is only modified once every operation will succeed
+    let mut model = self.value().and_then(|value| node_to_input_value(&value));
+    for (operation_index, operation) in operations.iter().enumerate() {
+      apply_to_model(&mut model, operation).map_err(|kind| PatchError { operation_index, kind })?;
+    }
+
+    // the model should catch every failure, but restore the tree if
+    // it ever differs from the CST so a failed patch changes nothing
+    let snapshot = CstSnapshot::take(self);
+    for (operation_index, operation) in operations.iter().enumerate() {
+      if let Err(kind) = apply_to_cst(self, operation) {
+        snapshot.restore();
+        return Err(PatchError { operation_index, kind });
+      }
+    }
+    Ok(())
+  }
+
+  /// Applies a JSON Merge Patch (RFC 7396) to the document.
+  ///
+  /// See [`CstObject::apply_merge_patch`] for details.
+  pub fn apply_merge_patch(&self, patch: CstInputValue) {
+    match self.object_value() {
+      Some(obj) if matches!(patch, CstInputValue::Object(_)) => obj.apply_merge_patch(patch),
+      _ => self.set_value(without_merge_patch_nulls(patch)),
+    }
+  }
+}
+
+impl CstObject {
+  /// Applies a JSON Merge Patch (RFC 7396) to the object.
+  ///
+  /// Properties with a `null` value in the patch are removed, objects are
+  /// merged recursively, and any other value replaces the existing value.
+  /// Existing properties keep their comments and new properties are appended
+  /// using the object's indentation. A patch that isn't an object replaces
+  /// this object.
+  pub fn apply_merge_patch(&self, patch: CstInputValue) {
+    let CstInputValue::Object(patch_props) = patch else {
+      self.clone().replace_with(patch);
+      return;
+    };
+    for (name, value) in patch_props {
+      let prop = self.get(&name);
+      if matches!(value, CstInputValue::Null) {
+        if let Some(prop) = prop {
+          prop.remove();
+        }
+        continue;
+      }
+      if let CstInputValue::Object(_) = &value
+        && let Some(obj) = prop.as_ref().and_then(|prop| prop.object_value())
+      {
+        obj.apply_merge_patch(value);
+        continue;
+      }
+      let value = without_merge_patch_nulls(value);
+      match prop {
+        Some(prop) => prop.set_value(value),
+        None => {
+          self.append(&name, value);
+        }
+      }
+    }
+  }
+}
+
+/// Gets the value a merge patch results in when there's nothing to merge
+/// into, which is the value with the `null` properties removed.
+fn without_merge_patch_nulls(value: CstInputValue) -> CstInputValue {
+  match value {
+    CstInputValue::Object(props) => CstInputValue::Object(
+      props
+        .into_iter()
+        .filter(|(_, value)| !matches!(value, CstInputValue::Null))
+        .map(|(name, value)| (name, without_merge_patch_nulls(value)))
+        .collect(),
+    ),
+    value => value,
+  }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Cow<'_, str>>, PatchErrorKind> {
+  parse_pointer(path).ok_or_else(|| PatchErrorKind::InvalidPointer(path.to_string()))
+}
+
+fn path_not_found(path: &str) -> PatchErrorKind {
+  PatchErrorKind::PathNotFound(path.to_string())
+}
+
+fn apply_to_model(root: &mut Option<CstInputValue>, operation: &PatchOperation) -> Result<(), PatchErrorKind> {
+  match operation {
+    PatchOperation::Add { path, value } => {
+      model_add(root, &parse_path(path)?, value.clone()).ok_or_else(|| path_not_found(path))
+    }
+    PatchOperation::Remove { path } => model_remove(root, &parse_path(path)?)
+      .map(|_| ())
+      .ok_or_else(|| path_not_found(path)),
+    PatchOperation::Replace { path, value } => {
+      let target = model_get_mut(root, &parse_path(path)?).ok_or_else(|| path_not_found(path))?;
+      *target = value.clone();
+      Ok(())
+    }
+    PatchOperation::Move { from, path } => {
+      let from_tokens = parse_path(from)?;
+      let path_tokens = parse_path(path)?;
+      if path_tokens.len() > from_tokens.len() && path_tokens.starts_with(&from_tokens) {
+        return Err(PatchErrorKind::MoveIntoDescendant {
+          from: from.clone(),
+          path: path.clone(),
+        });
+      }
+      let value = model_remove(root, &from_tokens).ok_or_else(|| path_not_found(from))?;
+      model_add(root, &path_tokens, value).ok_or_else(|| path_not_found(path))
+    }
+    PatchOperation::Copy { from, path } => {
+      let value = model_get(root, &parse_path(from)?)
+        .cloned()
+        .ok_or_else(|| path_not_found(from))?;
+      model_add(root, &parse_path(path)?, value).ok_or_else(|| path_not_found(path))
+    }
+    PatchOperation::Test { path, value } => {
+      let actual = model_get(root, &parse_path(path)?).ok_or_else(|| path_not_found(path))?;
+      if input_values_equal(actual, value) {
+        Ok(())
+      } else {
+        Err(PatchErrorKind::TestFailed(path.clone()))
+      }
+    }
+  }
+}
+
+fn model_get<'a>(root: &'a Option<CstInputValue>, tokens: &[Cow<str>]) -> Option<&'a CstInputValue> {
+  tokens.iter().try_fold(root.as_ref()?, |value, token| match value {
+    CstInputValue::Object(props) => props.iter().rfind(|(name, _)| name == token).map(|(_, value)| value),
+    CstInputValue::Array(elements) => elements.get(parse_array_index(token)?),
+    _ => None,
+  })
+}
+
+fn model_get_mut<'a>(root: &'a mut Option<CstInputValue>, tokens: &[Cow<str>]) -> Option<&'a mut CstInputValue> {
+  tokens.iter().try_fold(root.as_mut()?, |value, token| match value {
+    CstInputValue::Object(props) => props
+      .iter_mut()
+      .rfind(|(name, _)| name == token)
+      .map(|(_, value)| value),
+    CstInputValue::Array(elements) => elements.get_mut(parse_array_index(token)?),
+    _ => None,
+  })
+}
+
+fn model_add(root: &mut Option<CstInputValue>, tokens: &[Cow<str>], value: CstInputValue) -> Option<()> {
+  let Some((last, parent_tokens)) = tokens.split_last() else {
+    *root = Some(value);
+    return Some(());
+  };
+  match model_get_mut(root, parent_tokens)? {
+    CstInputValue::Object(props) => match props.iter_mut().rfind(|(name, _)| name == last) {
+      Some((_, existing)) => *existing = value,
+      None => props.push((last.to_string(), value)),
+    },
+    CstInputValue::Array(elements) => {
+      let index = array_insert_index(last, elements.len())?;
+      elements.insert(index, value);
+    }
+    _ => return None,
+  }
+  Some(())
+}
+
+fn model_remove(root: &mut Option<CstInputValue>, tokens: &[Cow<str>]) -> Option<CstInputValue> {
+  let Some((last, parent_tokens)) = tokens.split_last() else {
+    return root.take();
+  };
+  match model_get_mut(root, parent_tokens)? {
+    CstInputValue::Object(props) => {
+      let index = props.iter().rposition(|(name, _)| name == last)?;
+      Some(props.remove(index).1)
+    }
+    CstInputValue::Array(elements) => {
+      let index = parse_array_index(last)?;
+      (index < elements.len()).then(|| elements.remove(index))
+    }
+    _ => None,
+  }
+}
+
+/// Gets the index to add an array element at where `-` refers to the end.
+fn array_insert_index(token: &str, len: usize) -> Option<usize> {
+  let index = if token == "-" { len } else { parse_array_index(token)? };
+  (index <= len).then_some(index)
+}
+
+fn apply_to_cst(root: &CstRootNode, operation: &PatchOperation) -> Result<(), PatchErrorKind> {
+  match operation {
+    PatchOperation::Add { path, value } => cst_add(root, &parse_path(path)?, value.clone())
+      .map(|_| ())
+      .ok_or_else(|| path_not_found(path)),
+    PatchOperation::Remove { path } => cst_remove(root, &parse_path(path)?)
+      .map(|_| ())
+      .ok_or_else(|| path_not_found(path)),
+    PatchOperation::Replace { path, value } => {
+      cst_replace(root, &parse_path(path)?, value.clone()).ok_or_else(|| path_not_found(path))
+    }
+    PatchOperation::Move { from, path } => {
+      let node = cst_get(root, &parse_path(from)?).ok_or_else(|| path_not_found(from))?;
+      cst_move(root, node, &parse_path(path)?).ok_or_else(|| path_not_found(path))
+    }
+    PatchOperation::Copy { from, path } => {
+      let value = cst_get(root, &parse_path(from)?)
+        .and_then(|node| node_to_input_value(&node))
+        .ok_or_else(|| path_not_found(from))?;
+      cst_add(root, &parse_path(path)?, value)
+        .map(|_| ())
+        .ok_or_else(|| path_not_found(path))
+    }
+    // already checked against the model
+    PatchOperation::Test { .. } => Ok(()),
+  }
+}
+
+fn cst_get(root: &CstRootNode, tokens: &[Cow<str>]) -> Option<CstNode> {
+  CstNode::from(root.clone()).pointer_tokens(tokens)
+}
+
+/// Adds the value and returns its node.
+fn cst_add(root: &CstRootNode, tokens: &[Cow<str>], value: CstInputValue) -> Option<CstNode> {
+  let Some((last, parent_tokens)) = tokens.split_last() else {
+    root.set_value(value);
+    return root.value();
+  };
+  match cst_get(root, parent_tokens)? {
+    CstNode::Container(CstContainerNode::Object(obj)) => match last_prop(&obj, last) {
+      Some(prop) => {
+        prop.set_value(value);
+        prop.value()
+      }
+      None => obj.append(last, value).value(),
+    },
+    CstNode::Container(CstContainerNode::Array(arr)) => {
+      let index = array_insert_index(last, arr.elements().len())?;
+      Some(arr.insert(index, value))
+    }
+    _ => None,
+  }
+}
+
+/// Gets the last property with the name, which is the one a pointer resolves to.
+fn last_prop(obj: &CstObject, name: &str) -> Option<CstObjectProp> {
+  obj.properties().into_iter().rfind(|prop| {
+    prop
+      .name()
+      .and_then(|prop_name| prop_name.decoded_value().ok())
+      .as_deref()
+      == Some(name)
+  })
+}
+
+fn cst_remove(root: &CstRootNode, tokens: &[Cow<str>]) -> Option<CstInputValue> {
+  let node = cst_get(root, tokens)?;
+  let value = node_to_input_value(&node)?;
+  cst_remove_node(&node);
+  Some(value)
+}
+
+/// Moves the value node to the path, keeping the comments and formatting within it.
+fn cst_move(root: &CstRootNode, node: CstNode, tokens: &[Cow<str>]) -> Option<()> {
+  let from_indent = compute_indents(&node).current_indent;
+  cst_remove_node(&node);
+  // detach it from its removed property
+  node.clone().remove_raw();
+
+  // add a placeholder that handles the commas and newlines, then swap in the node
+  let placeholder = cst_add(root, tokens, CstInputValue::Null)?;
+  reindent(&node, &from_indent, &compute_indents(&placeholder).current_indent);
+  let parent = placeholder.parent()?;
+  let mut child_index = placeholder.child_index();
+  parent.remove_child_set_no_parent(child_index);
+  parent.raw_insert_child(Some(&mut child_index), node);
+  Some(())
+}
+
+/// Removes the value node along with its property when in an object.
+fn cst_remove_node(node: &CstNode) {
+  match node.parent() {
+    Some(CstContainerNode::ObjectProp(prop)) => prop.remove(),
+    _ => node.clone().remove(),
+  }
+}
+
+/// Changes the indentation of the lines within a moved node from the
+/// indentation at its previous location to the one at its new location.
+fn reindent(node: &CstNode, from_indent: &str, to_indent: &str) {
+  let CstNode::Container(container) = node else {
+    return;
+  };
+  if from_indent == to_indent {
+    return;
+  }
+  let children = container.children();
+  for (index, child) in children.iter().enumerate() {
+    if child.is_newline() {
+      match children.get(index + 1) {
+        Some(CstNode::Leaf(CstLeafNode::Whitespace(whitespace))) => {
+          if let Some(rest) = whitespace.value().strip_prefix(from_indent) {
+            whitespace.set_value(format!("{}{}", to_indent, rest));
+          }
+        }
+        Some(next) if from_indent.is_empty() && !next.is_newline() => {
+          let mut insert_index = next.child_index();
+          container.raw_insert_child(
+            Some(&mut insert_index),
+            CstWhitespace::new(to_indent.to_string()).into(),
+          );
+        }
+        _ => {}
+      }
+    }
+    reindent(child, from_indent, to_indent);
+  }
+}
+
+fn cst_replace(root: &CstRootNode, tokens: &[Cow<str>], value: CstInputValue) -> Option<()> {
+  let node = cst_get(root, tokens)?;
+  match node.parent()? {
+    CstContainerNode::ObjectProp(prop) => prop.set_value(value),
+    CstContainerNode::Root(root) => root.set_value(value),
+    _ => {
+      replace_with(node, InsertValue::Value(value))?;
+    }
+  }
+  Some(())
+}
+
+/// Converts a value node to an input value, ignoring trivia.
+fn node_to_input_value(node: &CstNode) -> Option<CstInputValue> {
+  Some(match node {
+    CstNode::Container(CstContainerNode::Object(obj)) => CstInputValue::Object(
+      obj
+        .properties()
+        .into_iter()
+        .filter_map(|prop| {
+          let name = prop.name()?.decoded_value().ok()?;
+          Some((name, node_to_input_value(&prop.value()?)?))
+        })
+        .collect(),
+    ),
+    CstNode::Container(CstContainerNode::Array(arr)) => {
+      CstInputValue::Array(arr.elements().iter().filter_map(node_to_input_value).collect())
+    }
+    CstNode::Leaf(CstLeafNode::BooleanLit(value)) => CstInputValue::Bool(value.value()),
+    CstNode::Leaf(CstLeafNode::NullKeyword(_)) => CstInputValue::Null,
+    CstNode::Leaf(CstLeafNode::NumberLit(value)) => CstInputValue::Number(value.to_string()),
+    CstNode::Leaf(CstLeafNode::StringLit(value)) => CstInputValue::String(value.decoded_value().ok()?),
+    _ => return None,
+  })
+}
+
+/// Compares values where numbers are equal when they have the same value
+/// (ex. `1`, `1.0`, and `0x1`) and object property order doesn't matter.
+fn input_values_equal(a: &CstInputValue, b: &CstInputValue) -> bool {
+  match (a, b) {
+    (CstInputValue::Null, CstInputValue::Null) => true,
+    (CstInputValue::Bool(a), CstInputValue::Bool(b)) => a == b,
+    (CstInputValue::String(a), CstInputValue::String(b)) => a == b,
+    (CstInputValue::Number(a), CstInputValue::Number(b)) => numbers_equal(a, b),
+    (CstInputValue::Array(a), CstInputValue::Array(b)) => {
+      a.len() == b.len() && a.iter().zip(b).all(|(a, b)| input_values_equal(a, b))
+    }
+    (CstInputValue::Object(a), CstInputValue::Object(b)) => {
+      a.len() == b.len()
+        && a.iter().all(|(name, a)| {
+          b.iter()
+            .find(|(other_name, _)| other_name == name)
+            .is_some_and(|(_, b)| input_values_equal(a, b))
+        })
+    }
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::ParseOptions;
+  use crate::json;
+
+  fn apply(text: &str, operations: &[PatchOperation]) -> Result<String, PatchError> {
+    let root = CstRootNode::parse(text, &ParseOptions::default()).unwrap();
+    let result = root.apply_patch(operations);
+    if result.is_err() {
+      // failures should never modify the document
+      assert_eq!(root.to_string(), text);
+    }
+    result.map(|_| root.to_string())
+  }
+
+  fn add(path: &str, value: CstInputValue) -> PatchOperation {
+    PatchOperation::Add {
+      path: path.to_string(),
+      value,
+    }
+  }
+
+  fn remove(path: &str) -> PatchOperation {
+    PatchOperation::Remove { path: path.to_string() }
+  }
+
+  fn replace(path: &str, value: CstInputValue) -> PatchOperation {
+    PatchOperation::Replace {
+      path: path.to_string(),
+      value,
+    }
+  }
+
+  fn test(path: &str, value: CstInputValue) -> PatchOperation {
+    PatchOperation::Test {
+      path: path.to_string(),
+      value,
+    }
+  }
+
+  #[test]
+  fn add_preserves_comments() {
+    let text = r#"{
+  // leading
+  "a": 1, // trailing
+  "b": [1, 2]
+}"#;
+    assert_eq!(
+      apply(
+        text,
+        &[add("/c", json!(true)), add("/b/0", json!(0)), add("/b/-", json!(3))]
+      )
+      .unwrap(),
+      r#"{
+  // leading
+  "a": 1, // trailing
+  "b": [0, 1, 2, 3],
+  "c": true
+}"#
+    );
+    // add to an existing key replaces it
+    assert_eq!(
+      apply(text, &[add("/a", json!({ "x": null }))]).unwrap(),
+      r#"{
+  // leading
+  "a": {
+    "x": null
+  }, // trailing
+  "b": [1, 2]
+}"#
+    );
+    // replaces the root
+    assert_eq!(
+      apply("// comment\n1\n", &[add("", json!([]))]).unwrap(),
+      "// comment\n[]\n"
+    );
+  }
+
+  #[test]
+  fn remove_and_replace() {
+    let text = r#"{
+  "a": 1, // a
+  // b
+  "b": [1, 2, 3],
+  "c": 3 // c
+}"#;
+    assert_eq!(
+      apply(text, &[remove("/a"), remove("/b/1"), replace("/c", json!("text"))]).unwrap(),
+      r#"{
+  // b
+  "b": [1, 3],
+  "c": "text" // c
+}"#
+    );
+    assert_eq!(
+      apply(text, &[replace("/b/0", json!(null))]).unwrap(),
+      r#"{
+  "a": 1, // a
+  // b
+  "b": [null, 2, 3],
+  "c": 3 // c
+}"#
+    );
+  }
+
+  #[test]
+  fn move_and_copy() {
+    let text = r#"{ "a": { "b": [1, "2"] }, "c": 0 }"#;
+    assert_eq!(
+      apply(
+        text,
+        &[
+          PatchOperation::Copy {
+            from: "/a/b".to_string(),
+            path: "/d".to_string(),
+          },
+          PatchOperation::Move {
+            from: "/c".to_string(),
+            path: "/a/b/0".to_string(),
+          },
+        ]
+      )
+      .unwrap(),
+      r#"{
+  "a": { "b": [0, 1, "2"] },
+  "d": [1, "2"]
+}"#
+    );
+    assert_eq!(
+      apply(
+        text,
+        &[PatchOperation::Move {
+          from: "/a".to_string(),
+          path: "/a/b/0".to_string(),
+        }]
+      ),
+      Err(PatchError {
+        operation_index: 0,
+        kind: PatchErrorKind::MoveIntoDescendant {
+          from: "/a".to_string(),
+          path: "/a/b/0".to_string(),
+        },
+      })
+    );
+
+    // comments and formatting within the moved value are kept
+    let text = r#"{
+  "a": {
+    "b": [
+      1, // one
+      /* two */ 2
+    ]
+  },
+  "c": {}
+}"#;
+    assert_eq!(
+      apply(
+        text,
+        &[PatchOperation::Move {
+          from: "/a/b".to_string(),
+          path: "/c/d".to_string(),
+        }]
+      )
+      .unwrap(),
+      r#"{
+  "a": {},
+  "c": {
+    "d": [
+      1, // one
+      /* two */ 2
+    ]
+  }
+}"#
+    );
+  }
+
+  #[test]
+  fn fails_atomically() {
+    let text = r#"{ "a": 1, "b": [0x1, 1.5e3, { "c": "d" }] }"#;
+    assert_eq!(
+      apply(text, &[remove("/a"), test("/b", json!([1, 1500, { "c": "d" }]))]).unwrap(),
+      r#"{ "b": [0x1, 1.5e3, { "c": "d" }] }"#
+    );
+    assert_eq!(
+      apply(text, &[remove("/a"), test("/b/2/c", json!("e"))]),
+      Err(PatchError {
+        operation_index: 1,
+        kind: PatchErrorKind::TestFailed("/b/2/c".to_string()),
+      })
+    );
+    // large integers are compared exactly
+    assert_eq!(
+      apply(
+        r#"{ "id": 9007199254740993 }"#,
+        &[test("/id", json!(9007199254740992u64))]
+      )
+      .unwrap_err()
+      .kind,
+      PatchErrorKind::TestFailed("/id".to_string())
+    );
+    // the removal is only seen by later operations
+    assert_eq!(
+      apply(text, &[remove("/a"), test("/a", json!(1))]),
+      Err(PatchError {
+        operation_index: 1,
+        kind: PatchErrorKind::PathNotFound("/a".to_string()),
+      })
+    );
+    assert_eq!(
+      apply(text, &[add("/b/4", json!(1))]).unwrap_err().kind,
+      PatchErrorKind::PathNotFound("/b/4".to_string())
+    );
+    assert_eq!(
+      apply(text, &[add("/x/y", json!(1))]).unwrap_err().kind,
+      PatchErrorKind::PathNotFound("/x/y".to_string())
+    );
+    assert_eq!(
+      apply(text, &[replace("/b/-", json!(1))]).unwrap_err().kind,
+      PatchErrorKind::PathNotFound("/b/-".to_string())
+    );
+    assert_eq!(
+      apply(text, &[remove("a")]).unwrap_err().kind,
+      PatchErrorKind::InvalidPointer("a".to_string())
+    );
+  }
+
+  #[test]
+  fn duplicate_keys() {
+    // the last occurrence is used the same as for pointers
+    let text = r#"{ "a": 1, "a": [2] }"#;
+    assert_eq!(
+      apply(text, &[test("/a", json!([2])), add("/a/-", json!(3))]).unwrap(),
+      r#"{ "a": 1, "a": [2, 3] }"#
+    );
+    assert_eq!(apply(text, &[add("/a", json!(4))]).unwrap(), r#"{ "a": 1, "a": 4 }"#);
+    assert_eq!(
+      apply(text, &[remove("/a"), test("/a", json!(1))]).unwrap(),
+      r#"{ "a": 1 }"#
+    );
+  }
+
+  #[test]
+  fn escaped_paths() {
+    let text = r#"{ "a/b": { "m~n": 1 } }"#;
+    assert_eq!(
+      apply(text, &[replace("/a~1b/m~0n", json!(2))]).unwrap(),
+      r#"{ "a/b": { "m~n": 2 } }"#
+    );
+  }
+
+  #[test]
+  fn parses_patch_documents() {
+    let operations = parse_patch(json!([
+      { "op": "add", "path": "/a", "value": 1 },
+      { "op": "test", "path": "/a", "value": 1 }
+    ]))
+    .unwrap();
+    assert_eq!(apply("{}", &operations).unwrap(), "{\n  \"a\": 1\n}");
+
+    assert_eq!(
+      parse_patch(json!([{ "op": "remove", "path": "/a" }, { "op": "add", "path": "/a" }])).unwrap_err(),
+      PatchError {
+        operation_index: 1,
+        kind: PatchErrorKind::InvalidOperation("Missing 'value'".to_string()),
+      }
+    );
+    assert_eq!(
+      parse_patch(json!([{ "op": "other", "path": "/a" }]))
+        .unwrap_err()
+        .to_string(),
+      "Invalid patch operation. Unknown operation 'other' (operation 0)"
+    );
+  }
+
+  #[test]
+  fn merge_patch() {
+    let text = r#"{
+  // base
+  "a": 1, // a
+  "b": {
+    "c": true, // c
+    "d": [1, 2]
+  },
+  "e": "remove" // e
+}"#;
+    let root = CstRootNode::parse(text, &ParseOptions::default()).unwrap();
+    root.apply_merge_patch(json!({
+      "a": 2,
+      "b": { "d": [3], "x": null, "f": { "g": null, "h": 1 } },
+      "e": null,
+      "i": { "j": null }
+    }));
+    assert_eq!(
+      root.to_string(),
+      r#"{
+  // base
+  "a": 2, // a
+  "b": {
+    "c": true, // c
+    "d": [3],
+    "f": {
+      "h": 1
+    }
+  },
+  "i": {}
+}"#
+    );
+
+    // non-object patches replace the value
+    root.apply_merge_patch(json!({ "a": [1], "b": 5 }));
+    root
+      .object_value()
+      .unwrap()
+      .object_value("i")
+      .unwrap()
+      .apply_merge_patch(json!(false));
+    assert_eq!(
+      root.to_string(),
+      r#"{
+  // base
+  "a": [1], // a
+  "b": 5,
+  "i": false
+}"#
+    );
+    root.apply_merge_patch(json!({ "z": null }));
+    assert_eq!(root.value().unwrap().as_object().unwrap().properties().len(), 3);
+    root.apply_merge_patch(json!("text"));
+    assert_eq!(root.to_string(), "\"text\"");
+  }
+
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn parses_serde_json_patch_documents() {
+    let document = serde_json::json!([
+      { "op": "replace", "path": "/a", "value": [1.5, "text", { "b": null }] },
+      { "op": "move", "from": "/a", "path": "/c" }
+    ]);
+    let operations = parse_patch(document.into()).unwrap();
+    assert_eq!(
+      apply("{\n  // keep\n  \"x\": 0,\n  \"a\": 1\n}", &operations).unwrap(),
+      r#"{
+  // keep
+  "x": 0,
+  "c": [
+    1.5,
+    "text",
+    {
+      "b": null
+    }
+  ]
+}"#
+    );
+  }
+}
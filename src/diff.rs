@@ -0,0 +1,387 @@
+//! Structural differences between JSON values.
+//!
+//! Comments, whitespace, the order of object properties, and how a number
+//! is written (ex. `1.0` and `1`) don't affect the result.
+//!
+//! ```
+//! use jsonc_parser::ParseOptions;
+//! use jsonc_parser::common::Range;
+//! use jsonc_parser::diff::ChangeKind;
+//! use jsonc_parser::diff::diff_ast_values;
+//! use jsonc_parser::parse_to_ast;
+//!
+//! let old = parse_to_ast(r#"{ "a": 1, "b": [true] }"#, &Default::default(), &ParseOptions::default()).unwrap();
+//! let new = parse_to_ast(r#"{
+//!   // comment
+//!   "b": [false],
+//!   "a": 1.0
+//! }"#, &Default::default(), &ParseOptions::default()).unwrap();
+//!
+//! let changes = diff_ast_values(old.value.as_ref().unwrap(), new.value.as_ref().unwrap());
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].kind, ChangeKind::Changed);
+//! assert_eq!(changes[0].path, "/b/0");
+//! assert_eq!(changes[0].old_range(), Some(Range::new(16, 20)));
+//! assert_eq!(changes[0].new_range(), Some(Range::new(23, 28)));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::JsonValue;
+use crate::ast;
+use crate::common::Range;
+use crate::common::Ranged;
+use crate::common::numbers_equal;
+use crate::pointer::escape_token;
+
+/// Kind of change between two values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+  /// The value only exists in the new value.
+  Added,
+  /// The value only exists in the old value.
+  Removed,
+  /// The value exists in both, but is different.
+  Changed,
+}
+
+/// A difference found at a path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change<'v, T> {
+  pub kind: ChangeKind,
+  /// JSON Pointer (RFC 6901) to the value.
+  pub path: String,
+  /// Value in the old value or `None` when added.
+  pub old: Option<&'v T>,
+  /// Value in the new value or `None` when removed.
+  pub new: Option<&'v T>,
+}
+
+impl<'v, 'a> Change<'v, ast::Value<'a>> {
+  /// Range of the value in the old text.
+  pub fn old_range(&self) -> Option<Range> {
+    self.old.map(|value| value.range())
+  }
+
+  /// Range of the value in the new text.
+  pub fn new_range(&self) -> Option<Range> {
+    self.new.map(|value| value.range())
+  }
+}
+
+#[cfg(feature = "cst")]
+impl<'v, T> Change<'v, T>
+where
+  &'v T: Into<crate::cst::CstInputValue>,
+{
+  /// Converts the change to a JSON Patch (RFC 6902) operation.
+  ///
+  /// Applying the operations of all the changes, in order, to
+  /// the old value results in the new value.
+  pub fn to_patch_operation(&self) -> crate::cst::patch::PatchOperation {
+    use crate::cst::patch::PatchOperation;
+
+    let path = self.path.clone();
+    match (self.old, self.new) {
+      (Some(_), Some(new)) => PatchOperation::Replace {
+        path,
+        value: new.into(),
+      },
+      (None, Some(new)) => PatchOperation::Add {
+        path,
+        value: new.into(),
+      },
+      (_, None) => PatchOperation::Remove { path },
+    }
+  }
+}
+
+/// Gets the differences between two values.
+///
+/// Removed array elements are provided from the last to the first so the
+/// paths remain valid when applying the changes in order.
+pub fn diff_values<'v, 'a>(old: &'v JsonValue<'a>, new: &'v JsonValue<'a>) -> Vec<Change<'v, JsonValue<'a>>> {
+  diff(old, new)
+}
+
+/// Gets the differences between two AST values.
+///
+/// When an object has duplicate properties, the last one is used.
+/// Removed array elements are provided from the last to the first so the
+/// paths remain valid when applying the changes in order.
+pub fn diff_ast_values<'v, 'a>(old: &'v ast::Value<'a>, new: &'v ast::Value<'a>) -> Vec<Change<'v, ast::Value<'a>>> {
+  diff(old, new)
+}
+
+enum ValueView<'v, T> {
+  Null,
+  Bool(bool),
+  Number(&'v str),
+  String(&'v str),
+  Array(Vec<&'v T>),
+  Object(Vec<(&'v str, &'v T)>),
+}
+
+trait DiffValue: Sized {
+  fn view(&self) -> ValueView<'_, Self>;
+}
+
+impl DiffValue for JsonValue<'_> {
+  fn view(&self) -> ValueView<'_, Self> {
+    match self {
+      JsonValue::Null => ValueView::Null,
+      JsonValue::Boolean(value) => ValueView::Bool(*value),
+      JsonValue::Number(value) => ValueView::Number(value),
+      JsonValue::String(value) => ValueView::String(value),
+      JsonValue::Array(arr) => ValueView::Array(arr.iter().collect()),
+      JsonValue::Object(obj) => ValueView::Object(obj.iter().map(|(name, value)| (name.as_ref(), value)).collect()),
+    }
+  }
+}
+
+impl DiffValue for ast::Value<'_> {
+  fn view(&self) -> ValueView<'_, Self> {
+    match self {
+      // a value that couldn't be parsed is treated as null
+      ast::Value::NullKeyword(_) | ast::Value::MissingValue(_) => ValueView::Null,
+      ast::Value::BooleanLit(lit) => ValueView::Bool(lit.value),
+      ast::Value::NumberLit(lit) => ValueView::Number(lit.value),
+      ast::Value::StringLit(lit) => ValueView::String(&lit.value),
+      ast::Value::Array(arr) => ValueView::Array(arr.elements.iter().collect()),
+      ast::Value::Object(obj) => ValueView::Object(
+        obj
+          .properties
+          .iter()
+          .map(|prop| (prop.name.as_str(), &prop.value))
+          .collect(),
+      ),
+    }
+  }
+}
+
+fn diff<'v, T: DiffValue>(old: &'v T, new: &'v T) -> Vec<Change<'v, T>> {
+  let mut changes = Vec::new();
+  diff_at(&mut String::new(), old, new, &mut changes);
+  changes
+}
+
+fn diff_at<'v, T: DiffValue>(path: &mut String, old: &'v T, new: &'v T, changes: &mut Vec<Change<'v, T>>) {
+  fn push_change<'v, T>(
+    changes: &mut Vec<Change<'v, T>>,
+    kind: ChangeKind,
+    path: &str,
+    old: Option<&'v T>,
+    new: Option<&'v T>,
+  ) {
+    changes.push(Change {
+      kind,
+      path: path.to_string(),
+      old,
+      new,
+    });
+  }
+
+  fn with_token(path: &mut String, token: &str, action: impl FnOnce(&mut String)) {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&escape_token(token));
+    action(path);
+    path.truncate(len);
+  }
+
+  match (old.view(), new.view()) {
+    (ValueView::Object(old_props), ValueView::Object(new_props)) => {
+      // collecting keeps the last of any duplicate properties
+      let old_map = old_props.iter().copied().collect::<HashMap<_, _>>();
+      let new_map = new_props.iter().copied().collect::<HashMap<_, _>>();
+      let is_last = |map: &HashMap<&str, &T>, name: &str, value: &T| std::ptr::eq(map[name], value);
+      for (name, old_value) in old_props.iter().copied() {
+        if !is_last(&old_map, name, old_value) {
+          continue;
+        }
+        with_token(path, name, |path| match new_map.get(name) {
+          Some(new_value) => diff_at(path, old_value, new_value, changes),
+          None => push_change(changes, ChangeKind::Removed, path, Some(old_value), None),
+        });
+      }
+      for (name, new_value) in new_props.iter().copied() {
+        if !is_last(&new_map, name, new_value) || old_map.contains_key(name) {
+          continue;
+        }
+        with_token(path, name, |path| {
+          push_change(changes, ChangeKind::Added, path, None, Some(new_value))
+        });
+      }
+    }
+    (ValueView::Array(old_elements), ValueView::Array(new_elements)) => {
+      for (index, (old_value, new_value)) in old_elements.iter().zip(&new_elements).enumerate() {
+        with_token(path, &index.to_string(), |path| {
+          diff_at(path, *old_value, *new_value, changes)
+        });
+      }
+      for index in (new_elements.len()..old_elements.len()).rev() {
+        with_token(path, &index.to_string(), |path| {
+          push_change(changes, ChangeKind::Removed, path, Some(old_elements[index]), None)
+        });
+      }
+      for (index, new_value) in new_elements.iter().enumerate().skip(old_elements.len()) {
+        with_token(path, &index.to_string(), |path| {
+          push_change(changes, ChangeKind::Added, path, None, Some(*new_value))
+        });
+      }
+    }
+    (old_view, new_view) => {
+      let is_equal = match (old_view, new_view) {
+        (ValueView::Null, ValueView::Null) => true,
+        (ValueView::Bool(a), ValueView::Bool(b)) => a == b,
+        (ValueView::Number(a), ValueView::Number(b)) => numbers_equal(a, b),
+        (ValueView::String(a), ValueView::String(b)) => a == b,
+        _ => false,
+      };
+      if !is_equal {
+        push_change(changes, ChangeKind::Changed, path, Some(old), Some(new));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ParseOptions;
+  use crate::parse_to_ast;
+  use crate::parse_to_value;
+  use pretty_assertions::assert_eq;
+
+  fn parse_ast(text: &str) -> ast::Value<'_> {
+    parse_to_ast(text, &Default::default(), &ParseOptions::default())
+      .unwrap()
+      .value
+      .unwrap()
+  }
+
+  fn summarize<'c, T>(changes: &'c [Change<'_, T>]) -> Vec<(ChangeKind, &'c str)> {
+    changes
+      .iter()
+      .map(|change| (change.kind, change.path.as_str()))
+      .collect()
+  }
+
+  #[test]
+  fn it_should_diff_ast_values() {
+    let old = parse_ast(r#"{ "a": 1, "b": { "c": [1, 2, 3], "d": "x" }, "e/f": null, "g": 0 }"#);
+    let new = parse_ast(
+      r#"{
+  // comments and property order are ignored
+  "g": 0x0,
+  "e/f": {},
+  "b": { "c": [1, 5], "d": "x", "h": true },
+  "i": [],
+}"#,
+    );
+    let changes = diff_ast_values(&old, &new);
+    assert_eq!(
+      summarize(&changes),
+      vec![
+        (ChangeKind::Removed, "/a"),
+        (ChangeKind::Changed, "/b/c/1"),
+        (ChangeKind::Removed, "/b/c/2"),
+        (ChangeKind::Added, "/b/h"),
+        (ChangeKind::Changed, "/e~1f"),
+        (ChangeKind::Added, "/i"),
+      ]
+    );
+    assert_eq!(changes[0].old_range(), Some(Range::new(7, 8)));
+    assert_eq!(changes[0].new_range(), None);
+    assert_eq!(changes[1].old_range(), Some(Range::new(26, 27)));
+    assert_eq!(changes[1].new_range(), Some(Range::new(90, 91)));
+    assert_eq!(
+      changes[3].new,
+      Some(&new.as_object().unwrap().get_object("b").unwrap().properties[2].value)
+    );
+  }
+
+  #[test]
+  fn it_should_diff_root_and_duplicate_keys() {
+    assert_eq!(diff_ast_values(&parse_ast("[1]"), &parse_ast("[1.0]")), vec![]);
+    let old = parse_ast("1");
+    let new = parse_ast("\"1\"");
+    assert_eq!(summarize(&diff_ast_values(&old, &new)), vec![(ChangeKind::Changed, "")]);
+    // last property wins
+    assert_eq!(
+      diff_ast_values(&parse_ast(r#"{ "a": 1, "a": 2 }"#), &parse_ast(r#"{ "a": 2 }"#)),
+      vec![]
+    );
+    assert_eq!(
+      summarize(&diff_ast_values(
+        &parse_ast(r#"{ "a": 1 }"#),
+        &parse_ast(r#"{ "a": 1, "a": 2 }"#)
+      )),
+      vec![(ChangeKind::Changed, "/a")]
+    );
+  }
+
+  #[test]
+  fn it_should_diff_json_values() {
+    let old = parse_to_value(r#"{ "a": [1, { "b": null }] }"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    let new = parse_to_value(r#"{ "a": [1, { "b": false }, "c"] }"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    let changes = diff_values(&old, &new);
+    assert_eq!(
+      summarize(&changes),
+      vec![(ChangeKind::Changed, "/a/1/b"), (ChangeKind::Added, "/a/2")]
+    );
+    assert_eq!(changes[0].old, Some(&JsonValue::Null));
+    assert_eq!(changes[0].new, Some(&JsonValue::Boolean(false)));
+    assert_eq!(diff_values(&new, &new.clone()), vec![]);
+  }
+
+  #[test]
+  fn it_should_diff_large_integers_exactly() {
+    let old = parse_to_value(r#"{ "id": 9007199254740992, "a": 1 }"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    let new = parse_to_value(r#"{ "id": 9007199254740993, "a": 1.0 }"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    assert_eq!(summarize(&diff_values(&old, &new)), vec![(ChangeKind::Changed, "/id")]);
+  }
+
+  #[cfg(feature = "cst")]
+  #[test]
+  fn it_should_convert_to_patch_operations() {
+    use crate::cst::CstRootNode;
+
+    let old_text = r#"{
+  // comment
+  "a": [1, 2, 3, 4],
+  "b": { "c": 1 },
+  "d": true
+}"#;
+    let new_text = r#"{ "a": [0, 2], "b": { "c": 1, "e": ["f"] }, "g": null }"#;
+    let old = parse_ast(old_text);
+    let new = parse_ast(new_text);
+    let operations = diff_ast_values(&old, &new)
+      .iter()
+      .map(Change::to_patch_operation)
+      .collect::<Vec<_>>();
+    let root = CstRootNode::parse(old_text, &ParseOptions::default()).unwrap();
+    root.apply_patch(&operations).unwrap();
+    assert_eq!(
+      root.to_string(),
+      r#"{
+  // comment
+  "a": [0, 2],
+  "b": {
+    "c": 1,
+    "e": ["f"]
+  },
+  "g": null
+}"#
+    );
+    assert_eq!(diff_ast_values(&parse_ast(&root.to_string()), &new), vec![]);
+  }
+}
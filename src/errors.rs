@@ -3,10 +3,13 @@ use std::fmt;
 use crate::ParseStringErrorKind;
 
 use super::common::Range;
+use super::common::Ranged;
+use super::common::char_display_width;
 
 #[derive(Debug)]
 pub enum ParseErrorKind {
   CommentsNotAllowed,
+  DuplicateObjectKey(String),
   ExpectedColonAfterObjectKey,
   ExpectedObjectValue,
   ExpectedDigit,
@@ -14,7 +17,9 @@ pub enum ParseErrorKind {
   ExpectedPlusMinusOrDigitInNumberLiteral,
   ExpectedStringObjectProperty,
   HexadecimalNumbersNotAllowed,
+  LeadingZerosNotAllowed,
   ExpectedComma,
+  ExpectedNewline,
   MultipleRootJsonValues,
   SingleQuotedStringsNotAllowed,
   String(ParseStringErrorKind),
@@ -31,6 +36,8 @@ pub enum ParseErrorKind {
   UnterminatedCommentBlock,
   UnterminatedObject,
   NestingDepthExceeded,
+  /// Error reading from the underlying reader or the text wasn't valid UTF-8.
+  Io(std::io::Error),
   /// Custom error message, used by the serde deserializer.
   #[cfg(feature = "serde")]
   Custom(String),
@@ -43,6 +50,9 @@ impl std::fmt::Display for ParseErrorKind {
       CommentsNotAllowed => {
         write!(f, "Comments are not allowed")
       }
+      DuplicateObjectKey(name) => {
+        write!(f, "Duplicate object key '{}'", name)
+      }
       ExpectedColonAfterObjectKey => {
         write!(f, "Expected colon after the string or word in object property")
       }
@@ -64,9 +74,15 @@ impl std::fmt::Display for ParseErrorKind {
       HexadecimalNumbersNotAllowed => {
         write!(f, "Hexadecimal numbers are not allowed")
       }
+      LeadingZerosNotAllowed => {
+        write!(f, "Leading zeros on numbers are not allowed")
+      }
       ExpectedComma => {
         write!(f, "Expected comma")
       }
+      ExpectedNewline => {
+        write!(f, "Expected newline between values")
+      }
       MultipleRootJsonValues => {
         write!(f, "Text cannot contain more than one JSON value")
       }
@@ -113,6 +129,7 @@ impl std::fmt::Display for ParseErrorKind {
       NestingDepthExceeded => {
         write!(f, "Maximum nesting depth exceeded")
       }
+      Io(err) => write!(f, "{}", err),
       #[cfg(feature = "serde")]
       Custom(msg) => write!(f, "{}", msg),
     }
@@ -131,11 +148,26 @@ struct ParseErrorInner {
 #[derive(Debug)]
 pub struct ParseError(Box<ParseErrorInner>);
 
+impl Ranged for ParseError {
+  fn range(&self) -> Range {
+    self.0.range
+  }
+}
+
 impl std::error::Error for ParseError {}
 
 impl ParseError {
   pub(crate) fn new(range: Range, kind: ParseErrorKind, file_text: &str) -> ParseError {
     let (line_display, column_display) = get_line_and_column_display(range, file_text);
+    ParseError::new_with_line_and_column(range, line_display, column_display, kind)
+  }
+
+  pub(crate) fn new_with_line_and_column(
+    range: Range,
+    line_display: usize,
+    column_display: usize,
+    kind: ParseErrorKind,
+  ) -> ParseError {
     ParseError(Box::new(ParseErrorInner {
       range,
       line_display,
@@ -144,6 +176,17 @@ impl ParseError {
     }))
   }
 
+  /// Moves an error created for a slice of the text to the position of the
+  /// slice, which starts at the provided byte offset, line index, and column index.
+  pub(crate) fn offset_by(mut self, offset: usize, line_index: usize, column_index: usize) -> ParseError {
+    self.0.range = Range::new(self.0.range.start + offset, self.0.range.end + offset);
+    if self.0.line_display == 1 {
+      self.0.column_display += column_index;
+    }
+    self.0.line_display += line_index;
+    self
+  }
+
   /// Start and end position of the error.
   pub fn range(&self) -> Range {
     self.0.range
@@ -158,7 +201,8 @@ impl ParseError {
   ///
   /// Note: Use the `error_unicode_width` feature to get the correct column
   /// number for Unicode characters on the line, otherwise this is just the
-  /// number of characters by default.
+  /// number of characters by default. Use `Ranged::range_line_and_column` for
+  /// other units such as UTF-16 code units.
   pub fn column_display(&self) -> usize {
     self.0.column_display
   }
@@ -168,6 +212,11 @@ impl ParseError {
     &self.0.kind
   }
 
+  #[cfg(feature = "serde")]
+  pub(crate) fn into_kind(self) -> ParseErrorKind {
+    self.0.kind
+  }
+
   /// Creates an error with a custom message and no position info.
   #[cfg(feature = "serde")]
   pub(crate) fn custom_err(msg: String) -> ParseError {
@@ -181,15 +230,31 @@ impl ParseError {
 
   /// Attaches position info to an error that doesn't have any yet.
   #[cfg(feature = "serde")]
-  pub(crate) fn with_position(mut self, range: Range, file_text: &str) -> ParseError {
-    if self.0.range.start == 0 && self.0.range.end == 0 {
+  pub(crate) fn with_position(self, range: Range, file_text: &str) -> ParseError {
+    if self.has_position() {
+      self
+    } else {
       let (line_display, column_display) = get_line_and_column_display(range, file_text);
+      self.with_line_and_column(range, line_display, column_display)
+    }
+  }
+
+  /// Attaches position info with an already known line and column to an
+  /// error that doesn't have any yet.
+  #[cfg(feature = "serde")]
+  pub(crate) fn with_line_and_column(mut self, range: Range, line_display: usize, column_display: usize) -> ParseError {
+    if !self.has_position() {
       self.0.range = range;
       self.0.line_display = line_display;
       self.0.column_display = column_display;
     }
     self
   }
+
+  #[cfg(feature = "serde")]
+  pub(crate) fn has_position(&self) -> bool {
+    self.0.range.start != 0 || self.0.range.end != 0
+  }
 }
 
 impl fmt::Display for ParseError {
@@ -203,25 +268,49 @@ impl fmt::Display for ParseError {
   }
 }
 
-fn get_line_and_column_display(range: Range, file_text: &str) -> (usize, usize) {
-  let mut line_index = 0;
-  let mut column_index = 0;
-  for c in file_text[..range.start].chars() {
+/// Error that could occur while serializing to JSONC text.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum SerializeError {
+  /// Error writing to the underlying writer.
+  Io(std::io::Error),
+  /// An object key was not a string, char, or integer.
+  KeyMustBeAString,
+  /// Custom error message from a `serde::Serialize` implementation.
+  Custom(String),
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for SerializeError {}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SerializeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SerializeError::Io(err) => write!(f, "{}", err),
+      SerializeError::KeyMustBeAString => write!(f, "Object keys must be strings"),
+      SerializeError::Custom(msg) => write!(f, "{}", msg),
+    }
+  }
+}
+
+pub(crate) fn get_line_and_column_display(range: Range, file_text: &str) -> (usize, usize) {
+  let (line_index, column_index) = advance_line_and_column_index((0, 0), &file_text[..range.start]);
+  (line_index + 1, column_index + 1)
+}
+
+/// Moves the 0-indexed line and column past the provided text.
+pub(crate) fn advance_line_and_column_index(
+  (mut line_index, mut column_index): (usize, usize),
+  text: &str,
+) -> (usize, usize) {
+  for c in text.chars() {
     if c == '\n' {
       line_index += 1;
       column_index = 0;
     } else {
-      #[cfg(feature = "error_unicode_width")]
-      {
-        if let Some(width) = unicode_width::UnicodeWidthChar::width_cjk(c) {
-          column_index += width;
-        }
-      }
-      #[cfg(not(feature = "error_unicode_width"))]
-      {
-        column_index += 1;
-      }
+      column_index += char_display_width(c);
     }
   }
-  (line_index + 1, column_index + 1)
+  (line_index, column_index)
 }
@@ -33,6 +33,14 @@
 //! # }
 //! ```
 //!
+//! For editor scenarios where the text is often incomplete, `parse_to_ast_recovering` continues
+//! past errors and returns a best effort AST along with all the errors found.
+//!
+//! To read a document one event at a time without building a tree, use `PullParser`.
+//!
+//! Number literals are kept as text. Convert them with methods such as `as_i64`, `as_f64`, or `as_decimal`,
+//! which understand hexadecimal and the other number syntaxes the parser may accept.
+//!
 //! ## Manipulation (CST)
 //!
 //! When enabling the `cst` cargo feature, parsing to a CST provides a first class manipulation API:
@@ -96,10 +104,24 @@
 //! # }
 //! ```
 //!
+//! Use `parse_to_serde_value_borrowed` instead to deserialize types with `&str` or `Cow<str>`
+//! fields that borrow from the text.
+//! To deserialize from a `std::io::Read` without loading the full text into memory, use `from_reader`.
+//!
+//! Values implementing `serde::Serialize` can be written back out as JSONC text with
+//! `to_jsonc_string` or `to_jsonc_writer`, which accept `SerializeOptions` for the indentation,
+//! newline kind, trailing commas, and quote kind.
+//!
+//! Wrap a field in `Spanned<T>` to also capture the range, line, and column of its value in the text.
+//!
+//! Integers that don't fit in 64 bits can be deserialized to `i128` and `u128`. To deserialize numbers
+//! to `serde_json::Value` without losing precision, enable the `arbitrary_precision` cargo feature,
+//! which also enables serde_json's feature of the same name.
+//!
 //! ## Parse Strictly as JSON
 //!
 //! By default this library is extremely loose in what it allows parsing. To be strict,
-//! provide `ParseOptions` and set all the options to false:
+//! provide `ParseOptions::strict_json()`:
 //!
 //! ```
 //! use jsonc_parser::parse_to_value;
@@ -107,15 +129,25 @@
 //!
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! # let text = "{}";
-//! let json_value = parse_to_value(text, &ParseOptions {
-//!   allow_comments: false,
-//!   allow_loose_object_property_names: false,
-//!   allow_trailing_commas: false,
-//!   allow_missing_commas: false,
-//!   allow_single_quoted_strings: false,
-//!   allow_hexadecimal_numbers: false,
-//!   allow_unary_plus_numbers: false,
-//! })?;
+//! let json_value = parse_to_value(text, &ParseOptions::strict_json())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! There are also presets for other common formats, such as `ParseOptions::jsonc()`,
+//! `ParseOptions::vscode_settings()`, and `ParseOptions::tsconfig()`.
+//!
+//! ## JSON5
+//!
+//! To parse [JSON5](https://spec.json5.org/), which additionally allows `Infinity`, `NaN`, leading
+//! and trailing decimal points, line continuations in strings, and `\x` escapes, use `ParseOptions::json5()`:
+//!
+//! ```
+//! use jsonc_parser::parse_to_value;
+//! use jsonc_parser::ParseOptions;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let json_value = parse_to_value("{ ratio: .5, max: Infinity, }", &ParseOptions::json5())?;
 //! # Ok(())
 //! # }
 //! ```
@@ -150,11 +182,17 @@ pub mod ast;
 pub mod common;
 #[cfg(feature = "cst")]
 pub mod cst;
+pub mod diff;
 pub mod errors;
 pub mod map;
+pub mod number;
 mod parse_to_ast;
 mod parse_to_value;
+mod parse_values_iter;
 mod parser;
+mod pointer;
+mod pull_parser;
+mod reader_scanner;
 mod scanner;
 #[cfg(feature = "serde")]
 mod serde;
@@ -165,6 +203,9 @@ mod value;
 pub use map::Map;
 pub use parse_to_ast::*;
 pub use parse_to_value::*;
+pub use parse_values_iter::*;
+pub use pull_parser::*;
+pub use reader_scanner::ReaderScanner;
 pub use scanner::*;
 pub use string::ParseStringErrorKind;
 pub use value::*;
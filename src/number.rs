@@ -0,0 +1,634 @@
+//! Conversions of number literals to Rust numbers.
+//!
+//! These understand every syntax the scanner may accept, such as hexadecimal
+//! numbers, unary plus, leading or trailing decimal points, and `Infinity`
+//! or `NaN`.
+
+use std::fmt;
+
+/// An error converting a number literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumberConversionError {
+  /// The text is not a number literal.
+  Invalid,
+  /// The number is outside the range of the type.
+  Overflow,
+  /// The number can't be represented by the type without losing precision,
+  /// such as a fractional number converted to an integer.
+  PrecisionLoss,
+  /// The number is `Infinity` or `NaN`, which the type can't represent.
+  NotFinite,
+}
+
+impl std::error::Error for NumberConversionError {}
+
+impl fmt::Display for NumberConversionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      NumberConversionError::Invalid => write!(f, "Invalid number"),
+      NumberConversionError::Overflow => write!(f, "Number is out of range"),
+      NumberConversionError::PrecisionLoss => write!(f, "Number cannot be represented without losing precision"),
+      NumberConversionError::NotFinite => write!(f, "Number is not finite"),
+    }
+  }
+}
+
+/// An exact decimal number, which is `digits * 10^exponent`.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::parse_to_value;
+///
+/// let value = parse_to_value("-1.250e2", &Default::default()).unwrap().unwrap();
+/// let decimal = value.as_decimal().unwrap();
+/// assert!(decimal.is_negative());
+/// assert_eq!(decimal.digits(), "125");
+/// assert_eq!(decimal.exponent(), 0);
+/// assert_eq!(decimal.to_string(), "-125");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decimal {
+  is_negative: bool,
+  digits: String,
+  exponent: i64,
+}
+
+impl Decimal {
+  /// If the number is below zero.
+  pub fn is_negative(&self) -> bool {
+    self.is_negative
+  }
+
+  /// If the number is zero.
+  pub fn is_zero(&self) -> bool {
+    self.digits == "0"
+  }
+
+  /// The significant digits without leading or trailing zeros (`"0"` for zero).
+  pub fn digits(&self) -> &str {
+    &self.digits
+  }
+
+  /// The power of ten the digits are multiplied by.
+  pub fn exponent(&self) -> i64 {
+    self.exponent
+  }
+
+  fn new(is_negative: bool, digits: &str, exponent: i64) -> Result<Self, NumberConversionError> {
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    if trimmed.is_empty() {
+      return Ok(Decimal {
+        is_negative: false,
+        digits: "0".to_string(),
+        exponent: 0,
+      });
+    }
+    let trailing_zeros = (digits.len() - trimmed.len()) as i64;
+    Ok(Decimal {
+      is_negative,
+      digits: trimmed.to_string(),
+      exponent: exponent
+        .checked_add(trailing_zeros)
+        .ok_or(NumberConversionError::Overflow)?,
+    })
+  }
+
+  fn magnitude(&self) -> Result<u128, NumberConversionError> {
+    if self.is_zero() {
+      return Ok(0);
+    }
+    if self.exponent < 0 {
+      return Err(NumberConversionError::PrecisionLoss);
+    }
+    // u128::MAX has 39 digits
+    if (self.digits.len() as i64).saturating_add(self.exponent) > 39 {
+      return Err(NumberConversionError::Overflow);
+    }
+    let mut value = 0u128;
+    let zeros = std::iter::repeat_n(b'0', self.exponent as usize);
+    for digit in self.digits.bytes().chain(zeros) {
+      value = value
+        .checked_mul(10)
+        .and_then(|value| value.checked_add((digit - b'0') as u128))
+        .ok_or(NumberConversionError::Overflow)?;
+    }
+    Ok(value)
+  }
+
+  fn to_i128(&self) -> Result<i128, NumberConversionError> {
+    let magnitude = self.magnitude()?;
+    if self.is_negative {
+      if magnitude > i128::MIN.unsigned_abs() {
+        return Err(NumberConversionError::Overflow);
+      }
+      Ok((magnitude as i128).wrapping_neg())
+    } else {
+      i128::try_from(magnitude).map_err(|_| NumberConversionError::Overflow)
+    }
+  }
+
+  fn to_u128(&self) -> Result<u128, NumberConversionError> {
+    if self.is_negative {
+      return Err(NumberConversionError::Overflow);
+    }
+    self.magnitude()
+  }
+}
+
+impl fmt::Display for Decimal {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // uses plain notation unless it would need more than this many added zeros
+    const MAX_PLAIN_ZEROS: i64 = 20;
+
+    if self.is_negative {
+      write!(f, "-")?;
+    }
+    let digit_count = self.digits.len() as i64;
+    let point_index = digit_count.saturating_add(self.exponent);
+    if (0..=MAX_PLAIN_ZEROS).contains(&self.exponent) {
+      write!(f, "{}", self.digits)?;
+      for _ in 0..self.exponent {
+        write!(f, "0")?;
+      }
+      Ok(())
+    } else if self.exponent < 0 && point_index > 0 {
+      let point_index = point_index as usize;
+      write!(f, "{}.{}", &self.digits[..point_index], &self.digits[point_index..])
+    } else if self.exponent < 0 && point_index >= -MAX_PLAIN_ZEROS {
+      write!(f, "0.")?;
+      for _ in 0..-point_index {
+        write!(f, "0")?;
+      }
+      write!(f, "{}", self.digits)
+    } else {
+      write!(f, "{}", &self.digits[..1])?;
+      if digit_count > 1 {
+        write!(f, ".{}", &self.digits[1..])?;
+      }
+      write!(f, "e{}", point_index - 1)
+    }
+  }
+}
+
+enum ParsedNumber {
+  Finite(Decimal),
+  Infinity,
+  NaN,
+}
+
+fn split_sign(text: &str) -> (bool, &str) {
+  match text.as_bytes().first() {
+    Some(b'-') => (true, &text[1..]),
+    Some(b'+') => (false, &text[1..]),
+    _ => (false, text),
+  }
+}
+
+fn parse(raw: &str) -> Result<(ParsedNumber, bool), NumberConversionError> {
+  let (is_negative, text) = split_sign(raw);
+  let number = match text {
+    "Infinity" => ParsedNumber::Infinity,
+    "NaN" => ParsedNumber::NaN,
+    _ => match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+      Some(hex_digits) => ParsedNumber::Finite(Decimal::new(is_negative, &hex_to_decimal_digits(hex_digits)?, 0)?),
+      None => ParsedNumber::Finite(parse_decimal(is_negative, text)?),
+    },
+  };
+  Ok((number, is_negative))
+}
+
+fn hex_to_decimal_digits(hex_digits: &str) -> Result<String, NumberConversionError> {
+  if hex_digits.is_empty() {
+    return Err(NumberConversionError::Invalid);
+  }
+  // little endian decimal digits
+  let mut digits = vec![0u8];
+  for c in hex_digits.chars() {
+    let mut carry = c.to_digit(16).ok_or(NumberConversionError::Invalid)?;
+    for digit in digits.iter_mut() {
+      let value = *digit as u32 * 16 + carry;
+      *digit = (value % 10) as u8;
+      carry = value / 10;
+    }
+    while carry > 0 {
+      digits.push((carry % 10) as u8);
+      carry /= 10;
+    }
+  }
+  Ok(digits.iter().rev().map(|digit| (b'0' + digit) as char).collect())
+}
+
+fn parse_decimal(is_negative: bool, text: &str) -> Result<Decimal, NumberConversionError> {
+  let (mantissa, exponent) = match text.find(['e', 'E']) {
+    Some(index) => (&text[..index], parse_exponent(&text[index + 1..])?),
+    None => (text, 0),
+  };
+  let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+  let is_digits = |text: &str| text.bytes().all(|b| b.is_ascii_digit());
+  if integer.is_empty() && fraction.is_empty() || !is_digits(integer) || !is_digits(fraction) {
+    return Err(NumberConversionError::Invalid);
+  }
+  let digits = format!("{}{}", integer, fraction);
+  let exponent = exponent
+    .checked_sub(fraction.len() as i64)
+    .ok_or(NumberConversionError::Overflow)?;
+  Decimal::new(is_negative, &digits, exponent)
+}
+
+fn parse_exponent(text: &str) -> Result<i64, NumberConversionError> {
+  let (is_negative, digits) = split_sign(text);
+  if digits.is_empty() {
+    return Err(NumberConversionError::Invalid);
+  }
+  let mut value = 0i64;
+  for b in digits.bytes() {
+    if !b.is_ascii_digit() {
+      return Err(NumberConversionError::Invalid);
+    }
+    // accumulate with the sign so i64::MIN can be represented
+    let digit = (b - b'0') as i64;
+    value = value
+      .checked_mul(10)
+      .and_then(|value| {
+        if is_negative {
+          value.checked_sub(digit)
+        } else {
+          value.checked_add(digit)
+        }
+      })
+      .ok_or(NumberConversionError::Overflow)?;
+  }
+  Ok(value)
+}
+
+fn parse_finite(raw: &str) -> Result<Decimal, NumberConversionError> {
+  match parse(raw)?.0 {
+    ParsedNumber::Finite(decimal) => Ok(decimal),
+    ParsedNumber::Infinity | ParsedNumber::NaN => Err(NumberConversionError::NotFinite),
+  }
+}
+
+pub(crate) fn to_decimal(raw: &str) -> Result<Decimal, NumberConversionError> {
+  parse_finite(raw)
+}
+
+pub(crate) fn to_i128(raw: &str) -> Result<i128, NumberConversionError> {
+  parse_finite(raw)?.to_i128()
+}
+
+pub(crate) fn to_u128(raw: &str) -> Result<u128, NumberConversionError> {
+  parse_finite(raw)?.to_u128()
+}
+
+pub(crate) fn to_i64(raw: &str) -> Result<i64, NumberConversionError> {
+  i64::try_from(to_i128(raw)?).map_err(|_| NumberConversionError::Overflow)
+}
+
+pub(crate) fn to_u64(raw: &str) -> Result<u64, NumberConversionError> {
+  u64::try_from(to_u128(raw)?).map_err(|_| NumberConversionError::Overflow)
+}
+
+/// Gets if the literal has no fractional part or exponent, such as `5` or `0x1F`.
+#[cfg(feature = "serde")]
+pub(crate) fn is_integer_literal(raw: &str) -> bool {
+  let unsigned = raw.trim_start_matches(['-', '+']);
+  unsigned.starts_with("0x") || unsigned.starts_with("0X") || !unsigned.contains(['.', 'e', 'E'])
+}
+
+pub(crate) fn to_f64(raw: &str) -> Result<f64, NumberConversionError> {
+  let value = to_closest_f64(raw)?;
+  // unlike fractions such as 0.1, integers are expected to be exact however
+  // they're written (ex. `9007199254740993.0` or `1e23`)
+  if let Ok(decimal) = to_decimal(raw)
+    && decimal.exponent >= 0
+    && to_decimal(&format!("{:.0}", value)).as_ref() != Ok(&decimal)
+  {
+    return Err(NumberConversionError::PrecisionLoss);
+  }
+  Ok(value)
+}
+
+/// Converts the number to the closest `f64` even when it's an integer that
+/// can't be represented exactly, as serde_json does when deserializing.
+pub(crate) fn to_closest_f64(raw: &str) -> Result<f64, NumberConversionError> {
+  let (number, is_negative) = match parse(raw) {
+    // an exponent below the range of an i64 is far too small for an f64
+    Err(NumberConversionError::Overflow) if raw.contains("e-") || raw.contains("E-") => {
+      return Err(NumberConversionError::PrecisionLoss);
+    }
+    result => result?,
+  };
+  let decimal = match number {
+    ParsedNumber::Finite(decimal) => decimal,
+    ParsedNumber::Infinity => return Ok(if is_negative { f64::NEG_INFINITY } else { f64::INFINITY }),
+    ParsedNumber::NaN => return Ok(f64::NAN),
+  };
+  if decimal.is_zero() {
+    return Ok(if is_negative { -0.0 } else { 0.0 });
+  }
+  let value = format!("{}e{}", decimal.digits, decimal.exponent)
+    .parse::<f64>()
+    .map_err(|_| NumberConversionError::Invalid)?;
+  if value.is_infinite() {
+    Err(NumberConversionError::Overflow)
+  } else if value == 0.0 {
+    Err(NumberConversionError::PrecisionLoss)
+  } else {
+    Ok(if is_negative { -value } else { value })
+  }
+}
+
+/// Converts the number to text that's a valid JSON number without changing its precision.
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) fn to_json_text(raw: &str) -> Result<String, NumberConversionError> {
+  let (is_negative, text) = split_sign(raw);
+  let sign = if is_negative { "-" } else { "" };
+  if text == "Infinity" || text == "NaN" {
+    return Err(NumberConversionError::NotFinite);
+  }
+  if let Some(hex_digits) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+    return Ok(format!("{}{}", sign, hex_to_decimal_digits(hex_digits)?));
+  }
+
+  let (mantissa, exponent) = match text.find(['e', 'E']) {
+    Some(index) => {
+      parse_exponent(&text[index + 1..])?;
+      (&text[..index], &text[index..])
+    }
+    None => (text, ""),
+  };
+  let (integer, fraction) = match mantissa.split_once('.') {
+    Some((integer, fraction)) => (integer, Some(fraction)),
+    None => (mantissa, None),
+  };
+  let is_digits = |text: &str| text.bytes().all(|b| b.is_ascii_digit());
+  if integer.is_empty() && fraction.is_none_or(str::is_empty) || !is_digits(integer) || !fraction.is_none_or(is_digits)
+  {
+    return Err(NumberConversionError::Invalid);
+  }
+  let integer = match integer.trim_start_matches('0') {
+    "" => "0",
+    integer => integer,
+  };
+  Ok(match fraction {
+    Some("") => format!("{}{}.0{}", sign, integer, exponent),
+    Some(fraction) => format!("{}{}.{}{}", sign, integer, fraction, exponent),
+    None => format!("{}{}{}", sign, integer, exponent),
+  })
+}
+
+/// Implements the number conversion methods on a type given an expression
+/// that gets the raw number text.
+macro_rules! impl_number_conversions {
+  ($type:ty, |$self:ident| $raw:expr) => {
+    impl $type {
+      /// Converts the number to an `i64`.
+      ///
+      /// Errors when it's out of range, has a fractional part, or isn't finite.
+      pub fn as_i64(&$self) -> Result<i64, $crate::number::NumberConversionError> {
+        $crate::number::to_i64($raw)
+      }
+
+      /// Converts the number to a `u64`.
+      ///
+      /// Errors when it's out of range, has a fractional part, or isn't finite.
+      pub fn as_u64(&$self) -> Result<u64, $crate::number::NumberConversionError> {
+        $crate::number::to_u64($raw)
+      }
+
+      /// Converts the number to an `i128`.
+      ///
+      /// Errors when it's out of range, has a fractional part, or isn't finite.
+      pub fn as_i128(&$self) -> Result<i128, $crate::number::NumberConversionError> {
+        $crate::number::to_i128($raw)
+      }
+
+      /// Converts the number to a `u128`.
+      ///
+      /// Errors when it's out of range, has a fractional part, or isn't finite.
+      pub fn as_u128(&$self) -> Result<u128, $crate::number::NumberConversionError> {
+        $crate::number::to_u128($raw)
+      }
+
+      /// Converts the number to the closest `f64`.
+      ///
+      /// Errors when it's too large for an `f64`, is so small it would become
+      /// zero, or is an integer that an `f64` can't represent exactly (ex.
+      /// `9007199254740993` or `1e23`). Use `as_decimal` for the exact value.
+      pub fn as_f64(&$self) -> Result<f64, $crate::number::NumberConversionError> {
+        $crate::number::to_f64($raw)
+      }
+
+      /// Converts the number to an exact `Decimal`.
+      ///
+      /// Errors when it's not finite or its exponent is outside the range of an `i64`.
+      pub fn as_decimal(&$self) -> Result<$crate::number::Decimal, $crate::number::NumberConversionError> {
+        $crate::number::to_decimal($raw)
+      }
+    }
+  };
+}
+
+pub(crate) use impl_number_conversions;
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn it_should_convert_integers() {
+    assert_eq!(to_i64("123"), Ok(123));
+    assert_eq!(to_i64("-123"), Ok(-123));
+    assert_eq!(to_i64("+123"), Ok(123));
+    assert_eq!(to_i64("1.5e1"), Ok(15));
+    assert_eq!(to_i64("1500e-2"), Ok(15));
+    assert_eq!(to_i64("5."), Ok(5));
+    assert_eq!(to_i64("-0"), Ok(0));
+    assert_eq!(to_i64("0xFF"), Ok(255));
+    assert_eq!(to_i64("-0x7fffffffffffffff"), Ok(-i64::MAX));
+    assert_eq!(to_i64("9223372036854775807"), Ok(i64::MAX));
+    assert_eq!(to_i64("-9223372036854775808"), Ok(i64::MIN));
+    assert_eq!(to_i64("9223372036854775808"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_i64("1.5"), Err(NumberConversionError::PrecisionLoss));
+    assert_eq!(to_i64(".5"), Err(NumberConversionError::PrecisionLoss));
+    assert_eq!(to_i64("Infinity"), Err(NumberConversionError::NotFinite));
+    assert_eq!(to_i64("NaN"), Err(NumberConversionError::NotFinite));
+    assert_eq!(to_i64("abc"), Err(NumberConversionError::Invalid));
+    assert_eq!(to_i64(""), Err(NumberConversionError::Invalid));
+
+    assert_eq!(to_u64("18446744073709551615"), Ok(u64::MAX));
+    assert_eq!(to_u64("0xFFFFFFFFFFFFFFFF"), Ok(u64::MAX));
+    assert_eq!(to_u64("18446744073709551616"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_u64("-1"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_u64("-0"), Ok(0));
+
+    assert_eq!(to_i128("-170141183460469231731687303715884105728"), Ok(i128::MIN));
+    assert_eq!(
+      to_i128("170141183460469231731687303715884105728"),
+      Err(NumberConversionError::Overflow)
+    );
+    assert_eq!(to_u128("340282366920938463463374607431768211455"), Ok(u128::MAX));
+    assert_eq!(to_u128("0xffffffffffffffffffffffffffffffff"), Ok(u128::MAX));
+    assert_eq!(
+      to_u128("340282366920938463463374607431768211456"),
+      Err(NumberConversionError::Overflow)
+    );
+    assert_eq!(to_u128("1e39"), Err(NumberConversionError::Overflow));
+    assert_eq!(
+      to_u128("1e9999999999999999999999"),
+      Err(NumberConversionError::Overflow)
+    );
+  }
+
+  #[test]
+  fn it_should_convert_floats() {
+    assert_eq!(to_f64("1.5"), Ok(1.5));
+    assert_eq!(to_f64("-.5"), Ok(-0.5));
+    assert_eq!(to_f64("+5.e2"), Ok(500.0));
+    assert_eq!(to_f64("0x10"), Ok(16.0));
+    assert_eq!(to_f64("0.1"), Ok(0.1));
+    assert_eq!(to_f64("-0").map(|value| value.is_sign_negative()), Ok(true));
+    assert_eq!(to_f64("0e-99999"), Ok(0.0));
+    assert_eq!(to_f64("-Infinity"), Ok(f64::NEG_INFINITY));
+    assert!(to_f64("NaN").unwrap().is_nan());
+    assert_eq!(to_f64("1e400"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_f64("1e-400"), Err(NumberConversionError::PrecisionLoss));
+    assert_eq!(to_f64("1.2.3"), Err(NumberConversionError::Invalid));
+    assert_eq!(to_f64("9007199254740992"), Ok(9007199254740992.0));
+    assert_eq!(to_f64("-9007199254740993"), Err(NumberConversionError::PrecisionLoss));
+    assert_eq!(
+      to_f64("0x1000000000000000000000000000000001"),
+      Err(NumberConversionError::PrecisionLoss)
+    );
+    assert_eq!(to_f64("0x10000000000000000"), Ok(18446744073709551616.0));
+    assert_eq!(to_f64("9007199254740993.0"), Err(NumberConversionError::PrecisionLoss));
+    assert_eq!(
+      to_f64("90071992547409930e-1"),
+      Err(NumberConversionError::PrecisionLoss)
+    );
+    assert_eq!(to_f64("1e23"), Err(NumberConversionError::PrecisionLoss));
+    assert_eq!(to_f64("1e22"), Ok(1e22));
+    assert_eq!(to_f64("9007199254740992.0"), Ok(9007199254740992.0));
+    assert_eq!(to_f64("-1.5e1"), Ok(-15.0));
+    assert_eq!(to_closest_f64("9007199254740993"), Ok(9007199254740992.0));
+    assert_eq!(to_closest_f64("1e23"), Ok(1e23));
+  }
+
+  #[test]
+  fn it_should_convert_decimals() {
+    fn decimal(raw: &str) -> String {
+      to_decimal(raw).unwrap().to_string()
+    }
+
+    assert_eq!(decimal("0"), "0");
+    assert_eq!(decimal("-0.0"), "0");
+    assert_eq!(decimal("123.4500"), "123.45");
+    assert_eq!(decimal("-00123"), "-123");
+    assert_eq!(decimal("1e2"), "100");
+    assert_eq!(decimal(".5"), "0.5");
+    assert_eq!(decimal("0.00012"), "0.00012");
+    assert_eq!(decimal("12345e-2"), "123.45");
+    assert_eq!(decimal("1.5e30"), "1.5e30");
+    assert_eq!(decimal("-12e-40"), "-1.2e-39");
+    assert_eq!(
+      decimal("0x100000000000000000000000000000000"),
+      "340282366920938463463374607431768211456"
+    );
+    assert_eq!(to_decimal("NaN"), Err(NumberConversionError::NotFinite));
+
+    let value = to_decimal("1.50e-3").unwrap();
+    assert_eq!(
+      (value.is_negative(), value.digits(), value.exponent()),
+      (false, "15", -4)
+    );
+    assert_eq!(to_decimal("1.5"), to_decimal("15e-1"));
+  }
+
+  #[test]
+  fn it_should_error_for_exponents_out_of_range() {
+    let value = to_decimal("1e9223372036854775807").unwrap();
+    assert_eq!((value.digits(), value.exponent()), ("1", i64::MAX));
+    let value = to_decimal("1e-9223372036854775808").unwrap();
+    assert_eq!((value.digits(), value.exponent()), ("1", i64::MIN));
+    assert_eq!(
+      to_decimal("1e9223372036854775808"),
+      Err(NumberConversionError::Overflow)
+    );
+    assert_eq!(
+      to_decimal("1e-9223372036854775809"),
+      Err(NumberConversionError::Overflow)
+    );
+    // the trailing zero or fraction digit moves the exponent out of range
+    assert_eq!(
+      to_decimal("10e9223372036854775807"),
+      Err(NumberConversionError::Overflow)
+    );
+    assert_eq!(
+      to_decimal("0.1e-9223372036854775808"),
+      Err(NumberConversionError::Overflow)
+    );
+
+    assert_eq!(to_u128("1e9223372036854775807"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_i64("1e9223372036854775808"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_f64("1e9223372036854775807"), Err(NumberConversionError::Overflow));
+    assert_eq!(to_f64("1e9223372036854775808"), Err(NumberConversionError::Overflow));
+    assert_eq!(
+      to_f64("1e-9223372036854775809"),
+      Err(NumberConversionError::PrecisionLoss)
+    );
+  }
+
+  #[cfg(feature = "arbitrary_precision")]
+  #[test]
+  fn it_should_convert_to_json_text() {
+    assert_eq!(to_json_text("123").unwrap(), "123");
+    assert_eq!(to_json_text("+1.50E+3").unwrap(), "1.50E+3");
+    assert_eq!(to_json_text("-.5").unwrap(), "-0.5");
+    assert_eq!(to_json_text("5.e3").unwrap(), "5.0e3");
+    assert_eq!(to_json_text("007").unwrap(), "7");
+    assert_eq!(to_json_text("-0x1F").unwrap(), "-31");
+    assert_eq!(
+      to_json_text("0x100000000000000000000000000000000").unwrap(),
+      "340282366920938463463374607431768211456"
+    );
+    assert_eq!(to_json_text("Infinity"), Err(NumberConversionError::NotFinite));
+    assert_eq!(to_json_text("1e"), Err(NumberConversionError::Invalid));
+  }
+
+  #[test]
+  fn it_should_convert_each_representation() {
+    let text = "[0x1F, 1.5, \"text\"]";
+    let value = crate::parse_to_value(text, &Default::default()).unwrap().unwrap();
+    assert_eq!(value.pointer("/0").unwrap().as_u64(), Ok(31));
+    assert_eq!(value.pointer("/1").unwrap().as_f64(), Ok(1.5));
+    assert_eq!(
+      value.pointer("/2").unwrap().as_i64(),
+      Err(NumberConversionError::Invalid)
+    );
+
+    let ast = crate::parse_to_ast(text, &Default::default(), &Default::default()).unwrap();
+    let crate::ast::Value::Array(array) = ast.value.unwrap() else {
+      unreachable!()
+    };
+    let crate::ast::Value::NumberLit(number) = &array.elements[0] else {
+      unreachable!()
+    };
+    assert_eq!(number.as_i128(), Ok(31));
+
+    #[cfg(feature = "cst")]
+    {
+      let root = crate::cst::CstRootNode::parse(text, &Default::default()).unwrap();
+      let number = root.array_value().unwrap().elements()[1].as_number_lit().unwrap();
+      assert_eq!(
+        number.as_decimal().map(|value| value.to_string()),
+        Ok("1.5".to_string())
+      );
+      number.set_raw_value("-0x10".to_string());
+      assert_eq!(number.as_i64(), Ok(-16));
+    }
+  }
+}
@@ -1,12 +1,16 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 use super::ast::*;
 use super::common::Range;
+use super::common::Ranged;
 use super::errors::*;
+use super::parser::MAX_NESTING_DEPTH;
 use super::scanner::Scanner;
 use super::scanner::ScannerOptions;
+use super::string::decode_word;
 use super::tokens::Token;
 use super::tokens::TokenAndRange;
 
@@ -46,12 +50,37 @@ pub struct CollectOptions {
   pub tokens: bool,
 }
 
+/// How to handle an object that contains the same property name more than once.
+///
+/// The AST and CST keep every property in order to represent the text losslessly,
+/// so only the `Error` policy affects them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+  /// Use the value of the last occurrence.
+  ///
+  /// The serde deserializer reads the text once, so it passes every occurrence to
+  /// the visitor in order. Map types such as `HashMap` and `serde_json::Value` keep
+  /// the last one, while structs that derive `Deserialize` error with a duplicate
+  /// field.
+  #[default]
+  LastWins,
+  /// Use the value of the first occurrence.
+  FirstWins,
+  /// Error with `ParseErrorKind::DuplicateObjectKey` at the second occurrence.
+  Error,
+}
+
 /// Options for parsing.
+///
+/// The default options are extremely loose in what they allow. Use one of the
+/// presets such as [`ParseOptions::strict_json`] to parse a specific format.
 #[derive(Clone)]
 pub struct ParseOptions {
   /// Allow comments (defaults to `true`).
   pub allow_comments: bool,
-  /// Allow words and numbers as object property names (defaults to `true`).
+  /// Allow words like `$schema`, `true`, and `null` and numbers as object property names (defaults to `true`).
+  ///
+  /// Words may contain `\uXXXX` escapes, which are decoded in the property name.
   pub allow_loose_object_property_names: bool,
   /// Allow trailing commas on object literal and array literal values (defaults to `true`).
   pub allow_trailing_commas: bool,
@@ -63,6 +92,25 @@ pub struct ParseOptions {
   pub allow_hexadecimal_numbers: bool,
   /// Allow unary plus sign on numbers like +42 (defaults to `true`).
   pub allow_unary_plus_numbers: bool,
+  /// Allow `Infinity` and `NaN` as numbers, optionally signed (defaults to `false`).
+  pub allow_infinity_and_nan: bool,
+  /// Allow numbers with a leading decimal point like .5 (defaults to `false`).
+  pub allow_leading_decimal_point: bool,
+  /// Allow numbers with a trailing decimal point like 5. (defaults to `false`).
+  pub allow_trailing_decimal_point: bool,
+  /// Allow escaping a line terminator in a string to continue it on the next line (defaults to `false`).
+  pub allow_line_continuations: bool,
+  /// Allow the JSON5 string escapes `\xHH`, `\v`, `\0`, and escaping any other character (defaults to `false`).
+  pub allow_extended_escapes: bool,
+  /// Allow unescaped control characters (U+0000 through U+001F) in strings (defaults to `true`).
+  pub allow_control_characters_in_strings: bool,
+  /// Allow leading zeros on numbers like 007 (defaults to `false`).
+  ///
+  /// When not allowed and missing commas are allowed, a leading `0` ends the number
+  /// so `007` is read as the separate numbers `0`, `0`, and `7`. Otherwise it errors.
+  pub allow_leading_zeros: bool,
+  /// How to handle an object with the same property name more than once (defaults to `LastWins`).
+  pub duplicate_key_policy: DuplicateKeyPolicy,
 }
 
 impl Default for ParseOptions {
@@ -75,6 +123,135 @@ impl Default for ParseOptions {
       allow_single_quoted_strings: true,
       allow_hexadecimal_numbers: true,
       allow_unary_plus_numbers: true,
+      allow_infinity_and_nan: false,
+      allow_leading_decimal_point: false,
+      allow_trailing_decimal_point: false,
+      allow_line_continuations: false,
+      allow_extended_escapes: false,
+      allow_control_characters_in_strings: true,
+      allow_leading_zeros: false,
+      duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+    }
+  }
+}
+
+impl ParseOptions {
+  /// Options for parsing strict JSON as specified by [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259).
+  ///
+  /// Every extension is disallowed, including unescaped control characters in
+  /// strings and leading zeros on numbers. Unpaired surrogate escapes like `"\uD800"`
+  /// are rejected regardless of the options.
+  pub fn strict_json() -> Self {
+    Self {
+      allow_comments: false,
+      allow_loose_object_property_names: false,
+      allow_trailing_commas: false,
+      allow_missing_commas: false,
+      allow_single_quoted_strings: false,
+      allow_hexadecimal_numbers: false,
+      allow_unary_plus_numbers: false,
+      allow_infinity_and_nan: false,
+      allow_leading_decimal_point: false,
+      allow_trailing_decimal_point: false,
+      allow_line_continuations: false,
+      allow_extended_escapes: false,
+      allow_control_characters_in_strings: false,
+      allow_leading_zeros: false,
+      duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+    }
+  }
+
+  /// Options for parsing JSON with comments.
+  pub fn jsonc() -> Self {
+    Self {
+      allow_comments: true,
+      allow_loose_object_property_names: false,
+      allow_trailing_commas: false,
+      allow_missing_commas: false,
+      allow_single_quoted_strings: false,
+      allow_hexadecimal_numbers: false,
+      allow_unary_plus_numbers: false,
+      allow_infinity_and_nan: false,
+      allow_leading_decimal_point: false,
+      allow_trailing_decimal_point: false,
+      allow_line_continuations: false,
+      allow_extended_escapes: false,
+      allow_control_characters_in_strings: false,
+      allow_leading_zeros: false,
+      duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+    }
+  }
+
+  /// Options for parsing VS Code settings files, which allow comments and trailing commas.
+  pub fn vscode_settings() -> Self {
+    Self {
+      allow_comments: true,
+      allow_loose_object_property_names: false,
+      allow_trailing_commas: true,
+      allow_missing_commas: false,
+      allow_single_quoted_strings: false,
+      allow_hexadecimal_numbers: false,
+      allow_unary_plus_numbers: false,
+      allow_infinity_and_nan: false,
+      allow_leading_decimal_point: false,
+      allow_trailing_decimal_point: false,
+      allow_line_continuations: false,
+      allow_extended_escapes: false,
+      allow_control_characters_in_strings: false,
+      allow_leading_zeros: false,
+      duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+    }
+  }
+
+  /// Options for parsing `tsconfig.json` files, which allow comments and trailing commas.
+  ///
+  /// TypeScript reads these with the same JSONC dialect as VS Code settings, so this is
+  /// an alias of `vscode_settings()`.
+  pub fn tsconfig() -> Self {
+    Self::vscode_settings()
+  }
+
+  /// Options for parsing [JSON5](https://spec.json5.org/).
+  ///
+  /// Unquoted property names are matched using the Unicode Alphabetic and
+  /// Numeric properties for letters and digits, along with `$`, `_`, ZWNJ,
+  /// ZWJ, connector punctuation, and combining diacritical marks, rather than
+  /// the exact Unicode categories of the JSON5 IdentifierName production.
+  pub fn json5() -> Self {
+    Self {
+      allow_comments: true,
+      allow_loose_object_property_names: true,
+      allow_trailing_commas: true,
+      allow_missing_commas: false,
+      allow_single_quoted_strings: true,
+      allow_hexadecimal_numbers: true,
+      allow_unary_plus_numbers: true,
+      allow_infinity_and_nan: true,
+      allow_leading_decimal_point: true,
+      allow_trailing_decimal_point: true,
+      allow_line_continuations: true,
+      allow_extended_escapes: true,
+      allow_control_characters_in_strings: true,
+      allow_leading_zeros: false,
+      duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+    }
+  }
+
+  pub(crate) fn scanner_options(&self) -> ScannerOptions {
+    ScannerOptions {
+      allow_single_quoted_strings: self.allow_single_quoted_strings,
+      allow_hexadecimal_numbers: self.allow_hexadecimal_numbers,
+      allow_unary_plus_numbers: self.allow_unary_plus_numbers,
+      allow_infinity_and_nan: self.allow_infinity_and_nan,
+      allow_leading_decimal_point: self.allow_leading_decimal_point,
+      allow_trailing_decimal_point: self.allow_trailing_decimal_point,
+      allow_line_continuations: self.allow_line_continuations,
+      allow_extended_escapes: self.allow_extended_escapes,
+      allow_control_characters_in_strings: self.allow_control_characters_in_strings,
+      allow_leading_zeros: self.allow_leading_zeros,
+      // the separate numbers only parse when missing commas are allowed
+      split_leading_zeros: self.allow_missing_commas,
+      allow_dollar_sign_in_words: self.allow_loose_object_property_names,
     }
   }
 }
@@ -93,6 +270,10 @@ pub struct ParseResult<'a> {
   ///
   /// Provide `tokens: true` to the `ParseOptions` for this to have a value.
   pub tokens: Option<Vec<TokenAndRange<'a>>>,
+  /// Errors that were recovered from while parsing.
+  ///
+  /// This is only populated by `parse_to_ast_recovering`.
+  pub errors: Vec<ParseError>,
 }
 
 struct Context<'a> {
@@ -100,6 +281,14 @@ struct Context<'a> {
   comments: Option<CommentMap<'a>>,
   current_comments: Option<Vec<Comment<'a>>>,
   last_token_end: usize,
+  previous_token_end: usize,
+  /// When set, the next call to `scan()` returns the current token again.
+  reuse_token: bool,
+  /// Errors that were recovered from. `None` when not recovering.
+  errors: Option<Vec<ParseError>>,
+  /// Range of the text skipped over due to an error when scanning
+  /// the current token.
+  invalid_token_range: Option<Range>,
   range_stack: Vec<Range>,
   tokens: Option<Vec<TokenAndRange<'a>>>,
   collect_comments_as_tokens: bool,
@@ -107,14 +296,80 @@ struct Context<'a> {
   allow_trailing_commas: bool,
   allow_missing_commas: bool,
   allow_loose_object_property_names: bool,
+  allow_single_quoted_strings: bool,
+  allow_hexadecimal_numbers: bool,
+  allow_unary_plus_numbers: bool,
+  duplicate_key_policy: DuplicateKeyPolicy,
   maximum_nesting_depth: usize,
 }
 
 impl<'a> Context<'a> {
+  pub fn new(text: &'a str, collect_options: &CollectOptions, parse_options: &ParseOptions, recover: bool) -> Self {
+    Context {
+      scanner: Scanner::new(
+        text,
+        &if recover {
+          // these are checked by the context instead in order to keep the token
+          ScannerOptions {
+            allow_single_quoted_strings: true,
+            allow_hexadecimal_numbers: true,
+            allow_unary_plus_numbers: true,
+            ..parse_options.scanner_options()
+          }
+        } else {
+          parse_options.scanner_options()
+        },
+      ),
+      comments: match collect_options.comments {
+        CommentCollectionStrategy::Separate => Some(Default::default()),
+        CommentCollectionStrategy::Off | CommentCollectionStrategy::AsTokens => None,
+      },
+      current_comments: None,
+      last_token_end: 0,
+      previous_token_end: 0,
+      reuse_token: false,
+      errors: if recover { Some(Vec::new()) } else { None },
+      invalid_token_range: None,
+      range_stack: Vec::new(),
+      tokens: if collect_options.tokens { Some(Vec::new()) } else { None },
+      collect_comments_as_tokens: collect_options.comments == CommentCollectionStrategy::AsTokens,
+      allow_comments: parse_options.allow_comments,
+      allow_trailing_commas: parse_options.allow_trailing_commas,
+      allow_missing_commas: parse_options.allow_missing_commas,
+      allow_loose_object_property_names: parse_options.allow_loose_object_property_names,
+      allow_single_quoted_strings: parse_options.allow_single_quoted_strings,
+      allow_hexadecimal_numbers: parse_options.allow_hexadecimal_numbers,
+      allow_unary_plus_numbers: parse_options.allow_unary_plus_numbers,
+      duplicate_key_policy: parse_options.duplicate_key_policy,
+      maximum_nesting_depth: MAX_NESTING_DEPTH,
+    }
+  }
+
   pub fn scan(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+    if self.reuse_token {
+      self.reuse_token = false;
+      return Ok(self.token());
+    }
+
     let previous_end = self.last_token_end;
-    let token = self.scan_handling_comments()?;
+    self.invalid_token_range = None;
+    let token = loop {
+      match self.scan_handling_comments() {
+        Ok(token) => break token,
+        Err(err) => {
+          self.recover(err)?;
+          self.scanner.skip_invalid_token();
+          self.invalid_token_range = Some(Range::new(self.scanner.token_start(), self.scanner.token_end()));
+        }
+      }
+    };
+    self.previous_token_end = previous_end;
     self.last_token_end = self.scanner.token_end();
+    if self.is_recovering()
+      && let Some(token) = &token
+    {
+      self.check_token_allowed(token);
+    }
 
     // store the comment for the previous token end, and current token start
     if let Some(comments) = self.comments.as_mut()
@@ -150,7 +405,12 @@ impl<'a> Context<'a> {
       .range_stack
       .pop()
       .expect("Range was popped from the stack, but the stack was empty.");
-    range.end = self.scanner.token_end();
+    range.end = if self.reuse_token {
+      // the current token belongs to a parent node
+      self.previous_token_end
+    } else {
+      self.scanner.token_end()
+    };
     range
   }
 
@@ -174,6 +434,30 @@ impl<'a> Context<'a> {
     self.scanner.create_error_for_range(range, kind)
   }
 
+  /// Stores the error when recovering from errors, otherwise returns it.
+  pub fn recover(&mut self, error: ParseError) -> Result<(), ParseError> {
+    match self.errors.as_mut() {
+      Some(errors) => {
+        errors.push(error);
+        Ok(())
+      }
+      None => Err(error),
+    }
+  }
+
+  pub fn is_recovering(&self) -> bool {
+    self.errors.is_some()
+  }
+
+  /// Creates a placeholder for a value that's not in the text and leaves
+  /// the current token to be handled by the parent node.
+  pub fn create_missing_value(&mut self) -> Value<'a> {
+    self.reuse_token = self.token().is_some();
+    Value::MissingValue(MissingValue {
+      range: Range::from_byte_index(self.previous_token_end),
+    })
+  }
+
   fn scan_handling_comments(&mut self) -> Result<Option<Token<'a>>, ParseError> {
     loop {
       let token = self.scanner.scan()?;
@@ -198,6 +482,31 @@ impl<'a> Context<'a> {
     }
   }
 
+  /// Reports errors for tokens disallowed by the parse options, which
+  /// the scanner allows when recovering.
+  fn check_token_allowed(&mut self, token: &Token<'a>) {
+    let kind = match token {
+      Token::String(_)
+        if !self.allow_single_quoted_strings
+          && self.scanner.file_text().as_bytes()[self.scanner.token_start()] == b'\'' =>
+      {
+        ParseErrorKind::SingleQuotedStringsNotAllowed
+      }
+      Token::Number(value) if !self.allow_unary_plus_numbers && value.starts_with('+') => {
+        ParseErrorKind::UnaryPlusNumbersNotAllowed
+      }
+      Token::Number(value)
+        if !self.allow_hexadecimal_numbers
+          && matches!(value.trim_start_matches(['-', '+']).as_bytes(), [b'0', b'x' | b'X', ..]) =>
+      {
+        ParseErrorKind::HexadecimalNumbersNotAllowed
+      }
+      _ => return,
+    };
+    let error = self.create_error(kind);
+    self.recover(error).unwrap();
+  }
+
   fn capture_token(&mut self, token: Token<'a>) {
     let range = self.create_range_from_last_token();
     if let Some(tokens) = self.tokens.as_mut() {
@@ -246,36 +555,8 @@ pub fn parse_to_ast<'a>(
   collect_options: &CollectOptions,
   parse_options: &ParseOptions,
 ) -> Result<ParseResult<'a>, ParseError> {
-  let mut context = Context {
-    scanner: Scanner::new(
-      text,
-      &ScannerOptions {
-        allow_single_quoted_strings: parse_options.allow_single_quoted_strings,
-        allow_hexadecimal_numbers: parse_options.allow_hexadecimal_numbers,
-        allow_unary_plus_numbers: parse_options.allow_unary_plus_numbers,
-      },
-    ),
-    comments: match collect_options.comments {
-      CommentCollectionStrategy::Separate => Some(Default::default()),
-      CommentCollectionStrategy::Off | CommentCollectionStrategy::AsTokens => None,
-    },
-    current_comments: None,
-    last_token_end: 0,
-    range_stack: Vec::new(),
-    tokens: if collect_options.tokens { Some(Vec::new()) } else { None },
-    collect_comments_as_tokens: collect_options.comments == CommentCollectionStrategy::AsTokens,
-    allow_comments: parse_options.allow_comments,
-    allow_trailing_commas: parse_options.allow_trailing_commas,
-    allow_missing_commas: parse_options.allow_missing_commas,
-    allow_loose_object_property_names: parse_options.allow_loose_object_property_names,
-    maximum_nesting_depth: 512,
-  };
-  context.scan()?;
-  let value = parse_value(&mut context)?;
-
-  if context.scan()?.is_some() {
-    return Err(context.create_error(ParseErrorKind::MultipleRootJsonValues));
-  }
+  let mut context = Context::new(text, collect_options, parse_options, false);
+  let value = parse_root(&mut context)?;
 
   debug_assert!(context.range_stack.is_empty());
 
@@ -283,53 +564,171 @@ pub fn parse_to_ast<'a>(
     comments: context.comments,
     tokens: context.tokens,
     value,
+    errors: Vec::new(),
   })
 }
 
+/// Parses a string containing JSONC to an AST, recovering from errors
+/// instead of stopping at the first one.
+///
+/// The returned value is a best effort tree of what could be parsed.
+/// Values that were expected, but could not be parsed, are represented
+/// as `Value::MissingValue` and unterminated objects and arrays end at the
+/// end of the text. All the errors encountered are stored in `errors`.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::parse_to_ast_recovering;
+///
+/// let parse_result = parse_to_ast_recovering(
+///   r#"{ "a": , "b": [1, 2 }"#,
+///   &Default::default(),
+///   &Default::default(),
+/// );
+/// let obj = parse_result.value.unwrap();
+/// let obj = obj.as_object().unwrap();
+/// assert!(obj.get("a").unwrap().value.as_missing_value().is_some());
+/// assert_eq!(obj.get_array("b").unwrap().elements.len(), 2);
+/// assert_eq!(parse_result.errors.len(), 2);
+/// ```
+pub fn parse_to_ast_recovering<'a>(
+  text: &'a str,
+  collect_options: &CollectOptions,
+  parse_options: &ParseOptions,
+) -> ParseResult<'a> {
+  let mut context = Context::new(text, collect_options, parse_options, true);
+  let value = match parse_root(&mut context) {
+    Ok(value) => value,
+    // errors that can't be recovered from (ex. exceeding the nesting depth)
+    Err(err) => {
+      context.recover(err).unwrap();
+      None
+    }
+  };
+
+  ParseResult {
+    comments: context.comments,
+    tokens: context.tokens,
+    value,
+    errors: context.errors.unwrap_or_default(),
+  }
+}
+
+fn parse_root<'a>(context: &mut Context<'a>) -> Result<Option<Value<'a>>, ParseError> {
+  context.scan()?;
+  let value = parse_value(context)?;
+
+  // a reused token already had an error reported for it
+  let is_reported = context.reuse_token;
+  if context.scan()?.is_some() && !is_reported {
+    // when recovering, the remaining text is ignored
+    context.recover(context.create_error(ParseErrorKind::MultipleRootJsonValues))?;
+  }
+
+  Ok(value)
+}
+
 fn parse_value<'a>(context: &mut Context<'a>) -> Result<Option<Value<'a>>, ParseError> {
   if context.range_stack.len() > context.maximum_nesting_depth {
     return Err(context.create_error_for_current_range(ParseErrorKind::NestingDepthExceeded));
   }
 
-  match context.token() {
-    None => Ok(None),
-    Some(token) => match token {
-      Token::OpenBrace => Ok(Some(Value::Object(parse_object(context)?))),
-      Token::OpenBracket => Ok(Some(Value::Array(parse_array(context)?))),
-      Token::String(value) => Ok(Some(Value::StringLit(create_string_lit(context, value)))),
-      Token::Boolean(value) => Ok(Some(Value::BooleanLit(create_boolean_lit(context, value)))),
-      Token::Number(value) => Ok(Some(Value::NumberLit(create_number_lit(context, value)))),
-      Token::Null => Ok(Some(Value::NullKeyword(create_null_keyword(context)))),
-      Token::CloseBracket => Err(context.create_error(ParseErrorKind::UnexpectedCloseBracket)),
-      Token::CloseBrace => Err(context.create_error(ParseErrorKind::UnexpectedCloseBrace)),
-      Token::Comma => Err(context.create_error(ParseErrorKind::UnexpectedComma)),
-      Token::Colon => Err(context.create_error(ParseErrorKind::UnexpectedColon)),
-      Token::Word(_) => Err(context.create_error(ParseErrorKind::UnexpectedWord)),
-      Token::CommentLine(_) => unreachable!(),
-      Token::CommentBlock(_) => unreachable!(),
-    },
+  loop {
+    return match context.token() {
+      None => Ok(None),
+      Some(token) => match token {
+        Token::OpenBrace => Ok(Some(Value::Object(parse_object(context)?))),
+        Token::OpenBracket => Ok(Some(Value::Array(parse_array(context)?))),
+        Token::String(value) => Ok(Some(Value::StringLit(create_string_lit(context, value)))),
+        Token::Boolean(value) => Ok(Some(Value::BooleanLit(create_boolean_lit(context, value)))),
+        Token::Number(value) => Ok(Some(Value::NumberLit(create_number_lit(context, value)))),
+        Token::Null => Ok(Some(Value::NullKeyword(create_null_keyword(context)))),
+        Token::CloseBracket | Token::CloseBrace | Token::Comma => {
+          if let Some(range) = context.invalid_token_range {
+            // the error was already reported when scanning
+            context.reuse_token = true;
+            context.previous_token_end = range.end;
+            return Ok(Some(Value::MissingValue(MissingValue { range })));
+          }
+          let kind = match token {
+            Token::CloseBracket => ParseErrorKind::UnexpectedCloseBracket,
+            Token::CloseBrace => ParseErrorKind::UnexpectedCloseBrace,
+            _ => ParseErrorKind::UnexpectedComma,
+          };
+          context.recover(context.create_error(kind))?;
+          Ok(Some(context.create_missing_value()))
+        }
+        Token::Colon => {
+          context.recover(context.create_error(ParseErrorKind::UnexpectedColon))?;
+          // skip over the colon and try again
+          context.scan()?;
+          continue;
+        }
+        Token::Word(_) => {
+          context.recover(context.create_error(ParseErrorKind::UnexpectedWord))?;
+          Ok(Some(Value::MissingValue(MissingValue {
+            range: context.create_range_from_last_token(),
+          })))
+        }
+        Token::CommentLine(_) => unreachable!(),
+        Token::CommentBlock(_) => unreachable!(),
+      },
+    };
   }
 }
 
 fn parse_object<'a>(context: &mut Context<'a>) -> Result<Object<'a>, ParseError> {
   debug_assert!(context.token() == Some(Token::OpenBrace));
   let mut properties = Vec::new();
+  // only tracked when duplicates are an error because the AST keeps every property otherwise
+  let mut seen_names = (context.duplicate_key_policy == DuplicateKeyPolicy::Error).then(HashSet::new);
 
   context.start_range();
   context.scan()?;
 
   loop {
-    match context.token() {
+    let prop = match context.token() {
       Some(Token::CloseBrace) => break,
-      Some(Token::String(prop_name)) => {
-        properties.push(parse_object_property(context, PropName::String(prop_name))?);
-      }
+      Some(Token::String(prop_name)) => parse_object_property(context, PropName::String(prop_name))?,
       Some(Token::Word(prop_name)) | Some(Token::Number(prop_name)) => {
-        properties.push(parse_object_property(context, PropName::Word(prop_name))?);
+        parse_object_property(context, PropName::Word(prop_name))?
+      }
+      Some(Token::Boolean(value)) => {
+        parse_object_property(context, PropName::Word(if value { "true" } else { "false" }))?
+      }
+      Some(Token::Null) => parse_object_property(context, PropName::Word("null"))?,
+      None => {
+        let range = context.end_range();
+        context.recover(context.create_error_for_range(range, ParseErrorKind::UnterminatedObject))?;
+        return Ok(Object { range, properties });
+      }
+      Some(Token::CloseBracket) if context.is_recovering() => {
+        context.recover(context.create_error(ParseErrorKind::UnexpectedCloseBracket))?;
+        // end the object and leave the close bracket for the parent
+        context.reuse_token = true;
+        return Ok(Object {
+          range: context.end_range(),
+          properties,
+        });
       }
-      None => return Err(context.create_error_for_current_range(ParseErrorKind::UnterminatedObject)),
-      _ => return Err(context.create_error(ParseErrorKind::UnexpectedTokenInObject)),
+      Some(token) => {
+        context.recover(context.create_error(ParseErrorKind::UnexpectedTokenInObject))?;
+        // skip over the token and any value it starts
+        if matches!(token, Token::OpenBrace | Token::OpenBracket) {
+          parse_value(context)?;
+        }
+        context.scan()?;
+        continue;
+      }
+    };
+    if let Some(seen_names) = seen_names.as_mut()
+      && !seen_names.insert(prop.name.as_str().to_string())
+    {
+      let kind = ParseErrorKind::DuplicateObjectKey(prop.name.as_str().to_string());
+      context.recover(context.create_error_for_range(prop.name.range(), kind))?;
     }
+    properties.push(prop);
 
     // skip the comma
     let after_value_end = context.last_token_end;
@@ -339,15 +738,15 @@ fn parse_object<'a>(context: &mut Context<'a>) -> Result<Object<'a>, ParseError>
         if let Some(Token::CloseBrace) = context.scan()?
           && !context.allow_trailing_commas
         {
-          return Err(context.create_error_for_range(comma_range, ParseErrorKind::TrailingCommasNotAllowed));
+          context.recover(context.create_error_for_range(comma_range, ParseErrorKind::TrailingCommasNotAllowed))?;
         }
       }
-      Some(Token::String(_) | Token::Word(_) | Token::Number(_)) if !context.allow_missing_commas => {
+      Some(token) if token.is_object_key() && !context.allow_missing_commas => {
         let range = Range {
           start: after_value_end,
           end: after_value_end,
         };
-        return Err(context.create_error_for_range(range, ParseErrorKind::ExpectedComma));
+        context.recover(context.create_error_for_range(range, ParseErrorKind::ExpectedComma))?;
       }
       _ => {}
     }
@@ -370,30 +769,42 @@ fn parse_object_property<'a>(context: &mut Context<'a>, prop_name: PropName<'a>)
   let name = match prop_name {
     PropName::String(prop_name) => ObjectPropName::String(create_string_lit(context, prop_name)),
     PropName::Word(prop_name) => {
-      if context.allow_loose_object_property_names {
-        ObjectPropName::Word(create_word(context, prop_name))
-      } else {
-        return Err(context.create_error(ParseErrorKind::ExpectedStringObjectProperty));
+      if !context.allow_loose_object_property_names {
+        context.recover(context.create_error(ParseErrorKind::ExpectedStringObjectProperty))?;
       }
+      ObjectPropName::Word(create_word(context, prop_name))
     }
   };
 
-  match context.scan()? {
-    Some(Token::Colon) => {}
-    _ => return Err(context.create_error(ParseErrorKind::ExpectedColonAfterObjectKey)),
-  }
+  let value = match context.scan()? {
+    Some(Token::Colon) => {
+      context.scan()?;
+      parse_value(context)?
+    }
+    Some(Token::Comma | Token::CloseBrace | Token::CloseBracket) | None => {
+      context.recover(context.create_error(ParseErrorKind::ExpectedColonAfterObjectKey))?;
+      Some(context.create_missing_value())
+    }
+    Some(_) => {
+      context.recover(context.create_error(ParseErrorKind::ExpectedColonAfterObjectKey))?;
+      // use the current token as the value
+      parse_value(context)?
+    }
+  };
 
-  context.scan()?;
-  let value = parse_value(context)?;
+  let value = match value {
+    Some(value) => value,
+    None => {
+      context.recover(context.create_error(ParseErrorKind::ExpectedObjectValue))?;
+      context.create_missing_value()
+    }
+  };
 
-  match value {
-    Some(value) => Ok(ObjectProp {
-      range: context.end_range(),
-      name,
-      value,
-    }),
-    None => Err(context.create_error(ParseErrorKind::ExpectedObjectValue)),
-  }
+  Ok(ObjectProp {
+    range: context.end_range(),
+    name,
+    value,
+  })
 }
 
 fn parse_array<'a>(context: &mut Context<'a>) -> Result<Array<'a>, ParseError> {
@@ -404,23 +815,48 @@ fn parse_array<'a>(context: &mut Context<'a>) -> Result<Array<'a>, ParseError> {
   context.scan()?;
 
   loop {
-    match context.token() {
+    let value = match context.token() {
       Some(Token::CloseBracket) => break,
-      None => return Err(context.create_error_for_current_range(ParseErrorKind::UnterminatedArray)),
-      _ => match parse_value(context)? {
-        Some(value) => elements.push(value),
-        None => return Err(context.create_error_for_current_range(ParseErrorKind::UnterminatedArray)),
-      },
+      Some(Token::CloseBrace) if context.is_recovering() => {
+        context.recover(context.create_error(ParseErrorKind::UnexpectedCloseBrace))?;
+        // end the array and leave the close brace for the parent
+        context.reuse_token = true;
+        return Ok(Array {
+          range: context.end_range(),
+          elements,
+        });
+      }
+      None => None,
+      _ => parse_value(context)?,
+    };
+    match value {
+      Some(value) => elements.push(value),
+      None => {
+        let range = context.end_range();
+        context.recover(context.create_error_for_range(range, ParseErrorKind::UnterminatedArray))?;
+        return Ok(Array { range, elements });
+      }
     }
 
     // skip the comma
-    if let Some(Token::Comma) = context.scan()? {
-      let comma_range = context.create_range_from_last_token();
-      if let Some(Token::CloseBracket) = context.scan()?
-        && !context.allow_trailing_commas
-      {
-        return Err(context.create_error_for_range(comma_range, ParseErrorKind::TrailingCommasNotAllowed));
+    let after_value_end = context.last_token_end;
+    match context.scan()? {
+      Some(Token::Comma) => {
+        let comma_range = context.create_range_from_last_token();
+        if let Some(Token::CloseBracket) = context.scan()?
+          && !context.allow_trailing_commas
+        {
+          context.recover(context.create_error_for_range(comma_range, ParseErrorKind::TrailingCommasNotAllowed))?;
+        }
       }
+      Some(token) if token.is_value_start() && !context.allow_missing_commas => {
+        let range = Range {
+          start: after_value_end,
+          end: after_value_end,
+        };
+        context.recover(context.create_error_for_range(range, ParseErrorKind::ExpectedComma))?;
+      }
+      _ => {}
     }
   }
 
@@ -442,7 +878,7 @@ fn create_string_lit<'a>(context: &Context<'a>, value: Cow<'a, str>) -> StringLi
 fn create_word<'a>(context: &Context<'a>, value: &'a str) -> WordLit<'a> {
   WordLit {
     range: context.create_range_from_last_token(),
-    value,
+    value: decode_word(value),
   }
 }
 
@@ -582,19 +1018,7 @@ mod tests {
 
   #[track_caller]
   fn assert_has_strict_error(text: &str, message: &str) {
-    let result = parse_to_ast(
-      text,
-      &Default::default(),
-      &ParseOptions {
-        allow_comments: false,
-        allow_loose_object_property_names: false,
-        allow_trailing_commas: false,
-        allow_missing_commas: false,
-        allow_single_quoted_strings: false,
-        allow_hexadecimal_numbers: false,
-        allow_unary_plus_numbers: false,
-      },
-    );
+    let result = parse_to_ast(text, &Default::default(), &ParseOptions::strict_json());
     match result {
       Ok(_) => panic!("Expected error, but did not find one."),
       Err(err) => assert_eq!(err.to_string(), message),
@@ -720,6 +1144,19 @@ mod tests {
     assert_has_strict_error(text, "Expected comma on line 2 column 18");
   }
 
+  #[test]
+  fn missing_comma_between_array_elements() {
+    let text = "[1 \"a\" [] {}]";
+    let result = parse_to_ast(text, &Default::default(), &Default::default()).unwrap();
+    match result.value {
+      Some(Value::Array(array)) => assert_eq!(array.elements.len(), 4),
+      _ => panic!("Expected array"),
+    }
+
+    // but is strict when strict
+    assert_has_strict_error(text, "Expected comma on line 1 column 3");
+  }
+
   #[test]
   fn missing_comma_with_comment_between_properties() {
     // when comments are allowed but missing commas are not,
@@ -805,4 +1242,179 @@ mod tests {
       Err(_) => panic!("Expected Ok, but did not find one."),
     }
   }
+
+  #[track_caller]
+  fn assert_recovered_errors<'a>(text: &'a str, messages: &[&str]) -> Option<Value<'a>> {
+    let result = parse_to_ast_recovering(text, &Default::default(), &Default::default());
+    let errors = result.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(errors, messages);
+    result.value
+  }
+
+  #[test]
+  fn recovering_should_have_no_errors_for_valid_text() {
+    let value = assert_recovered_errors(r#"{ "a": [1, 2], "b": true }"#, &[]).unwrap();
+    let obj = value.as_object().unwrap();
+    assert_eq!(obj.get_array("b").map(|_| ()), None);
+    assert_eq!(obj.get_array("a").unwrap().elements.len(), 2);
+  }
+
+  #[test]
+  fn recovering_should_insert_missing_values() {
+    let value = assert_recovered_errors(
+      r#"{ "a": , "b": }"#,
+      &[
+        "Unexpected comma on line 1 column 8",
+        "Unexpected close brace on line 1 column 15",
+      ],
+    )
+    .unwrap();
+    let obj = value.as_object().unwrap();
+    assert_eq!(obj.range, Range::new(0, 15));
+    assert_eq!(obj.properties.len(), 2);
+    let a = obj.get("a").unwrap();
+    assert_eq!(a.range, Range::new(2, 6));
+    assert_eq!(a.value.as_missing_value().unwrap().range, Range::new(6, 6));
+    let b = obj.get("b").unwrap();
+    assert_eq!(b.range, Range::new(9, 13));
+    assert_eq!(b.value.as_missing_value().unwrap().range, Range::new(13, 13));
+  }
+
+  #[test]
+  fn recovering_should_handle_missing_colons() {
+    let value = assert_recovered_errors(
+      r#"{ "a" 1, "b" }"#,
+      &[
+        "Expected colon after the string or word in object property on line 1 column 7",
+        "Expected colon after the string or word in object property on line 1 column 14",
+      ],
+    )
+    .unwrap();
+    let obj = value.as_object().unwrap();
+    assert_eq!(obj.get_number("a").unwrap().value, "1");
+    assert!(obj.get("b").unwrap().value.as_missing_value().is_some());
+  }
+
+  #[test]
+  fn recovering_should_handle_unterminated_containers() {
+    let text = r#"{ "a": [1, 2"#;
+    let value = assert_recovered_errors(
+      text,
+      &[
+        "Unterminated array on line 1 column 8",
+        "Unterminated object on line 1 column 1",
+      ],
+    )
+    .unwrap();
+    let obj = value.as_object().unwrap();
+    assert_eq!(obj.range, Range::new(0, text.len()));
+    let array = obj.get_array("a").unwrap();
+    assert_eq!(array.range, Range::new(7, text.len()));
+    assert_eq!(array.elements.len(), 2);
+  }
+
+  #[test]
+  fn recovering_should_handle_mismatched_close_tokens() {
+    let value =
+      assert_recovered_errors(r#"[{ "a": [1 }, 2]"#, &["Unexpected close brace on line 1 column 12"]).unwrap();
+    let array = value.as_array().unwrap();
+    assert_eq!(array.elements.len(), 2);
+    let obj = array.elements[0].as_object().unwrap();
+    assert_eq!(obj.range, Range::new(1, 12));
+    assert_eq!(obj.get_array("a").unwrap().range, Range::new(8, 10));
+    assert_eq!(array.elements[1].as_number_lit().unwrap().value, "2");
+  }
+
+  #[test]
+  fn recovering_should_skip_unexpected_tokens() {
+    let value = assert_recovered_errors(
+      r#"{ : "a": [1 : 2, foo, @], [3] "b": 4 }"#,
+      &[
+        "Unexpected token in object on line 1 column 3",
+        "Unexpected colon on line 1 column 13",
+        "Unexpected token on line 1 column 18",
+        "Unexpected token on line 1 column 23",
+        "Unexpected token in object on line 1 column 27",
+      ],
+    )
+    .unwrap();
+    let obj = value.as_object().unwrap();
+    let elements = &obj.get_array("a").unwrap().elements;
+    assert_eq!(elements.len(), 3);
+    assert_eq!(elements[1].as_number_lit().unwrap().value, "2");
+    assert_eq!(elements[2].as_missing_value().unwrap().range, Range::new(17, 20));
+    assert_eq!(obj.get_number("b").unwrap().value, "4");
+  }
+
+  #[test]
+  fn recovering_should_continue_after_scanner_errors() {
+    let value = assert_recovered_errors(
+      "{ \"a\": \"\\q\", \"b\": 1 /* test",
+      &[
+        "Invalid escape on line 1 column 9",
+        "Unterminated comment block on line 1 column 21",
+        "Unterminated object on line 1 column 1",
+      ],
+    )
+    .unwrap();
+    let obj = value.as_object().unwrap();
+    assert_eq!(obj.properties.len(), 2);
+    assert_eq!(
+      obj.get("a").unwrap().value.as_missing_value().unwrap().range,
+      Range::new(7, 11)
+    );
+    assert_eq!(obj.get_number("b").unwrap().value, "1");
+  }
+
+  #[test]
+  fn recovering_should_report_strict_errors() {
+    let result = parse_to_ast_recovering(
+      "{ a: 1, 'b': 2, } // test",
+      &Default::default(),
+      &ParseOptions::strict_json(),
+    );
+    let errors = result.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+      errors,
+      [
+        "Expected string for object property on line 1 column 3",
+        "Single-quoted strings are not allowed on line 1 column 9",
+        "Trailing commas are not allowed on line 1 column 15",
+        "Comments are not allowed on line 1 column 19",
+      ]
+    );
+    let value = result.value.unwrap();
+    let obj = value.as_object().unwrap();
+    assert_eq!(obj.get_number("a").unwrap().value, "1");
+    assert_eq!(obj.get_number("b").unwrap().value, "2");
+  }
+
+  #[test]
+  fn it_should_keep_duplicate_keys_unless_policy_is_error() {
+    let text = r#"{ "a": 1, "b": { "a": 2 }, "a": 3 }"#;
+    let result = parse_to_ast(text, &Default::default(), &Default::default()).unwrap();
+    assert_eq!(result.value.unwrap().as_object().unwrap().properties.len(), 3);
+
+    let options = ParseOptions {
+      duplicate_key_policy: DuplicateKeyPolicy::Error,
+      ..Default::default()
+    };
+    let err = parse_to_ast(text, &Default::default(), &options).err().unwrap();
+    assert_eq!(err.to_string(), "Duplicate object key 'a' on line 1 column 28");
+    assert_eq!(err.range(), Range::new(27, 30));
+
+    let result = parse_to_ast_recovering(text, &Default::default(), &options);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.value.unwrap().as_object().unwrap().properties.len(), 3);
+  }
+
+  #[test]
+  fn recovering_should_report_multiple_root_values() {
+    let value = assert_recovered_errors(
+      "[] [",
+      &["Text cannot contain more than one JSON value on line 1 column 4"],
+    );
+    assert!(value.unwrap().as_array().is_some());
+    assert_recovered_errors("]", &["Unexpected close bracket on line 1 column 1"]);
+  }
 }
@@ -1,3 +1,4 @@
+use super::DuplicateKeyPolicy;
 use super::ParseOptions;
 use super::errors::*;
 use super::tokens::Token;
@@ -19,13 +20,14 @@ use crate::parser::JsoncParser;
 pub fn parse_to_value<'a>(text: &'a str, options: &ParseOptions) -> Result<Option<JsonValue<'a>>, ParseError> {
   let mut parser = JsoncParser::new(text, options);
 
-  let token = parser.scan()?;
-  let value = match token {
+  parser.scan()?;
+  let value = match parser.token() {
     Some(token) => parse_value(&mut parser, token)?,
     None => return Ok(None),
   };
 
-  if parser.scan()?.is_some() {
+  parser.scan()?;
+  if parser.current_token().is_some() {
     return Err(
       parser
         .scanner
@@ -36,7 +38,7 @@ pub fn parse_to_value<'a>(text: &'a str, options: &ParseOptions) -> Result<Optio
   Ok(Some(value))
 }
 
-fn parse_value<'a>(parser: &mut JsoncParser<'a>, token: Token<'a>) -> Result<JsonValue<'a>, ParseError> {
+pub(crate) fn parse_value<'a>(parser: &mut JsoncParser<'a>, token: Token<'a>) -> Result<JsonValue<'a>, ParseError> {
   match token {
     Token::OpenBrace => parse_object(parser),
     Token::OpenBracket => parse_array(parser),
@@ -44,7 +46,7 @@ fn parse_value<'a>(parser: &mut JsoncParser<'a>, token: Token<'a>) -> Result<Jso
     Token::Number(n) => Ok(JsonValue::Number(n)),
     Token::Boolean(b) => Ok(JsonValue::Boolean(b)),
     Token::Null => Ok(JsonValue::Null),
-    other => Err(parser.unexpected_token_error(&other)),
+    _ => Err(parser.unexpected_token_error()),
   }
 }
 
@@ -55,15 +57,24 @@ fn parse_object<'a>(parser: &mut JsoncParser<'a>) -> Result<JsonValue<'a>, Parse
 
   loop {
     match parser.scan_object_entry(first)? {
-      None => break,
-      Some(key) => {
+      false => break,
+      true => {
         first = false;
-        let key_string = key.into_cow();
+        let key_string = parser.object_key();
+        let is_duplicate =
+          parser.duplicate_key_policy != DuplicateKeyPolicy::LastWins && props.contains_key(&key_string);
+        if is_duplicate && parser.duplicate_key_policy == DuplicateKeyPolicy::Error {
+          return Err(parser.duplicate_key_error(&key_string));
+        }
         parser.scan_object_colon()?;
-        match parser.scan()? {
+        parser.scan()?;
+        match parser.token() {
           Some(value_token) => {
             let value = parse_value(parser, value_token)?;
-            props.insert(key_string, value);
+            // duplicates only reach here with the first wins policy
+            if !is_duplicate {
+              props.insert(key_string, value);
+            }
           }
           None => {
             parser.exit_container();
@@ -86,10 +97,10 @@ fn parse_array<'a>(parser: &mut JsoncParser<'a>) -> Result<JsonValue<'a>, ParseE
   parser.enter_container()?;
   let mut elements = Vec::new();
 
-  let mut token = parser.scan()?;
+  parser.scan()?;
 
   loop {
-    match token {
+    match parser.token() {
       Some(Token::CloseBracket) => break,
       None => {
         parser.exit_container();
@@ -103,7 +114,7 @@ fn parse_array<'a>(parser: &mut JsoncParser<'a>) -> Result<JsonValue<'a>, ParseE
         elements.push(parse_value(parser, value_token)?);
       }
     }
-    token = parser.scan_array_comma()?;
+    parser.scan_array_comma()?;
   }
 
   parser.exit_container();
@@ -343,18 +354,7 @@ mod tests {
 
   #[track_caller]
   fn assert_has_strict_error(text: &str, message: &str) {
-    let result = parse_to_value(
-      text,
-      &ParseOptions {
-        allow_comments: false,
-        allow_loose_object_property_names: false,
-        allow_trailing_commas: false,
-        allow_missing_commas: false,
-        allow_single_quoted_strings: false,
-        allow_hexadecimal_numbers: false,
-        allow_unary_plus_numbers: false,
-      },
-    );
+    let result = parse_to_value(text, &ParseOptions::strict_json());
     match result {
       Ok(_) => panic!("Expected error, but did not find one."),
       Err(err) => assert_eq!(err.to_string(), message),
@@ -479,6 +479,19 @@ mod tests {
     assert_has_strict_error(text, "Expected comma on line 2 column 18");
   }
 
+  #[test]
+  fn missing_comma_between_array_elements() {
+    let text = "[1 \"a\" [] {}]";
+    let value = parse_to_value(text, &Default::default()).unwrap().unwrap();
+    match value {
+      JsonValue::Array(elements) => assert_eq!(elements.len(), 4),
+      _ => panic!("Expected array"),
+    }
+
+    // but is strict when strict
+    assert_has_strict_error(text, "Expected comma on line 1 column 3");
+  }
+
   #[test]
   fn missing_comma_with_comment_between_properties() {
     // when comments are allowed but missing commas are not,
@@ -525,6 +538,148 @@ mod tests {
     assert_eq!(obj.get_number("test").unwrap(), "+42");
   }
 
+  #[test]
+  fn it_should_parse_json5() {
+    let text = r#"// https://spec.json5.org/#introduction
+{
+  // comments
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  lineBreaks: "Look, Mom! \
+No \\n's!",
+  hexadecimal: 0xdecaf,
+  leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+  positiveSign: +1,
+  trailingComma: 'in objects', andIn: ['arrays',],
+  "backwardsCompatible": "with JSON",
+  special: [Infinity, -NaN, '\x41\v'],
+}"#;
+    let value = parse_to_value(text, &ParseOptions::json5()).unwrap().unwrap();
+    let obj = match &value {
+      JsonValue::Object(o) => o,
+      _ => panic!("Expected object"),
+    };
+    assert_eq!(obj.get_string("lineBreaks").unwrap(), "Look, Mom! No \\n's!");
+    assert_eq!(obj.get_number("leadingDecimalPoint").unwrap(), ".8675309");
+    assert_eq!(obj.get_number("andTrailing").unwrap(), "8675309.");
+    assert_eq!(
+      obj.get_array("special").unwrap().clone().take_inner(),
+      vec![
+        JsonValue::Number("Infinity"),
+        JsonValue::Number("-NaN"),
+        JsonValue::String(Cow::Borrowed("A\u{0B}")),
+      ]
+    );
+  }
+
+  #[test]
+  fn it_should_parse_json5_identifiers() {
+    let text = "{\u{FEFF}null: 2, true: 3, false: 4, a\\u0062: 1, \\u0024c: 5, a\u{200C}b\u{200D}c: 6,}";
+    let value = parse_to_value(text, &ParseOptions::json5()).unwrap().unwrap();
+    let obj = match &value {
+      JsonValue::Object(o) => o,
+      _ => panic!("Expected object"),
+    };
+    let mut keys = obj.clone().take_inner().into_keys().collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, ["$c", "ab", "a\u{200C}b\u{200D}c", "false", "null", "true"]);
+    assert_eq!(obj.get_number("ab").unwrap(), "1");
+
+    assert_has_strict_error("{null: 1}", "Expected string for object property on line 1 column 2");
+  }
+
+  #[test]
+  fn it_should_error_for_strict_json_extensions() {
+    assert_has_strict_error(
+      "\"a\tb\"",
+      "Control characters must be escaped in strings on line 1 column 3",
+    );
+    assert_has_strict_error("[007]", "Leading zeros on numbers are not allowed on line 1 column 2");
+    assert_has_strict_error("007", "Leading zeros on numbers are not allowed on line 1 column 1");
+    assert_has_strict_error("-01", "Leading zeros on numbers are not allowed on line 1 column 1");
+    assert_has_strict_error(
+      r#""\uD800""#,
+      "Invalid unicode escape sequence. 'D800 (unpaired high surrogate)' is not a valid UTF8 character on line 1 column 2",
+    );
+  }
+
+  #[test]
+  fn it_should_allow_strict_json_extensions_by_default() {
+    let value = parse_to_value("[\"a\tb\"]", &Default::default()).unwrap().unwrap();
+    assert_eq!(
+      value,
+      JsonValue::Array(vec![JsonValue::String(Cow::Borrowed("a\tb"))].into())
+    );
+  }
+
+  #[test]
+  fn it_should_only_allow_leading_zeros_when_enabled() {
+    // without the option, a leading zero ends the number as it always has
+    let value = parse_to_value("[007]", &Default::default()).unwrap().unwrap();
+    assert_eq!(
+      value,
+      JsonValue::Array(vec![JsonValue::Number("0"), JsonValue::Number("0"), JsonValue::Number("7")].into())
+    );
+    assert!(parse_to_value("007", &Default::default()).is_err());
+
+    let options = ParseOptions {
+      allow_leading_zeros: true,
+      ..Default::default()
+    };
+    let value = parse_to_value("[007]", &options).unwrap().unwrap();
+    assert_eq!(value, JsonValue::Array(vec![JsonValue::Number("007")].into()));
+  }
+
+  #[test]
+  fn it_should_parse_with_presets() {
+    let text = "{\n  // comment\n  \"a\": [1, 2,],\n}";
+    assert!(parse_to_value(text, &ParseOptions::strict_json()).is_err());
+    assert_eq!(
+      parse_to_value(text, &ParseOptions::jsonc()).err().unwrap().to_string(),
+      "Trailing commas are not allowed on line 3 column 13"
+    );
+    assert!(parse_to_value(text, &ParseOptions::vscode_settings()).is_ok());
+    assert!(parse_to_value(text, &ParseOptions::tsconfig()).is_ok());
+    assert_eq!(
+      parse_to_value("{ a: 1 }", &ParseOptions::vscode_settings())
+        .err()
+        .unwrap()
+        .to_string(),
+      "Expected string for object property on line 1 column 3"
+    );
+  }
+
+  #[test]
+  fn it_should_error_for_json5_by_default() {
+    assert_has_error(r#"{ "a": Infinity }"#, "Unexpected word on line 1 column 8");
+    assert_has_error("[5.]", "Expected digit on line 1 column 4");
+    assert_has_error(r#"["\x41"]"#, "Invalid escape on line 1 column 3");
+  }
+
+  #[test]
+  fn it_should_handle_duplicate_keys_with_policy() {
+    let text = r#"{ "a": 1, "b": 2, a: 3 }"#;
+    let parse = |policy| {
+      parse_to_value(
+        text,
+        &ParseOptions {
+          duplicate_key_policy: policy,
+          ..Default::default()
+        },
+      )
+    };
+    let get_a = |value: Option<JsonValue>| match value {
+      Some(JsonValue::Object(obj)) => obj.get_number("a").unwrap().to_string(),
+      _ => panic!("Expected object"),
+    };
+    assert_eq!(get_a(parse(DuplicateKeyPolicy::LastWins).unwrap()), "3");
+    assert_eq!(get_a(parse(DuplicateKeyPolicy::FirstWins).unwrap()), "1");
+    let err = parse(DuplicateKeyPolicy::Error).err().unwrap();
+    assert_eq!(err.to_string(), "Duplicate object key 'a' on line 1 column 19");
+    assert_eq!(err.range().start, 18);
+    assert_eq!(err.range().end, 19);
+  }
+
   #[test]
   fn it_should_parse_large_shallow_objects() {
     // makes sure that nesting depth limit does not affect shallow objects
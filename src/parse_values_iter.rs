@@ -0,0 +1,225 @@
+use super::ParseOptions;
+use super::common::Range;
+use super::errors::*;
+use super::tokens::Token;
+use super::value::JsonValue;
+use crate::parse_to_value::parse_value;
+use crate::parser::JsoncParser;
+
+/// How multiple root values are separated in the text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ValuesSeparator {
+  /// Each value must start on a new line, such as in JSON Lines and NDJSON.
+  #[default]
+  Newline,
+  /// Values may be separated by any whitespace or follow each other directly.
+  Whitespace,
+}
+
+/// A root value along with its position in the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootValue<T> {
+  /// Start and end position of the value.
+  pub range: Range,
+  /// The parsed value.
+  pub value: T,
+}
+
+/// Parses a string containing multiple JSONC values to an iterator
+/// of `JsonValue`s.
+///
+/// An error for one value doesn't stop the iterator. It continues with the
+/// next line after the value that failed for `ValuesSeparator::Newline` or
+/// the next line after the error for `ValuesSeparator::Whitespace`.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::JsonValue;
+/// use jsonc_parser::ValuesSeparator;
+/// use jsonc_parser::parse_values_iter;
+///
+/// let text = r#"{ "level": "info" } // first
+/// { "level": "warn", }
+/// { "level": }
+/// "done"
+/// "#;
+/// let results = parse_values_iter(text, &Default::default(), ValuesSeparator::Newline).collect::<Vec<_>>();
+/// assert_eq!(results.len(), 4);
+/// assert_eq!(results[0].as_ref().unwrap().range.start, 0);
+/// assert!(results[2].is_err());
+/// assert_eq!(results[3].as_ref().unwrap().value, JsonValue::String("done".into()));
+/// ```
+pub fn parse_values_iter<'a>(text: &'a str, options: &ParseOptions, separator: ValuesSeparator) -> ParseValuesIter<'a> {
+  ParseValuesIter {
+    values: RootValuesParser::new(text, options, separator),
+  }
+}
+
+/// Iterator of the values in a string. See `parse_values_iter`.
+pub struct ParseValuesIter<'a> {
+  values: RootValuesParser<'a>,
+}
+
+impl<'a> Iterator for ParseValuesIter<'a> {
+  type Item = Result<RootValue<JsonValue<'a>>, ParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.values.next(parse_value)
+  }
+}
+
+/// Parses one root value after another, moving past errors.
+pub(crate) struct RootValuesParser<'a> {
+  parser: JsoncParser<'a>,
+  text: &'a str,
+  separator: ValuesSeparator,
+  previous_end: Option<usize>,
+  is_done: bool,
+}
+
+impl<'a> RootValuesParser<'a> {
+  pub fn new(text: &'a str, options: &ParseOptions, separator: ValuesSeparator) -> Self {
+    Self {
+      parser: JsoncParser::new(text, options),
+      text,
+      separator,
+      previous_end: None,
+      is_done: false,
+    }
+  }
+
+  /// Parses the next root value with the provided function, which
+  /// receives the first token of the value.
+  pub fn next<T>(
+    &mut self,
+    parse: impl FnOnce(&mut JsoncParser<'a>, Token<'a>) -> Result<T, ParseError>,
+  ) -> Option<Result<RootValue<T>, ParseError>> {
+    if self.is_done {
+      return None;
+    }
+
+    let token = match self.parser.scan().map(|_| self.parser.token()) {
+      Ok(Some(token)) => token,
+      Ok(None) => {
+        self.is_done = true;
+        return None;
+      }
+      Err(err) => {
+        self.recover(self.parser.scanner.token_start(), &err);
+        return Some(Err(err));
+      }
+    };
+    let start = self.parser.scanner.token_start();
+    if self.separator == ValuesSeparator::Newline
+      && let Some(previous_end) = self.previous_end
+      && !self.text[previous_end..start].contains('\n')
+    {
+      let err = self
+        .parser
+        .scanner
+        .create_error_for_current_token(ParseErrorKind::ExpectedNewline);
+      self.recover(start, &err);
+      return Some(Err(err));
+    }
+
+    match parse(&mut self.parser, token) {
+      Ok(value) => {
+        let end = self.parser.scanner.token_end();
+        self.previous_end = Some(end);
+        Some(Ok(RootValue {
+          range: Range::new(start, end),
+          value,
+        }))
+      }
+      Err(err) => {
+        self.recover(start, &err);
+        Some(Err(err))
+      }
+    }
+  }
+
+  /// Moves to the start of the next line in order to continue after an error.
+  fn recover(&mut self, value_start: usize, err: &ParseError) {
+    let from = match self.separator {
+      ValuesSeparator::Newline => value_start,
+      ValuesSeparator::Whitespace => value_start.max(err.range().start),
+    };
+    match self.text[from..].find('\n') {
+      Some(index) => {
+        self.parser.reset_to(from + index + 1);
+        self.previous_end = None;
+      }
+      None => self.is_done = true,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn collect(text: &str, separator: ValuesSeparator) -> Vec<String> {
+    parse_values_iter(text, &Default::default(), separator)
+      .map(|result| match result {
+        Ok(item) => format!(
+          "{}..{} {}",
+          item.range.start,
+          item.range.end,
+          &text[item.range.start..item.range.end]
+        ),
+        Err(err) => err.to_string(),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn it_should_parse_newline_delimited_values() {
+    let text = "// header\n{ \"a\": 1 }\n\n[1,\n  2] /* trailing */\n\"text\"\n";
+    assert_eq!(
+      collect(text, ValuesSeparator::Newline),
+      vec!["10..20 { \"a\": 1 }", "22..30 [1,\n  2]", "46..52 \"text\""]
+    );
+    assert_eq!(collect("", ValuesSeparator::Newline), Vec::<String>::new());
+    assert_eq!(
+      collect("  // only a comment\n", ValuesSeparator::Newline),
+      Vec::<String>::new()
+    );
+  }
+
+  #[test]
+  fn it_should_parse_whitespace_separated_values() {
+    assert_eq!(
+      collect("1 2{}[]\"a\"null", ValuesSeparator::Whitespace),
+      vec!["0..1 1", "2..3 2", "3..5 {}", "5..7 []", "7..10 \"a\"", "10..14 null"]
+    );
+  }
+
+  #[test]
+  fn it_should_continue_after_errors() {
+    let text = "{ \"a\": 1 }\n{ \"b\": }\n1 2\n[1,\n{ \"c\": 3 }\n";
+    assert_eq!(
+      collect(text, ValuesSeparator::Newline),
+      vec![
+        "0..10 { \"a\": 1 }".to_string(),
+        "Unexpected close brace on line 2 column 8".to_string(),
+        "20..21 1".to_string(),
+        "Expected newline between values on line 3 column 3".to_string(),
+        // recovers at the line after the start of the array
+        "Unterminated array on line 6 column 1".to_string(),
+        "28..38 { \"c\": 3 }".to_string(),
+      ]
+    );
+    assert_eq!(
+      collect("1 ] 2\n3 [\n", ValuesSeparator::Whitespace),
+      vec![
+        "0..1 1".to_string(),
+        "Unexpected close bracket on line 1 column 3".to_string(),
+        "6..7 3".to_string(),
+        "Unterminated array on line 3 column 1".to_string(),
+      ]
+    );
+  }
+}
@@ -1,98 +1,199 @@
 use std::borrow::Cow;
 
+use crate::DuplicateKeyPolicy;
 use crate::ParseOptions;
 use crate::common::Range;
 use crate::errors::*;
 use crate::scanner::Scanner;
-use crate::scanner::ScannerOptions;
+use crate::string::decode_word;
 use crate::tokens::Token;
+use crate::tokens::TokenAndRange;
 
-pub(crate) enum ObjectKey<'a> {
-  String(Cow<'a, str>),
-  Word(&'a str),
-}
+/// Maximum depth of nested objects and arrays.
+pub(crate) const MAX_NESTING_DEPTH: usize = 512;
 
-impl<'a> ObjectKey<'a> {
-  /// Converts the key into a `Cow`, borrowing from the source when possible
-  /// to avoid an allocation for clean (unescaped) keys.
-  pub fn into_cow(self) -> Cow<'a, str> {
-    match self {
-      ObjectKey::String(s) => s,
-      ObjectKey::Word(s) => Cow::Borrowed(s),
-    }
+/// Gets the error kind for a token found where a value was expected.
+pub(crate) fn unexpected_token_error_kind(token: &Token) -> ParseErrorKind {
+  match token {
+    Token::CloseBracket => ParseErrorKind::UnexpectedCloseBracket,
+    Token::CloseBrace => ParseErrorKind::UnexpectedCloseBrace,
+    Token::Comma => ParseErrorKind::UnexpectedComma,
+    Token::Colon => ParseErrorKind::UnexpectedColon,
+    Token::Word(_) => ParseErrorKind::UnexpectedWord,
+    _ => ParseErrorKind::UnexpectedToken,
   }
 }
 
-/// Shared JSONC parser infrastructure used by both `parse_to_value` and
-/// the serde deserializer. Handles scanning, comment skipping, depth
+/// Provides the tokens for `JsoncParser`.
+///
+/// This is implemented by `Scanner` for text in memory and by
+/// `ReaderScanner` for a stream, where a token only lives until the next
+/// token is scanned.
+pub(crate) trait TokenSource<'a> {
+  /// Position of a token that can be used to create an error once the
+  /// source has moved past the token.
+  type Position: Copy;
+
+  /// Moves to the next token.
+  fn move_next_token(&mut self) -> Result<(), ParseError>;
+
+  /// Gets the current token.
+  fn current_token(&self) -> Option<Token<'_>>;
+
+  /// Gets the current token when it borrows from text that lives for `'a`.
+  /// Returns `None` when tokens are only kept until the next token is scanned.
+  fn borrowed_token(&self) -> Option<Token<'a>>;
+
+  /// Gets the start position of the current token.
+  fn token_start(&self) -> usize;
+
+  /// Gets the end position of the current token.
+  fn token_end(&self) -> usize;
+
+  /// Gets the position of the current token.
+  fn token_position(&self) -> Self::Position;
+
+  /// Gets the position directly after the current token.
+  fn token_end_position(&self) -> Self::Position;
+
+  /// Gets the 1-indexed line and column of the start of the position.
+  #[cfg(feature = "serde")]
+  fn line_and_column_display(&self, position: Self::Position) -> (usize, usize);
+
+  fn create_error_at(&self, position: Self::Position, kind: ParseErrorKind) -> ParseError;
+
+  fn create_error_for_current_token(&self, kind: ParseErrorKind) -> ParseError;
+}
+
+/// Shared JSONC parser infrastructure used by `parse_to_value`, the serde
+/// deserializer, and `from_reader`. Handles scanning, comment skipping, depth
 /// tracking, and comma/separator logic.
-pub(crate) struct JsoncParser<'a> {
-  pub scanner: Scanner<'a>,
-  #[allow(dead_code)] // used by the serde feature
-  pub text: &'a str,
+///
+/// The parser moves through the tokens of its source, which makes the
+/// token it's on available via `current_token()`.
+pub(crate) struct JsoncParser<'a, S = Scanner<'a>> {
+  pub scanner: S,
+  pub duplicate_key_policy: DuplicateKeyPolicy,
   allow_comments: bool,
   allow_trailing_commas: bool,
   allow_missing_commas: bool,
   allow_loose_object_property_names: bool,
   depth: usize,
-  pending_token: Option<Token<'a>>,
+  /// If the next `scan()` should stay on the current token.
+  is_put_back: bool,
+  /// Comments skipped by `scan()` when collecting them.
+  comments: Option<Vec<TokenAndRange<'a>>>,
 }
 
 impl<'a> JsoncParser<'a> {
   pub fn new(text: &'a str, options: &ParseOptions) -> Self {
+    Self::with_source(Scanner::new(text, &options.scanner_options()), options)
+  }
+
+  /// Gets the current token borrowed from the text.
+  pub fn token(&self) -> Option<Token<'a>> {
+    self.scanner.token()
+  }
+
+  /// Moves the parser to the provided byte index in order to continue
+  /// parsing from there after an error.
+  pub fn reset_to(&mut self, byte_index: usize) {
+    self.scanner.reset_to(byte_index);
+    self.depth = 0;
+    self.is_put_back = false;
+  }
+}
+
+impl<'a, S: TokenSource<'a>> JsoncParser<'a, S> {
+  pub fn with_source(scanner: S, options: &ParseOptions) -> Self {
     Self {
-      scanner: Scanner::new(
-        text,
-        &ScannerOptions {
-          allow_single_quoted_strings: options.allow_single_quoted_strings,
-          allow_hexadecimal_numbers: options.allow_hexadecimal_numbers,
-          allow_unary_plus_numbers: options.allow_unary_plus_numbers,
-        },
-      ),
-      text,
+      scanner,
+      duplicate_key_policy: options.duplicate_key_policy,
       allow_comments: options.allow_comments,
       allow_trailing_commas: options.allow_trailing_commas,
       allow_missing_commas: options.allow_missing_commas,
       allow_loose_object_property_names: options.allow_loose_object_property_names,
       depth: 0,
-      pending_token: None,
+      is_put_back: false,
+      comments: None,
     }
   }
 
-  /// Scans the next non-comment token. Returns a pending token if one
-  /// was put back via `put_back`.
-  pub fn scan(&mut self) -> Result<Option<Token<'a>>, ParseError> {
-    if let Some(token) = self.pending_token.take() {
-      return Ok(Some(token));
+  /// Keeps the comments skipped by `scan()` so they can be taken with `take_comments()`.
+  pub fn collect_comments(&mut self) {
+    self.comments.get_or_insert_with(Vec::new);
+  }
+
+  /// Takes the comments skipped since the last call.
+  pub fn take_comments(&mut self) -> Vec<TokenAndRange<'a>> {
+    self.comments.as_mut().map(std::mem::take).unwrap_or_default()
+  }
+
+  /// Gets the current token.
+  pub fn current_token(&self) -> Option<Token<'_>> {
+    self.scanner.current_token()
+  }
+
+  /// Moves to the next non-comment token. Stays on the current token
+  /// when it was put back via `put_back`.
+  pub fn scan(&mut self) -> Result<(), ParseError> {
+    if self.is_put_back {
+      self.is_put_back = false;
+      return Ok(());
     }
     loop {
-      match self.scanner.scan()? {
-        Some(Token::CommentLine(_) | Token::CommentBlock(_)) => {
-          if !self.allow_comments {
-            return Err(
-              self
-                .scanner
-                .create_error_for_current_token(ParseErrorKind::CommentsNotAllowed),
-            );
-          }
-          continue;
+      self.scanner.move_next_token()?;
+      if let Some(Token::CommentLine(_) | Token::CommentBlock(_)) = self.scanner.current_token() {
+        if !self.allow_comments {
+          return Err(
+            self
+              .scanner
+              .create_error_for_current_token(ParseErrorKind::CommentsNotAllowed),
+          );
         }
-        token => return Ok(token),
+        if let Some(comments) = self.comments.as_mut()
+          && let Some(token) = self.scanner.borrowed_token()
+        {
+          comments.push(TokenAndRange {
+            range: Range::new(self.scanner.token_start(), self.scanner.token_end()),
+            token,
+          });
+        }
+        continue;
       }
+      return Ok(());
     }
   }
 
-  /// Puts a token back so the next `scan()` returns it.
+  /// Gets the key of the object entry found by `scan_object_entry`, borrowing
+  /// it from the text when possible to avoid an allocation for clean
+  /// (unescaped) keys.
+  pub fn object_key(&self) -> Cow<'a, str> {
+    match self.scanner.borrowed_token() {
+      Some(Token::String(key)) => key,
+      Some(Token::Word(key)) => decode_word(key),
+      Some(Token::Number(key)) => Cow::Borrowed(key),
+      _ => {
+        let token = self.scanner.current_token().expect("scan_object_entry found a key");
+        match token {
+          Token::Word(key) => Cow::Owned(decode_word(key).into_owned()),
+          token => Cow::Owned(token.as_str().to_string()),
+        }
+      }
+    }
+  }
+
+  /// Makes the next `scan()` stay on the current token.
   #[cfg(feature = "serde")]
-  pub fn put_back(&mut self, token: Token<'a>) {
-    debug_assert!(self.pending_token.is_none(), "put_back called with pending token");
-    self.pending_token = Some(token);
+  pub fn put_back(&mut self) {
+    debug_assert!(!self.is_put_back, "put_back called with pending token");
+    self.is_put_back = true;
   }
 
   /// Increments depth and checks the nesting limit.
   pub fn enter_container(&mut self) -> Result<(), ParseError> {
     self.depth += 1;
-    if self.depth > 512 {
+    if self.depth > MAX_NESTING_DEPTH {
       self.depth -= 1;
       Err(
         self
@@ -109,61 +210,64 @@ impl<'a> JsoncParser<'a> {
     self.depth -= 1;
   }
 
-  /// Returns an error appropriate for an unexpected token.
-  pub fn unexpected_token_error(&self, token: &Token) -> ParseError {
-    let kind = match token {
-      Token::CloseBracket => ParseErrorKind::UnexpectedCloseBracket,
-      Token::CloseBrace => ParseErrorKind::UnexpectedCloseBrace,
-      Token::Comma => ParseErrorKind::UnexpectedComma,
-      Token::Colon => ParseErrorKind::UnexpectedColon,
-      Token::Word(_) => ParseErrorKind::UnexpectedWord,
-      _ => ParseErrorKind::UnexpectedToken,
+  /// Returns an error appropriate for the current token found where a
+  /// value was expected.
+  pub fn unexpected_token_error(&self) -> ParseError {
+    let kind = match self.scanner.current_token() {
+      Some(token) => unexpected_token_error_kind(&token),
+      None => ParseErrorKind::UnexpectedToken,
     };
     self.scanner.create_error_for_current_token(kind)
   }
 
-  /// Scans the next object entry (key or close brace), handling commas
-  /// between entries. Pass `first = true` for the first entry.
-  pub fn scan_object_entry(&mut self, first: bool) -> Result<Option<ObjectKey<'a>>, ParseError> {
+  /// Returns an error for a duplicate object key. Must be called
+  /// directly after the key was scanned.
+  pub fn duplicate_key_error(&self, key: &str) -> ParseError {
+    self
+      .scanner
+      .create_error_for_current_token(ParseErrorKind::DuplicateObjectKey(key.to_string()))
+  }
+
+  /// Attaches the position to an error that doesn't have one yet.
+  #[cfg(feature = "serde")]
+  pub fn with_position(&self, err: ParseError, position: S::Position) -> ParseError {
+    if err.has_position() {
+      err
+    } else {
+      self.scanner.create_error_at(position, err.into_kind())
+    }
+  }
+
+  /// Moves to the next object entry, handling commas between entries, and
+  /// returns if it's a key rather than the close brace. Pass `first = true`
+  /// for the first entry.
+  pub fn scan_object_entry(&mut self, first: bool) -> Result<bool, ParseError> {
     if first {
       return self.scan_object_key();
     }
 
-    let after_value_end = self.scanner.token_end();
-    let token = self.scan()?;
-    match token {
+    let after_value_end = self.scanner.token_end_position();
+    self.scan()?;
+    match self.scanner.current_token() {
       Some(Token::Comma) => {
-        let comma_range = Range::new(self.scanner.token_start(), self.scanner.token_end());
-        let key = self.scan_object_key()?;
-        if key.is_none() && !self.allow_trailing_commas {
+        let comma_position = self.scanner.token_position();
+        let has_key = self.scan_object_key()?;
+        if !has_key && !self.allow_trailing_commas {
           return Err(
             self
               .scanner
-              .create_error_for_range(comma_range, ParseErrorKind::TrailingCommasNotAllowed),
+              .create_error_at(comma_position, ParseErrorKind::TrailingCommasNotAllowed),
           );
         }
-        Ok(key)
-      }
-      Some(Token::CloseBrace) => Ok(None),
-      Some(Token::String(s)) if self.allow_missing_commas => Ok(Some(ObjectKey::String(s))),
-      Some(Token::Word(s) | Token::Number(s)) if self.allow_missing_commas => {
-        if !self.allow_loose_object_property_names {
-          return Err(
-            self
-              .scanner
-              .create_error_for_current_token(ParseErrorKind::ExpectedStringObjectProperty),
-          );
-        }
-        Ok(Some(ObjectKey::Word(s)))
-      }
-      Some(Token::String(_) | Token::Word(_) | Token::Number(_)) => {
-        let range = Range::new(after_value_end, after_value_end);
-        Err(
-          self
-            .scanner
-            .create_error_for_range(range, ParseErrorKind::ExpectedComma),
-        )
+        Ok(has_key)
       }
+      Some(Token::CloseBrace) => Ok(false),
+      Some(token) if token.is_object_key() && self.allow_missing_commas => self.check_object_key(),
+      Some(token) if token.is_object_key() => Err(
+        self
+          .scanner
+          .create_error_at(after_value_end, ParseErrorKind::ExpectedComma),
+      ),
       None => Err(
         self
           .scanner
@@ -179,7 +283,8 @@ impl<'a> JsoncParser<'a> {
 
   /// Scans an object property colon separator.
   pub fn scan_object_colon(&mut self) -> Result<(), ParseError> {
-    match self.scan()? {
+    self.scan()?;
+    match self.scanner.current_token() {
       Some(Token::Colon) => Ok(()),
       _ => Err(
         self
@@ -190,39 +295,37 @@ impl<'a> JsoncParser<'a> {
   }
 
   /// After an array element, scans for the comma/close-bracket and
-  /// returns the next token.
-  pub fn scan_array_comma(&mut self) -> Result<Option<Token<'a>>, ParseError> {
-    let token = self.scan()?;
-    if matches!(&token, Some(Token::Comma)) {
-      let comma_range = Range::new(self.scanner.token_start(), self.scanner.token_end());
-      let next = self.scan()?;
-      if matches!(&next, Some(Token::CloseBracket)) && !self.allow_trailing_commas {
-        return Err(
-          self
-            .scanner
-            .create_error_for_range(comma_range, ParseErrorKind::TrailingCommasNotAllowed),
-        );
-      }
-      Ok(next)
-    } else {
-      Ok(token)
-    }
-  }
-
-  fn scan_object_key(&mut self) -> Result<Option<ObjectKey<'a>>, ParseError> {
-    match self.scan()? {
-      Some(Token::CloseBrace) => Ok(None),
-      Some(Token::String(s)) => Ok(Some(ObjectKey::String(s))),
-      Some(Token::Word(s) | Token::Number(s)) => {
-        if !self.allow_loose_object_property_names {
+  /// moves to the token after it.
+  pub fn scan_array_comma(&mut self) -> Result<(), ParseError> {
+    let after_value_end = self.scanner.token_end_position();
+    self.scan()?;
+    match self.scanner.current_token() {
+      Some(Token::Comma) => {
+        let comma_position = self.scanner.token_position();
+        self.scan()?;
+        if matches!(self.scanner.current_token(), Some(Token::CloseBracket)) && !self.allow_trailing_commas {
           return Err(
             self
               .scanner
-              .create_error_for_current_token(ParseErrorKind::ExpectedStringObjectProperty),
+              .create_error_at(comma_position, ParseErrorKind::TrailingCommasNotAllowed),
           );
         }
-        Ok(Some(ObjectKey::Word(s)))
+        Ok(())
       }
+      Some(token) if token.is_value_start() && !self.allow_missing_commas => Err(
+        self
+          .scanner
+          .create_error_at(after_value_end, ParseErrorKind::ExpectedComma),
+      ),
+      _ => Ok(()),
+    }
+  }
+
+  fn scan_object_key(&mut self) -> Result<bool, ParseError> {
+    self.scan()?;
+    match self.scanner.current_token() {
+      Some(Token::CloseBrace) => Ok(false),
+      Some(token) if token.is_object_key() => self.check_object_key(),
       None => Err(
         self
           .scanner
@@ -235,4 +338,15 @@ impl<'a> JsoncParser<'a> {
       ),
     }
   }
+
+  fn check_object_key(&self) -> Result<bool, ParseError> {
+    if !matches!(self.scanner.current_token(), Some(Token::String(_))) && !self.allow_loose_object_property_names {
+      return Err(
+        self
+          .scanner
+          .create_error_for_current_token(ParseErrorKind::ExpectedStringObjectProperty),
+      );
+    }
+    Ok(true)
+  }
 }
@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+
+/// Splits a JSON Pointer (RFC 6901) into its unescaped reference tokens.
+///
+/// Returns `None` when the pointer is malformed, which is when it's not
+/// empty and doesn't start with a `/` or it contains an invalid `~` escape.
+pub(crate) fn parse_pointer(pointer: &str) -> Option<Vec<Cow<'_, str>>> {
+  if pointer.is_empty() {
+    return Some(Vec::new());
+  }
+  pointer.strip_prefix('/')?.split('/').map(unescape_token).collect()
+}
+
+fn unescape_token(token: &str) -> Option<Cow<'_, str>> {
+  if !token.contains('~') {
+    return Some(Cow::Borrowed(token));
+  }
+  let mut text = String::with_capacity(token.len());
+  let mut chars = token.chars();
+  while let Some(c) = chars.next() {
+    if c == '~' {
+      match chars.next() {
+        Some('0') => text.push('~'),
+        Some('1') => text.push('/'),
+        _ => return None,
+      }
+    } else {
+      text.push(c);
+    }
+  }
+  Some(Cow::Owned(text))
+}
+
+/// Escapes a reference token for use in a JSON Pointer.
+pub(crate) fn escape_token(token: &str) -> Cow<'_, str> {
+  if token.contains(['~', '/']) {
+    Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+  } else {
+    Cow::Borrowed(token)
+  }
+}
+
+/// Parses a reference token used on an array, which must be
+/// a base 10 number without leading zeros.
+pub(crate) fn parse_array_index(token: &str) -> Option<usize> {
+  if token.is_empty() || !token.bytes().all(|b| b.is_ascii_digit()) || token.len() > 1 && token.starts_with('0') {
+    return None;
+  }
+  token.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn it_should_parse_pointer() {
+    assert_eq!(parse_pointer(""), Some(vec![]));
+    assert_eq!(parse_pointer("/"), Some(vec![Cow::Borrowed("")]));
+    assert_eq!(parse_pointer("/a/0/b"), Some(vec!["a".into(), "0".into(), "b".into()]));
+    assert_eq!(
+      parse_pointer("/a~1b/m~0n/~01"),
+      Some(vec!["a/b".into(), "m~n".into(), "~1".into()])
+    );
+    assert_eq!(parse_pointer("a"), None);
+    assert_eq!(parse_pointer("/a~2"), None);
+    assert_eq!(parse_pointer("/a~"), None);
+  }
+
+  #[test]
+  fn it_should_escape_token() {
+    assert_eq!(escape_token("a"), "a");
+    assert_eq!(escape_token("a/b~c"), "a~1b~0c");
+    assert_eq!(
+      parse_pointer(&format!("/{}", escape_token("~1/"))),
+      Some(vec!["~1/".into()])
+    );
+  }
+
+  #[test]
+  fn it_should_parse_array_index() {
+    assert_eq!(parse_array_index("0"), Some(0));
+    assert_eq!(parse_array_index("10"), Some(10));
+    assert_eq!(parse_array_index("01"), None);
+    assert_eq!(parse_array_index("-"), None);
+    assert_eq!(parse_array_index("+1"), None);
+    assert_eq!(parse_array_index(""), None);
+  }
+}
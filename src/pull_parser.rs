@@ -0,0 +1,430 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use super::DuplicateKeyPolicy;
+use super::ParseOptions;
+use super::common::Range;
+use super::common::Ranged;
+use super::errors::*;
+use super::tokens::Token;
+use crate::parser::JsoncParser;
+
+/// An event produced by the `PullParser`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent<'a> {
+  /// The open brace of an object.
+  StartObject,
+  /// The close brace of an object.
+  EndObject,
+  /// The open bracket of an array.
+  StartArray,
+  /// The close bracket of an array.
+  EndArray,
+  /// An object property name, which is followed by the events for its value.
+  Key(Cow<'a, str>),
+  /// A string, number, boolean, or null value.
+  Value(Token<'a>),
+  /// A line or block comment.
+  Comment(Token<'a>),
+}
+
+/// An event with positional information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonEventAndRange<'a> {
+  pub range: Range,
+  pub event: JsonEvent<'a>,
+}
+
+impl<'a> Ranged for JsonEventAndRange<'a> {
+  fn range(&self) -> Range {
+    self.range
+  }
+}
+
+enum Container<'a> {
+  Object {
+    first: bool,
+    expecting_value: bool,
+    /// Keys seen so far when duplicate keys are an error.
+    keys: Option<HashSet<Cow<'a, str>>>,
+  },
+  Array {
+    first: bool,
+  },
+}
+
+/// Parses JSONC text one event at a time without building a tree,
+/// which is useful for extracting data from large documents.
+///
+/// Comments are surfaced as `JsonEvent::Comment` before the event that
+/// follows them. With the `DuplicateKeyPolicy::FirstWins` and `LastWins`
+/// policies, every duplicate key is still provided as an event.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::JsonEvent;
+/// use jsonc_parser::PullParser;
+/// use jsonc_parser::tokens::Token;
+///
+/// let text = r#"{ "skipped": { "a": [1, 2] }, "name": "value" }"#;
+/// let mut parser = PullParser::new(text, &Default::default());
+/// assert_eq!(parser.next_event().unwrap().unwrap().event, JsonEvent::StartObject);
+/// assert_eq!(parser.next_event().unwrap().unwrap().event, JsonEvent::Key("skipped".into()));
+/// parser.skip_value().unwrap();
+/// assert_eq!(parser.next_event().unwrap().unwrap().event, JsonEvent::Key("name".into()));
+/// assert_eq!(
+///   parser.next_event().unwrap().unwrap().event,
+///   JsonEvent::Value(Token::String("value".into()))
+/// );
+/// assert_eq!(parser.next_event().unwrap().unwrap().event, JsonEvent::EndObject);
+/// assert!(parser.next_event().unwrap().is_none());
+/// ```
+pub struct PullParser<'a> {
+  parser: JsoncParser<'a>,
+  containers: Vec<Container<'a>>,
+  events: VecDeque<JsonEventAndRange<'a>>,
+  has_root_value: bool,
+  is_done: bool,
+}
+
+impl<'a> PullParser<'a> {
+  pub fn new(text: &'a str, options: &ParseOptions) -> Self {
+    let mut parser = JsoncParser::new(text, options);
+    parser.collect_comments();
+    Self {
+      parser,
+      containers: Vec::new(),
+      events: VecDeque::new(),
+      has_root_value: false,
+      is_done: false,
+    }
+  }
+
+  /// Gets the next event or `None` when the end of the text was reached.
+  ///
+  /// Once an error is returned, the parser should no longer be used.
+  pub fn next_event(&mut self) -> Result<Option<JsonEventAndRange<'a>>, ParseError> {
+    self.fill_events()?;
+    Ok(self.events.pop_front())
+  }
+
+  /// Skips the next value along with all its descendants.
+  ///
+  /// After a `JsonEvent::Key`, this skips the property's value. When the next
+  /// event is a key, both the key and its value are skipped. Does nothing when
+  /// the next event ends the current object or array.
+  pub fn skip_value(&mut self) -> Result<(), ParseError> {
+    let mut depth = 0usize;
+    loop {
+      self.fill_events()?;
+      let Some(event) = self.events.front() else {
+        return Ok(());
+      };
+      let is_value_end = match &event.event {
+        JsonEvent::StartObject | JsonEvent::StartArray => {
+          depth += 1;
+          false
+        }
+        JsonEvent::EndObject | JsonEvent::EndArray => {
+          if depth == 0 {
+            return Ok(());
+          }
+          depth -= 1;
+          depth == 0
+        }
+        JsonEvent::Value(_) => depth == 0,
+        JsonEvent::Key(_) | JsonEvent::Comment(_) => false,
+      };
+      self.events.pop_front();
+      if is_value_end {
+        return Ok(());
+      }
+    }
+  }
+
+  /// Parses up to the next event when there are no pending events.
+  fn fill_events(&mut self) -> Result<(), ParseError> {
+    if !self.events.is_empty() || self.is_done {
+      return Ok(());
+    }
+
+    let result = self.scan_event();
+    for comment in self.parser.take_comments() {
+      self.events.push_back(JsonEventAndRange {
+        range: comment.range,
+        event: JsonEvent::Comment(comment.token),
+      });
+    }
+    match result {
+      Ok(Some(event)) => {
+        self.events.push_back(JsonEventAndRange {
+          range: self.current_range(),
+          event,
+        });
+        Ok(())
+      }
+      Ok(None) => {
+        self.is_done = true;
+        Ok(())
+      }
+      Err(err) => {
+        self.is_done = true;
+        self.events.clear();
+        Err(err)
+      }
+    }
+  }
+
+  fn scan_event(&mut self) -> Result<Option<JsonEvent<'a>>, ParseError> {
+    match self.containers.last_mut() {
+      None => self.scan_root_event(),
+      Some(Container::Object {
+        first, expecting_value, ..
+      }) => {
+        if std::mem::replace(expecting_value, false) {
+          self.scan_property_value_event().map(Some)
+        } else {
+          let is_first = std::mem::replace(first, false);
+          self.scan_property_key_event(is_first).map(Some)
+        }
+      }
+      Some(Container::Array { first }) => {
+        let is_first = std::mem::replace(first, false);
+        self.scan_array_element_event(is_first).map(Some)
+      }
+    }
+  }
+
+  fn scan_root_event(&mut self) -> Result<Option<JsonEvent<'a>>, ParseError> {
+    self.parser.scan()?;
+    match self.parser.token() {
+      Some(_) if self.has_root_value => Err(
+        self
+          .parser
+          .scanner
+          .create_error_for_current_token(ParseErrorKind::MultipleRootJsonValues),
+      ),
+      Some(token) => {
+        self.has_root_value = true;
+        self.value_event(token).map(Some)
+      }
+      None => Ok(None),
+    }
+  }
+
+  fn scan_property_key_event(&mut self, is_first: bool) -> Result<JsonEvent<'a>, ParseError> {
+    if !self.parser.scan_object_entry(is_first)? {
+      self.exit_container();
+      return Ok(JsonEvent::EndObject);
+    }
+    let key = self.parser.object_key();
+    if let Some(Container::Object {
+      expecting_value, keys, ..
+    }) = self.containers.last_mut()
+    {
+      *expecting_value = true;
+      if let Some(keys) = keys
+        && !keys.insert(key.clone())
+      {
+        return Err(self.parser.duplicate_key_error(&key));
+      }
+    }
+    Ok(JsonEvent::Key(key))
+  }
+
+  fn scan_property_value_event(&mut self) -> Result<JsonEvent<'a>, ParseError> {
+    self.parser.scan_object_colon()?;
+    self.parser.scan()?;
+    match self.parser.token() {
+      Some(token) => self.value_event(token),
+      None => Err(
+        self
+          .parser
+          .scanner
+          .create_error_for_current_token(ParseErrorKind::ExpectedObjectValue),
+      ),
+    }
+  }
+
+  fn scan_array_element_event(&mut self, is_first: bool) -> Result<JsonEvent<'a>, ParseError> {
+    if is_first {
+      self.parser.scan()?;
+    } else {
+      self.parser.scan_array_comma()?;
+    }
+    match self.parser.token() {
+      Some(Token::CloseBracket) => {
+        self.exit_container();
+        Ok(JsonEvent::EndArray)
+      }
+      Some(token) => self.value_event(token),
+      None => Err(
+        self
+          .parser
+          .scanner
+          .create_error_for_current_token(ParseErrorKind::UnterminatedArray),
+      ),
+    }
+  }
+
+  fn value_event(&mut self, token: Token<'a>) -> Result<JsonEvent<'a>, ParseError> {
+    match token {
+      Token::OpenBrace => {
+        self.parser.enter_container()?;
+        self.containers.push(Container::Object {
+          first: true,
+          expecting_value: false,
+          keys: (self.parser.duplicate_key_policy == DuplicateKeyPolicy::Error).then(HashSet::new),
+        });
+        Ok(JsonEvent::StartObject)
+      }
+      Token::OpenBracket => {
+        self.parser.enter_container()?;
+        self.containers.push(Container::Array { first: true });
+        Ok(JsonEvent::StartArray)
+      }
+      Token::String(_) | Token::Number(_) | Token::Boolean(_) | Token::Null => Ok(JsonEvent::Value(token)),
+      _ => Err(self.parser.unexpected_token_error()),
+    }
+  }
+
+  fn exit_container(&mut self) {
+    self.containers.pop();
+    self.parser.exit_container();
+  }
+
+  fn current_range(&self) -> Range {
+    Range::new(self.parser.scanner.token_start(), self.parser.scanner.token_end())
+  }
+}
+
+impl<'a> Iterator for PullParser<'a> {
+  type Item = Result<JsonEventAndRange<'a>, ParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.next_event().transpose()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn collect(text: &str, options: &ParseOptions) -> Vec<String> {
+    let mut results = Vec::new();
+    for result in PullParser::new(text, options) {
+      match result {
+        Ok(item) => results.push(format!("{}..{} {:?}", item.range.start, item.range.end, item.event)),
+        Err(err) => results.push(err.to_string()),
+      }
+    }
+    results
+  }
+
+  #[test]
+  fn it_should_provide_events() {
+    let text = r#"// header
+{
+  "a": [1, true, null, /* inner */ {}],
+  b: "text", // trailing
+}"#;
+    assert_eq!(
+      collect(text, &Default::default()),
+      vec![
+        "0..9 Comment(CommentLine(\" header\"))",
+        "10..11 StartObject",
+        "14..17 Key(\"a\")",
+        "19..20 StartArray",
+        "20..21 Value(Number(\"1\"))",
+        "23..27 Value(Boolean(true))",
+        "29..33 Value(Null)",
+        "35..46 Comment(CommentBlock(\" inner \"))",
+        "47..48 StartObject",
+        "48..49 EndObject",
+        "49..50 EndArray",
+        "54..55 Key(\"b\")",
+        "57..63 Value(String(\"text\"))",
+        "65..76 Comment(CommentLine(\" trailing\"))",
+        "77..78 EndObject",
+      ]
+    );
+    assert_eq!(collect("", &Default::default()), Vec::<String>::new());
+    assert_eq!(collect("5", &Default::default()), vec!["0..1 Value(Number(\"5\"))"]);
+  }
+
+  fn next_event<'a>(parser: &mut PullParser<'a>) -> Option<JsonEvent<'a>> {
+    parser.next_event().unwrap().map(|item| item.event)
+  }
+
+  #[test]
+  fn it_should_skip_values() {
+    let text = r#"{ "a": { "b": [1, { "c": 2 }] }, /* c */ "d": [3, [4], 5], "e": 6 }"#;
+    let mut parser = PullParser::new(text, &Default::default());
+    assert_eq!(next_event(&mut parser), Some(JsonEvent::StartObject));
+    assert_eq!(next_event(&mut parser), Some(JsonEvent::Key("a".into())));
+    parser.skip_value().unwrap();
+    // skips the comment along with the key and its value
+    parser.skip_value().unwrap();
+    assert_eq!(next_event(&mut parser), Some(JsonEvent::Key("e".into())));
+    assert_eq!(next_event(&mut parser), Some(JsonEvent::Value(Token::Number("6"))));
+    // does nothing at the end of the object
+    parser.skip_value().unwrap();
+    assert_eq!(next_event(&mut parser), Some(JsonEvent::EndObject));
+    parser.skip_value().unwrap();
+    assert_eq!(next_event(&mut parser), None);
+
+    let mut parser = PullParser::new("[[1, 2], 3]", &Default::default());
+    assert_eq!(next_event(&mut parser), Some(JsonEvent::StartArray));
+    parser.skip_value().unwrap();
+    let item = parser.next_event().unwrap().unwrap();
+    assert_eq!(item.event, JsonEvent::Value(Token::Number("3")));
+    assert_eq!(item.range, Range::new(9, 10));
+  }
+
+  #[test]
+  fn it_should_error() {
+    assert_eq!(
+      collect("[1, }", &Default::default()),
+      vec![
+        "0..1 StartArray",
+        "1..2 Value(Number(\"1\"))",
+        "Unexpected close brace on line 1 column 5",
+      ]
+    );
+    assert_eq!(
+      collect("{ \"a\": 1 } 2", &Default::default()),
+      vec![
+        "0..1 StartObject",
+        "2..5 Key(\"a\")",
+        "7..8 Value(Number(\"1\"))",
+        "9..10 EndObject",
+        "Text cannot contain more than one JSON value on line 1 column 12",
+      ]
+    );
+    assert_eq!(
+      collect("[1,]", &ParseOptions::strict_json()),
+      vec![
+        "0..1 StartArray",
+        "1..2 Value(Number(\"1\"))",
+        "Trailing commas are not allowed on line 1 column 3",
+      ]
+    );
+    let options = ParseOptions {
+      duplicate_key_policy: DuplicateKeyPolicy::Error,
+      ..Default::default()
+    };
+    assert_eq!(
+      collect("{ \"a\": 1, \"a\": 2 }", &options),
+      vec![
+        "0..1 StartObject",
+        "2..5 Key(\"a\")",
+        "7..8 Value(Number(\"1\"))",
+        "Duplicate object key 'a' on line 1 column 11",
+      ]
+    );
+  }
+}
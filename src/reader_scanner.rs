@@ -0,0 +1,533 @@
+use std::borrow::Cow;
+use std::io::ErrorKind;
+use std::io::Read;
+
+use super::common::Range;
+use super::errors::*;
+#[cfg(feature = "serde")]
+use super::parser::TokenSource;
+use super::scanner::Scanner;
+use super::scanner::ScannerOptions;
+use super::scanner::is_whitespace;
+use super::tokens::Token;
+
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Converts text from a reader into a stream of tokens.
+///
+/// Unlike `Scanner`, this doesn't require the full text to be in memory. It
+/// only buffers the text of the current token along with a small chunk read
+/// ahead of it.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::ReaderScanner;
+/// use jsonc_parser::tokens::Token;
+///
+/// let mut scanner = ReaderScanner::new(r#"{ "a": 1 } // end"#.as_bytes(), &Default::default());
+/// let mut tokens = Vec::new();
+/// while let Some(token) = scanner.scan().unwrap() {
+///   tokens.push(token.as_str().to_string());
+/// }
+/// assert_eq!(tokens, ["{", "a", ":", "1", "}", " end"]);
+/// ```
+pub struct ReaderScanner<R: Read> {
+  reader: R,
+  /// Text read from the reader that hasn't been discarded.
+  text: String,
+  /// Bytes at the end of what was read that don't yet form a complete character.
+  incomplete_bytes: Vec<u8>,
+  is_eof: bool,
+  /// Byte position of the start of `text` in the reader.
+  text_offset: usize,
+  /// Index in `text` of the start of the current token.
+  token_start: usize,
+  /// Index in `text` of the end of the current token.
+  token_end: usize,
+  /// 0-indexed line and column of the start of the current token.
+  line_and_column_index: (usize, usize),
+  current_token: Option<CurrentToken>,
+  options: ScannerOptions,
+}
+
+/// The current token, which gets its text from the token's range in the buffer.
+enum CurrentToken {
+  OpenBrace,
+  CloseBrace,
+  OpenBracket,
+  CloseBracket,
+  Comma,
+  Colon,
+  BorrowedString,
+  OwnedString(String),
+  Word,
+  Boolean(bool),
+  Number,
+  Null,
+  CommentLine,
+  CommentBlock,
+}
+
+impl<R: Read> ReaderScanner<R> {
+  /// Creates a new scanner for the reader with specific options.
+  ///
+  /// The reader is read in chunks, so it doesn't need to be buffered.
+  pub fn new(reader: R, options: &ScannerOptions) -> Self {
+    ReaderScanner {
+      reader,
+      text: String::new(),
+      incomplete_bytes: Vec::new(),
+      is_eof: false,
+      text_offset: 0,
+      token_start: 0,
+      token_end: 0,
+      line_and_column_index: (0, 0),
+      current_token: None,
+      options: options.clone(),
+    }
+  }
+
+  /// Moves to and returns the next token.
+  pub fn scan(&mut self) -> Result<Option<Token<'_>>, ParseError> {
+    self.move_next_token()?;
+    Ok(self.token())
+  }
+
+  /// Gets the start position of the token.
+  pub fn token_start(&self) -> usize {
+    self.text_offset + self.token_start
+  }
+
+  /// Gets the end position of the token.
+  pub fn token_end(&self) -> usize {
+    self.text_offset + self.token_end
+  }
+
+  /// Gets the current token.
+  pub fn token(&self) -> Option<Token<'_>> {
+    let text = &self.text[self.token_start..self.token_end];
+    let token = match self.current_token.as_ref()? {
+      CurrentToken::OpenBrace => Token::OpenBrace,
+      CurrentToken::CloseBrace => Token::CloseBrace,
+      CurrentToken::OpenBracket => Token::OpenBracket,
+      CurrentToken::CloseBracket => Token::CloseBracket,
+      CurrentToken::Comma => Token::Comma,
+      CurrentToken::Colon => Token::Colon,
+      CurrentToken::BorrowedString => Token::String(Cow::Borrowed(&text[1..text.len() - 1])),
+      CurrentToken::OwnedString(value) => Token::String(Cow::Borrowed(value)),
+      CurrentToken::Word => Token::Word(text),
+      CurrentToken::Boolean(value) => Token::Boolean(*value),
+      CurrentToken::Number => Token::Number(text),
+      CurrentToken::Null => Token::Null,
+      CurrentToken::CommentLine => Token::CommentLine(&text[2..]),
+      CurrentToken::CommentBlock => Token::CommentBlock(&text[2..text.len() - 2]),
+    };
+    Some(token)
+  }
+
+  /// Moves to the next token, which is then available via `token()`.
+  pub(crate) fn move_next_token(&mut self) -> Result<(), ParseError> {
+    self.current_token = None;
+    self.consume_to(self.token_end);
+    self.skip_whitespace()?;
+    if self.byte_at(0)?.is_none() {
+      return Ok(());
+    }
+
+    // include the character after the token so the scanner handles what
+    // follows it the same as when scanning the full text
+    let mut text_len = self.token_len()?;
+    if self.byte_at(text_len)?.is_some() {
+      text_len += self.text[self.token_start + text_len..]
+        .chars()
+        .next()
+        .unwrap()
+        .len_utf8();
+    }
+    let text = &self.text[self.token_start..self.token_start + text_len];
+    let (line_index, column_index) = self.line_and_column_index;
+    let mut scanner = Scanner::new(text, &self.options);
+    let token = scanner
+      .scan()
+      .map_err(|err| err.offset_by(self.text_offset + self.token_start, line_index, column_index))?;
+    let current_token = match token {
+      Some(Token::OpenBrace) => CurrentToken::OpenBrace,
+      Some(Token::CloseBrace) => CurrentToken::CloseBrace,
+      Some(Token::OpenBracket) => CurrentToken::OpenBracket,
+      Some(Token::CloseBracket) => CurrentToken::CloseBracket,
+      Some(Token::Comma) => CurrentToken::Comma,
+      Some(Token::Colon) => CurrentToken::Colon,
+      Some(Token::String(Cow::Borrowed(_))) => CurrentToken::BorrowedString,
+      Some(Token::String(Cow::Owned(value))) => CurrentToken::OwnedString(value),
+      Some(Token::Word(_)) => CurrentToken::Word,
+      Some(Token::Boolean(value)) => CurrentToken::Boolean(value),
+      Some(Token::Number(_)) => CurrentToken::Number,
+      Some(Token::Null) => CurrentToken::Null,
+      Some(Token::CommentLine(_)) => CurrentToken::CommentLine,
+      Some(Token::CommentBlock(_)) => CurrentToken::CommentBlock,
+      None => unreachable!("the token text is not whitespace"),
+    };
+    // the scanner may end the token before the end of the text, such as
+    // for `123abc`, in which case the rest is scanned as the next token
+    self.token_end = self.token_start + scanner.token_end();
+    self.current_token = Some(current_token);
+    Ok(())
+  }
+
+  /// Gets the length of the text that contains the token starting at
+  /// the current position, reading until the token is fully buffered.
+  fn token_len(&mut self) -> Result<usize, ParseError> {
+    let first = self.byte_at(0)?.unwrap();
+    match first {
+      b'{' | b'}' | b'[' | b']' | b',' | b':' => Ok(1),
+      b'"' | b'\'' => {
+        let mut index = 1;
+        while let Some(b) = self.byte_at(index)? {
+          index += 1;
+          match b {
+            b'\\' if self.byte_at(index)?.is_some() => index += 1,
+            b if b == first => break,
+            _ => {}
+          }
+        }
+        Ok(index)
+      }
+      b'/' => match self.byte_at(1)? {
+        Some(b'/') => {
+          let mut index = 2;
+          // include the newline so the scanner can exclude a preceding \r
+          while let Some(b) = self.byte_at(index)? {
+            index += 1;
+            if b == b'\n' {
+              break;
+            }
+          }
+          Ok(index)
+        }
+        Some(b'*') => {
+          let mut index = 2;
+          while let Some(b) = self.byte_at(index)? {
+            index += 1;
+            if b == b'*' && self.byte_at(index)? == Some(b'/') {
+              index += 1;
+              break;
+            }
+          }
+          Ok(index)
+        }
+        _ => Ok(1),
+      },
+      _ => {
+        // numbers, keywords, and words end at whitespace or punctuation
+        let mut index = 0;
+        while let Some(b) = self.byte_at(index)? {
+          if b < 0x80 {
+            if b.is_ascii_whitespace() || matches!(b, b'{' | b'}' | b'[' | b']' | b',' | b':' | b'"' | b'\'' | b'/') {
+              break;
+            }
+            index += 1;
+          } else {
+            let c = self.text[self.token_start + index..].chars().next().unwrap();
+            if is_whitespace(c) {
+              break;
+            }
+            index += c.len_utf8();
+          }
+        }
+        Ok(index)
+      }
+    }
+  }
+
+  fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+    while let Some(b) = self.byte_at(0)? {
+      let len = if b < 0x80 {
+        match b {
+          b' ' | b'\t' | b'\n' | b'\r' | 0x0B | 0x0C => 1,
+          _ => break,
+        }
+      } else {
+        let c = self.text[self.token_start..].chars().next().unwrap();
+        if is_whitespace(c) {
+          c.len_utf8()
+        } else {
+          break;
+        }
+      };
+      self.consume_to(self.token_start + len);
+    }
+    Ok(())
+  }
+
+  /// Moves the start of the next token to the provided index in the text.
+  fn consume_to(&mut self, index: usize) {
+    self.line_and_column_index =
+      advance_line_and_column_index(self.line_and_column_index, &self.text[self.token_start..index]);
+    self.token_start = index;
+    self.token_end = index;
+  }
+
+  /// Gets the byte at the provided index relative to the start of the
+  /// current token, reading more text when necessary.
+  fn byte_at(&mut self, index: usize) -> Result<Option<u8>, ParseError> {
+    loop {
+      if let Some(&b) = self.text.as_bytes().get(self.token_start + index) {
+        return Ok(Some(b));
+      }
+      if self.is_eof {
+        return Ok(None);
+      }
+      self.read_chunk()?;
+    }
+  }
+
+  fn read_chunk(&mut self) -> Result<(), ParseError> {
+    // discard the text before the current token
+    if self.token_start > 0 {
+      self.text.drain(..self.token_start);
+      self.text_offset += self.token_start;
+      self.token_end -= self.token_start;
+      self.token_start = 0;
+    }
+
+    let mut bytes = std::mem::take(&mut self.incomplete_bytes);
+    let previous_len = bytes.len();
+    bytes.resize(previous_len + READ_CHUNK_SIZE, 0);
+    let read_len = loop {
+      match self.reader.read(&mut bytes[previous_len..]) {
+        Ok(len) => break len,
+        Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+        Err(err) => return Err(self.create_error_at_end(ParseErrorKind::Io(err))),
+      }
+    };
+    bytes.truncate(previous_len + read_len);
+    if read_len == 0 {
+      self.is_eof = true;
+      if !bytes.is_empty() {
+        self.incomplete_bytes = bytes;
+        return Err(self.create_invalid_utf8_error());
+      }
+      return Ok(());
+    }
+
+    match std::str::from_utf8(&bytes) {
+      Ok(text) => self.text.push_str(text),
+      Err(err) => {
+        let valid_len = err.valid_up_to();
+        self.text.push_str(std::str::from_utf8(&bytes[..valid_len]).unwrap());
+        if err.error_len().is_some() {
+          return Err(self.create_invalid_utf8_error());
+        }
+        // the last character was cut off, so keep its bytes for the next read
+        self.incomplete_bytes = bytes[valid_len..].to_vec();
+      }
+    }
+    Ok(())
+  }
+
+  fn create_invalid_utf8_error(&self) -> ParseError {
+    self.create_error_at_end(ParseErrorKind::Io(std::io::Error::new(
+      ErrorKind::InvalidData,
+      "stream did not contain valid UTF-8",
+    )))
+  }
+
+  /// Creates an error at the end of the text read so far.
+  fn create_error_at_end(&self, kind: ParseErrorKind) -> ParseError {
+    let (line_index, column_index) =
+      advance_line_and_column_index(self.line_and_column_index, &self.text[self.token_start..]);
+    let position = self.text_offset + self.text.len();
+    ParseError::new_with_line_and_column(Range::new(position, position), line_index + 1, column_index + 1, kind)
+  }
+}
+
+/// Position of a token read from a `ReaderScanner`, which includes the
+/// line and column since the text before the token isn't kept.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy)]
+pub(crate) struct ReaderPosition {
+  range: Range,
+  line_index: usize,
+  column_index: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<'a, R: Read> TokenSource<'a> for ReaderScanner<R> {
+  type Position = ReaderPosition;
+
+  fn move_next_token(&mut self) -> Result<(), ParseError> {
+    ReaderScanner::move_next_token(self)
+  }
+
+  fn current_token(&self) -> Option<Token<'_>> {
+    self.token()
+  }
+
+  fn borrowed_token(&self) -> Option<Token<'a>> {
+    None
+  }
+
+  fn token_start(&self) -> usize {
+    ReaderScanner::token_start(self)
+  }
+
+  fn token_end(&self) -> usize {
+    ReaderScanner::token_end(self)
+  }
+
+  fn token_position(&self) -> ReaderPosition {
+    let (line_index, column_index) = self.line_and_column_index;
+    ReaderPosition {
+      range: Range::new(self.token_start(), self.token_end()),
+      line_index,
+      column_index,
+    }
+  }
+
+  fn token_end_position(&self) -> ReaderPosition {
+    let (line_index, column_index) =
+      advance_line_and_column_index(self.line_and_column_index, &self.text[self.token_start..self.token_end]);
+    let end = self.token_end();
+    ReaderPosition {
+      range: Range::new(end, end),
+      line_index,
+      column_index,
+    }
+  }
+
+  fn line_and_column_display(&self, position: ReaderPosition) -> (usize, usize) {
+    (position.line_index + 1, position.column_index + 1)
+  }
+
+  fn create_error_at(&self, position: ReaderPosition, kind: ParseErrorKind) -> ParseError {
+    ParseError::new_with_line_and_column(position.range, position.line_index + 1, position.column_index + 1, kind)
+  }
+
+  fn create_error_for_current_token(&self, kind: ParseErrorKind) -> ParseError {
+    self.create_error_at(self.token_position(), kind)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  /// Reader that returns a few bytes at a time to exercise the buffering.
+  struct ChunkedReader<'a> {
+    bytes: &'a [u8],
+    chunk_size: usize,
+  }
+
+  impl Read for ChunkedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+      let len = self.chunk_size.min(buf.len()).min(self.bytes.len());
+      buf[..len].copy_from_slice(&self.bytes[..len]);
+      self.bytes = &self.bytes[len..];
+      Ok(len)
+    }
+  }
+
+  fn reader_scanner<'a>(
+    bytes: &'a [u8],
+    chunk_size: usize,
+    options: &ScannerOptions,
+  ) -> ReaderScanner<ChunkedReader<'a>> {
+    ReaderScanner::new(ChunkedReader { bytes, chunk_size }, options)
+  }
+
+  /// Formats the tokens and their ranges or the error for comparing.
+  fn scan_all(scan: &mut dyn FnMut(&mut Vec<String>) -> Result<bool, ParseError>) -> Vec<String> {
+    let mut results = Vec::new();
+    loop {
+      match scan(&mut results) {
+        Ok(true) => {}
+        Ok(false) => break,
+        Err(err) => {
+          results.push(format!("{:?} {}", err.range(), err));
+          break;
+        }
+      }
+    }
+    results
+  }
+
+  fn assert_same_as_scanner(text: &str, options: &ScannerOptions) {
+    let mut scanner = Scanner::new(text, options);
+    let expected = scan_all(&mut |results| {
+      let token = scanner.scan()?;
+      results.push(format!(
+        "{:?} {}..{}",
+        token,
+        scanner.token_start(),
+        scanner.token_end()
+      ));
+      Ok(token.is_some())
+    });
+    for chunk_size in [1, 2, 3, 7, READ_CHUNK_SIZE] {
+      let mut scanner = reader_scanner(text.as_bytes(), chunk_size, options);
+      let actual = scan_all(&mut |results| {
+        let token = scanner.scan()?;
+        let is_some = token.is_some();
+        let token = format!("{:?}", token);
+        results.push(format!("{} {}..{}", token, scanner.token_start(), scanner.token_end()));
+        Ok(is_some)
+      });
+      assert_eq!(actual, expected, "chunk size {}", chunk_size);
+    }
+  }
+
+  #[test]
+  fn it_should_scan_same_as_scanner() {
+    let options = ScannerOptions::default();
+    assert_same_as_scanner("", &options);
+    assert_same_as_scanner(
+      "\u{FEFF}{\n  // comment\r\n  \"a\": [1, -2.5e10, 0x1F, +3, true, false, null],\n  /* block\n */ 'b': \"\\u0041\\n\\\"\",\n  word: \"héllo wörld\"\n}",
+      &options,
+    );
+    assert_same_as_scanner("[123abc, \u{3000}1\u{A0}]", &options);
+    assert_same_as_scanner("{\u{FEFF}a\\u0062\u{200C}:1, null\u{FEFF}: true}", &options);
+    let json5_options = ScannerOptions {
+      allow_infinity_and_nan: true,
+      allow_leading_decimal_point: true,
+      allow_trailing_decimal_point: true,
+      allow_line_continuations: true,
+      allow_extended_escapes: true,
+      ..Default::default()
+    };
+    assert_same_as_scanner("[Infinity, -NaN, .5, 5., 'a\\\nb', '\\x41\\é']", &json5_options);
+  }
+
+  #[test]
+  fn it_should_error_same_as_scanner() {
+    let options = ScannerOptions::default();
+    assert_same_as_scanner("[1,\n  \"test", &options);
+    assert_same_as_scanner("{\n  /* test", &options);
+    assert_same_as_scanner("[\n  1, 2e]", &options);
+    assert_same_as_scanner("[\n  a#b]", &options);
+    assert_same_as_scanner("[\n  \"\\q\"]", &options);
+    assert_same_as_scanner("[1, \u{FEFF}2]", &options);
+    assert_same_as_scanner(
+      "'test'",
+      &ScannerOptions {
+        allow_single_quoted_strings: false,
+        ..Default::default()
+      },
+    );
+  }
+
+  #[test]
+  fn it_should_error_for_invalid_utf8() {
+    let mut scanner = reader_scanner(b"[\n  \"a\xFFb\"]", 1, &Default::default());
+    assert_eq!(scanner.scan().unwrap(), Some(Token::OpenBracket));
+    let err = scanner.scan().err().unwrap();
+    assert_eq!(err.to_string(), "stream did not contain valid UTF-8 on line 2 column 5");
+
+    // cut off at the end of the stream
+    let mut scanner = reader_scanner(b"\"\xC3", 1, &Default::default());
+    let err = scanner.scan().err().unwrap();
+    assert_eq!(err.to_string(), "stream did not contain valid UTF-8 on line 1 column 2");
+  }
+}
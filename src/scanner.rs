@@ -1,9 +1,13 @@
 use std::borrow::Cow;
 
 use crate::string::CharProvider;
+use crate::string::ParseStringOptions;
+use crate::string::is_word_char;
+use crate::string::parse_word_escape;
 
 use super::common::Range;
 use super::errors::*;
+use super::parser::TokenSource;
 use super::tokens::Token;
 
 /// Converts text into a stream of tokens.
@@ -16,10 +20,19 @@ pub struct Scanner<'a> {
   allow_single_quoted_strings: bool,
   allow_hexadecimal_numbers: bool,
   allow_unary_plus_numbers: bool,
+  allow_infinity_and_nan: bool,
+  allow_leading_decimal_point: bool,
+  allow_trailing_decimal_point: bool,
+  allow_line_continuations: bool,
+  allow_extended_escapes: bool,
+  allow_control_characters_in_strings: bool,
+  allow_leading_zeros: bool,
+  split_leading_zeros: bool,
+  allow_dollar_sign_in_words: bool,
 }
 
 /// Options for the scanner.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ScannerOptions {
   /// Allow single-quoted strings (defaults to `true`).
   pub allow_single_quoted_strings: bool,
@@ -27,6 +40,26 @@ pub struct ScannerOptions {
   pub allow_hexadecimal_numbers: bool,
   /// Allow unary plus sign on numbers like +42 (defaults to `true`).
   pub allow_unary_plus_numbers: bool,
+  /// Allow `Infinity` and `NaN` as numbers, optionally signed (defaults to `false`).
+  pub allow_infinity_and_nan: bool,
+  /// Allow numbers with a leading decimal point like .5 (defaults to `false`).
+  pub allow_leading_decimal_point: bool,
+  /// Allow numbers with a trailing decimal point like 5. (defaults to `false`).
+  pub allow_trailing_decimal_point: bool,
+  /// Allow escaping a line terminator in a string to continue it on the next line (defaults to `false`).
+  pub allow_line_continuations: bool,
+  /// Allow the JSON5 string escapes `\xHH`, `\v`, `\0`, and escaping any other character (defaults to `false`).
+  pub allow_extended_escapes: bool,
+  /// Allow unescaped control characters (U+0000 through U+001F) in strings (defaults to `true`).
+  pub allow_control_characters_in_strings: bool,
+  /// Allow leading zeros on numbers like 007 (defaults to `false`).
+  pub allow_leading_zeros: bool,
+  /// When leading zeros are not allowed, end the number at a leading `0` instead
+  /// of erroring, so `007` is scanned as the separate numbers `0`, `0`, and `7`
+  /// (defaults to `true`).
+  pub split_leading_zeros: bool,
+  /// Allow `$` in words like the unquoted property name `$schema` (defaults to `true`).
+  pub allow_dollar_sign_in_words: bool,
 }
 
 impl Default for ScannerOptions {
@@ -35,6 +68,15 @@ impl Default for ScannerOptions {
       allow_single_quoted_strings: true,
       allow_hexadecimal_numbers: true,
       allow_unary_plus_numbers: true,
+      allow_infinity_and_nan: false,
+      allow_leading_decimal_point: false,
+      allow_trailing_decimal_point: false,
+      allow_line_continuations: false,
+      allow_extended_escapes: false,
+      allow_control_characters_in_strings: true,
+      allow_leading_zeros: false,
+      split_leading_zeros: true,
+      allow_dollar_sign_in_words: true,
     }
   }
 }
@@ -51,6 +93,15 @@ impl<'a> Scanner<'a> {
       allow_single_quoted_strings: options.allow_single_quoted_strings,
       allow_hexadecimal_numbers: options.allow_hexadecimal_numbers,
       allow_unary_plus_numbers: options.allow_unary_plus_numbers,
+      allow_infinity_and_nan: options.allow_infinity_and_nan,
+      allow_leading_decimal_point: options.allow_leading_decimal_point,
+      allow_trailing_decimal_point: options.allow_trailing_decimal_point,
+      allow_line_continuations: options.allow_line_continuations,
+      allow_extended_escapes: options.allow_extended_escapes,
+      allow_control_characters_in_strings: options.allow_control_characters_in_strings,
+      allow_leading_zeros: options.allow_leading_zeros,
+      split_leading_zeros: options.split_leading_zeros,
+      allow_dollar_sign_in_words: options.allow_dollar_sign_in_words,
     }
   }
 
@@ -60,6 +111,12 @@ impl<'a> Scanner<'a> {
 
   /// Moves to and returns the next token.
   pub fn scan(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+    self.move_next_token()?;
+    Ok(self.token())
+  }
+
+  /// Moves to the next token, which is then available via `token()`.
+  pub(crate) fn move_next_token(&mut self) -> Result<(), ParseError> {
     self.skip_whitespace();
     self.token_start = self.byte_index;
     if let Some(&b) = self.bytes.get(self.byte_index) {
@@ -102,22 +159,23 @@ impl<'a> Scanner<'a> {
           _ => Err(self.create_error_for_current_token(ParseErrorKind::UnexpectedToken)),
         },
         b'-' | b'+' | b'0'..=b'9' => self.parse_number(),
+        b'.' if self.allow_leading_decimal_point => self.parse_number(),
+        b'I' if self.allow_infinity_and_nan && self.try_move_word("Infinity") => {
+          Ok(Token::Number(&self.file_text[self.token_start..self.byte_index]))
+        }
+        b'N' if self.allow_infinity_and_nan && self.try_move_word("NaN") => {
+          Ok(Token::Number(&self.file_text[self.token_start..self.byte_index]))
+        }
         b't' if self.try_move_word("true") => Ok(Token::Boolean(true)),
         b'f' if self.try_move_word("false") => Ok(Token::Boolean(false)),
         b'n' if self.try_move_word("null") => Ok(Token::Null),
         _ => self.parse_word(),
       };
-      match token_result {
-        Ok(token) => {
-          self.current_token = Some(token.clone());
-          Ok(Some(token))
-        }
-        Err(err) => Err(err),
-      }
+      self.current_token = Some(token_result?);
     } else {
       self.current_token = None;
-      Ok(None)
     }
+    Ok(())
   }
 
   /// Gets the start position of the token.
@@ -135,6 +193,43 @@ impl<'a> Scanner<'a> {
     self.current_token.as_ref().map(|x| x.to_owned())
   }
 
+  /// Moves the scanner to the provided byte index so the next token
+  /// is scanned from there.
+  pub(crate) fn reset_to(&mut self, byte_index: usize) {
+    self.byte_index = byte_index;
+    self.token_start = byte_index;
+    self.current_token = None;
+  }
+
+  /// Moves past the text that caused an error when scanning the current
+  /// token so that scanning can continue after it.
+  pub(crate) fn skip_invalid_token(&mut self) {
+    let start = self.token_start;
+    match self.bytes.get(start) {
+      Some(&quote @ (b'"' | b'\'')) => {
+        // move to after the closing quote, stopping at the end of the line
+        let mut index = start + 1;
+        while let Some(&b) = self.bytes.get(index) {
+          match b {
+            b'\\' => index += 2,
+            b'\n' | b'\r' => break,
+            b if b == quote => {
+              index += 1;
+              break;
+            }
+            _ => index += 1,
+          }
+        }
+        self.byte_index = index.min(self.bytes.len());
+      }
+      Some(_) if self.byte_index <= start => {
+        let c = self.file_text[start..].chars().next().unwrap();
+        self.byte_index = start + c.len_utf8();
+      }
+      _ => {}
+    }
+  }
+
   pub(super) fn create_error_for_current_token(&self, kind: ParseErrorKind) -> ParseError {
     let end = if self.byte_index > self.token_start {
       // token was fully scanned — use the exact token end
@@ -176,7 +271,7 @@ impl<'a> Scanner<'a> {
     let quote = self.bytes[self.byte_index];
     let start = self.byte_index + 1;
 
-    // fast path: scan for closing quote or backslash byte-by-byte.
+    // fast path: scan for closing quote or backslash (or a disallowed control character) byte-by-byte.
     // this is safe because quote (0x22/0x27) and backslash (0x5C) are ASCII
     // and can never appear as continuation bytes in multi-byte UTF-8 sequences.
     let mut i = start;
@@ -188,14 +283,19 @@ impl<'a> Scanner<'a> {
         self.byte_index = i + 1;
         return Ok(Token::String(Cow::Borrowed(s)));
       }
-      if b == b'\\' {
+      if b == b'\\' || b < 0x20 && !self.allow_control_characters_in_strings {
         break;
       }
       i += 1;
     }
 
     // slow path: handle escape sequences via CharProvider
-    crate::string::parse_string_with_char_provider(self)
+    let options = ParseStringOptions {
+      allow_line_continuations: self.allow_line_continuations,
+      allow_extended_escapes: self.allow_extended_escapes,
+      allow_control_characters: self.allow_control_characters_in_strings,
+    };
+    crate::string::parse_string_with_char_provider(self, options)
       .map(Token::String)
       // todo(dsherret): don't convert the error kind to a string here
       .map_err(|err| self.create_error_for_start(err.byte_index, ParseErrorKind::String(err.kind)))
@@ -218,6 +318,11 @@ impl<'a> Scanner<'a> {
       _ => {}
     }
 
+    if self.allow_infinity_and_nan && (self.try_move_word("Infinity") || self.try_move_word("NaN")) {
+      return Ok(Token::Number(&self.file_text[start_byte_index..self.byte_index]));
+    }
+
+    let has_integer_digits = !matches!(self.bytes.get(self.byte_index), Some(b'.'));
     match self.bytes.get(self.byte_index) {
       Some(b'0') => {
         self.byte_index += 1;
@@ -241,6 +346,19 @@ impl<'a> Scanner<'a> {
 
           return Ok(Token::Number(&self.file_text[start_byte_index..self.byte_index]));
         }
+
+        if matches!(self.bytes.get(self.byte_index), Some(b'0'..=b'9')) {
+          if self.allow_leading_zeros {
+            while matches!(self.bytes.get(self.byte_index), Some(b'0'..=b'9')) {
+              self.byte_index += 1;
+            }
+          } else if !self.split_leading_zeros {
+            while matches!(self.bytes.get(self.byte_index), Some(b'0'..=b'9')) {
+              self.byte_index += 1;
+            }
+            return Err(self.create_error_for_current_token(ParseErrorKind::LeadingZerosNotAllowed));
+          }
+        }
       }
       Some(b'1'..=b'9') => {
         self.byte_index += 1;
@@ -248,6 +366,7 @@ impl<'a> Scanner<'a> {
           self.byte_index += 1;
         }
       }
+      Some(b'.') if self.allow_leading_decimal_point => {}
       _ => {
         return Err(self.create_error_for_current_char(ParseErrorKind::ExpectedDigitFollowingNegativeSign));
       }
@@ -256,7 +375,8 @@ impl<'a> Scanner<'a> {
     if self.bytes.get(self.byte_index) == Some(&b'.') {
       self.byte_index += 1;
 
-      if !matches!(self.bytes.get(self.byte_index), Some(b'0'..=b'9')) {
+      let has_fraction_digits = matches!(self.bytes.get(self.byte_index), Some(b'0'..=b'9'));
+      if !has_fraction_digits && (!has_integer_digits || !self.allow_trailing_decimal_point) {
         return Err(self.create_error_for_current_char(ParseErrorKind::ExpectedDigit));
       }
 
@@ -345,7 +465,7 @@ impl<'a> Scanner<'a> {
       } else if b >= 0x80 {
         // handle non-ASCII unicode whitespace
         let c = self.file_text[self.byte_index..].chars().next().unwrap();
-        if c.is_whitespace() {
+        if is_whitespace(c) {
           self.byte_index += c.len_utf8();
           continue;
         }
@@ -365,18 +485,11 @@ impl<'a> Scanner<'a> {
     if &self.bytes[self.byte_index..end] != text_bytes {
       return false;
     }
-    // ensure the word is not followed by an alphanumeric character
-    if let Some(&next_byte) = self.bytes.get(end) {
-      if next_byte.is_ascii_alphanumeric() {
-        return false;
-      }
-      // check non-ASCII alphanumeric
-      if next_byte >= 0x80
-        && let Some(c) = self.file_text[end..].chars().next()
-        && c.is_alphanumeric()
-      {
-        return false;
-      }
+    // ensure the word is not followed by a character that continues it
+    if let Some(c) = self.file_text[end..].chars().next()
+      && (c == '\\' || is_word_char(c, self.allow_dollar_sign_in_words))
+    {
+      return false;
     }
     self.byte_index = end;
     true
@@ -392,18 +505,30 @@ impl<'a> Scanner<'a> {
         if b.is_ascii_whitespace() || b == b':' {
           break;
         }
-        if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+        if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'$' && self.allow_dollar_sign_in_words {
           self.byte_index += 1;
+        } else if b == b'\\' {
+          // unicode escape sequence, which must be for a word character
+          let escape_start = self.byte_index;
+          match parse_word_escape(self) {
+            Some(c) if is_word_char(c, self.allow_dollar_sign_in_words) => {
+              self.move_next_char();
+            }
+            _ => {
+              self.byte_index = escape_start;
+              return Err(self.create_error_for_current_token(ParseErrorKind::UnexpectedToken));
+            }
+          }
         } else {
           return Err(self.create_error_for_current_token(ParseErrorKind::UnexpectedToken));
         }
       } else {
         // non-ASCII: decode char
         let c = self.file_text[self.byte_index..].chars().next().unwrap();
-        if c.is_whitespace() {
+        if is_whitespace(c) {
           break;
         }
-        if c.is_alphanumeric() {
+        if is_word_char(c, false) {
           self.byte_index += c.len_utf8();
         } else {
           return Err(self.create_error_for_current_token(ParseErrorKind::UnexpectedToken));
@@ -442,6 +567,12 @@ impl<'a> Scanner<'a> {
   }
 }
 
+/// Gets if the character is whitespace between tokens, which includes a
+/// byte order mark anywhere in the text.
+pub(crate) fn is_whitespace(c: char) -> bool {
+  c.is_whitespace() || c == '\u{FEFF}'
+}
+
 impl<'a> CharProvider<'a> for Scanner<'a> {
   fn current_char(&mut self) -> Option<char> {
     Scanner::current_char(self)
@@ -460,6 +591,54 @@ impl<'a> CharProvider<'a> for Scanner<'a> {
   }
 }
 
+impl<'a> TokenSource<'a> for Scanner<'a> {
+  type Position = Range;
+
+  fn move_next_token(&mut self) -> Result<(), ParseError> {
+    Scanner::move_next_token(self)
+  }
+
+  fn current_token(&self) -> Option<Token<'_>> {
+    self.current_token.as_ref().map(|token| match token {
+      Token::String(value) => Token::String(Cow::Borrowed(value)),
+      token => token.clone(),
+    })
+  }
+
+  fn borrowed_token(&self) -> Option<Token<'a>> {
+    self.token()
+  }
+
+  fn token_start(&self) -> usize {
+    self.token_start
+  }
+
+  fn token_end(&self) -> usize {
+    self.byte_index
+  }
+
+  fn token_position(&self) -> Range {
+    Range::new(self.token_start, self.byte_index)
+  }
+
+  fn token_end_position(&self) -> Range {
+    Range::new(self.byte_index, self.byte_index)
+  }
+
+  #[cfg(feature = "serde")]
+  fn line_and_column_display(&self, position: Range) -> (usize, usize) {
+    get_line_and_column_display(position, self.file_text)
+  }
+
+  fn create_error_at(&self, position: Range, kind: ParseErrorKind) -> ParseError {
+    self.create_error_for_range(position, kind)
+  }
+
+  fn create_error_for_current_token(&self, kind: ParseErrorKind) -> ParseError {
+    Scanner::create_error_for_current_token(self, kind)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::borrow::Cow;
@@ -536,6 +715,58 @@ mod tests {
     );
   }
 
+  #[test]
+  fn it_tokenizes_leading_zeros() {
+    assert_has_tokens(
+      "007, -01",
+      vec![
+        Token::Number("0"),
+        Token::Number("0"),
+        Token::Number("7"),
+        Token::Comma,
+        Token::Number("-0"),
+        Token::Number("1"),
+      ],
+    );
+    let options = ScannerOptions {
+      allow_leading_zeros: true,
+      ..Default::default()
+    };
+    assert_has_tokens_with_options(
+      "007, -00.5",
+      &options,
+      vec![Token::Number("007"), Token::Comma, Token::Number("-00.5")],
+    );
+    let options = ScannerOptions {
+      split_leading_zeros: false,
+      ..Default::default()
+    };
+    assert_has_tokens_with_options(
+      "0, 0.5",
+      &options,
+      vec![Token::Number("0"), Token::Comma, Token::Number("0.5")],
+    );
+    assert_has_error_with_options(
+      "[0, 01]",
+      &options,
+      "Leading zeros on numbers are not allowed on line 1 column 5",
+    );
+  }
+
+  #[test]
+  fn it_errors_control_characters_in_strings_when_not_allowed() {
+    assert_has_tokens("\"a\u{1}b\"", vec![Token::String(Cow::Borrowed("a\u{1}b"))]);
+    let options = ScannerOptions {
+      allow_control_characters_in_strings: false,
+      ..Default::default()
+    };
+    assert_has_error_with_options(
+      "\"a\\n\u{1}\"",
+      &options,
+      "Control characters must be escaped in strings on line 1 column 5",
+    );
+  }
+
   #[test]
   fn it_tokenizes_hexadecimal_numbers() {
     assert_has_tokens(
@@ -633,8 +864,136 @@ mod tests {
     );
   }
 
+  #[test]
+  fn it_tokenizes_json5_numbers() {
+    assert_has_tokens_with_options(
+      "Infinity, -Infinity, +NaN, .5, -.5, 5., 5.e3,",
+      &json5_options(),
+      vec![
+        Token::Number("Infinity"),
+        Token::Comma,
+        Token::Number("-Infinity"),
+        Token::Comma,
+        Token::Number("+NaN"),
+        Token::Comma,
+        Token::Number(".5"),
+        Token::Comma,
+        Token::Number("-.5"),
+        Token::Comma,
+        Token::Number("5."),
+        Token::Comma,
+        Token::Number("5.e3"),
+        Token::Comma,
+      ],
+    );
+  }
+
+  #[test]
+  fn it_errors_json5_numbers_when_not_allowed() {
+    assert_has_error(".5", "Unexpected token on line 1 column 1");
+    assert_has_error("5.", "Expected digit on line 1 column 3");
+    assert_has_error("-Infinity", "Expected digit following negative sign on line 1 column 2");
+    assert_has_error_with_options(".", &json5_options(), "Expected digit on line 1 column 2");
+    assert_has_error_with_options("-.e1", &json5_options(), "Expected digit on line 1 column 3");
+  }
+
+  #[test]
+  fn it_tokenizes_json5_strings() {
+    assert_has_tokens_with_options(
+      "'\\x41\\v\\0', \"a\\\nb\\\r\nc\", \"\\'\\q\",",
+      &json5_options(),
+      vec![
+        Token::String(Cow::Borrowed("A\u{0B}\0")),
+        Token::Comma,
+        Token::String(Cow::Borrowed("abc")),
+        Token::Comma,
+        Token::String(Cow::Borrowed("'q")),
+        Token::Comma,
+      ],
+    );
+  }
+
+  #[test]
+  fn it_errors_json5_strings() {
+    let options = json5_options();
+    assert_has_error_with_options(r#""\01""#, &options, "Invalid escape on line 1 column 2");
+    assert_has_error_with_options(r#""\1""#, &options, "Invalid escape on line 1 column 2");
+    assert_has_error_with_options(r#""\x4""#, &options, "Expected two hex digits on line 1 column 2");
+    assert_has_error(r#""\x41""#, "Invalid escape on line 1 column 2");
+    assert_has_error("\"a\\\nb\"", "Invalid escape on line 1 column 3");
+  }
+
+  #[test]
+  fn it_tokenizes_json5_identifiers_and_whitespace() {
+    assert_has_tokens(
+      "\u{FEFF}$schema\u{A0}_a",
+      vec![Token::Word("$schema"), Token::Word("_a")],
+    );
+    let options = ScannerOptions {
+      allow_dollar_sign_in_words: false,
+      ..Default::default()
+    };
+    assert_has_error_with_options("a$b", &options, "Unexpected token on line 1 column 1");
+    assert_has_error_with_options("a\\u0024", &options, "Unexpected token on line 1 column 1");
+  }
+
+  #[test]
+  fn it_tokenizes_json5_identifier_escapes_and_joiners() {
+    assert_has_tokens(
+      "a\\u0062 \\u0024c",
+      vec![Token::Word("a\\u0062"), Token::Word("\\u0024c")],
+    );
+    assert_has_tokens(
+      "a\u{200C}b\u{200D}c a\u{0301}_\u{203F}",
+      vec![Token::Word("a\u{200C}b\u{200D}c"), Token::Word("a\u{0301}_\u{203F}")],
+    );
+    assert_has_tokens("true\\u0061", vec![Token::Word("true\\u0061")]);
+    assert_has_error("a\\u0020", "Unexpected token on line 1 column 1");
+    assert_has_error("a\\x62", "Unexpected token on line 1 column 1");
+  }
+
+  #[test]
+  fn it_skips_byte_order_mark_as_whitespace() {
+    assert_has_tokens("\u{FEFF}1", vec![Token::Number("1")]);
+    assert_has_tokens(
+      "[1,\u{FEFF}2]",
+      vec![
+        Token::OpenBracket,
+        Token::Number("1"),
+        Token::Comma,
+        Token::Number("2"),
+        Token::CloseBracket,
+      ],
+    );
+    assert_has_tokens(
+      "{\u{FEFF}a:1}",
+      vec![
+        Token::OpenBrace,
+        Token::Word("a"),
+        Token::Colon,
+        Token::Number("1"),
+        Token::CloseBrace,
+      ],
+    );
+  }
+
+  fn json5_options() -> ScannerOptions {
+    ScannerOptions {
+      allow_infinity_and_nan: true,
+      allow_leading_decimal_point: true,
+      allow_trailing_decimal_point: true,
+      allow_line_continuations: true,
+      allow_extended_escapes: true,
+      ..Default::default()
+    }
+  }
+
   fn assert_has_tokens(text: &str, tokens: Vec<Token>) {
-    let mut scanner = Scanner::new(text, &Default::default());
+    assert_has_tokens_with_options(text, &Default::default(), tokens);
+  }
+
+  fn assert_has_tokens_with_options(text: &str, options: &ScannerOptions, tokens: Vec<Token>) {
+    let mut scanner = Scanner::new(text, options);
     let mut scanned_tokens = Vec::new();
 
     loop {
@@ -649,7 +1008,11 @@ mod tests {
   }
 
   fn assert_has_error(text: &str, message: &str) {
-    let mut scanner = Scanner::new(text, &Default::default());
+    assert_has_error_with_options(text, &Default::default(), message);
+  }
+
+  fn assert_has_error_with_options(text: &str, options: &ScannerOptions, message: &str) {
+    let mut scanner = Scanner::new(text, options);
     let mut error_message = String::new();
 
     loop {
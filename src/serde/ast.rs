@@ -0,0 +1,360 @@
+use std::borrow::Cow;
+use std::slice;
+
+use ::serde::Deserialize;
+use ::serde::Deserializer;
+use ::serde::de;
+use ::serde::de::DeserializeSeed;
+use ::serde::de::IntoDeserializer;
+use ::serde::de::MapAccess;
+use ::serde::de::SeqAccess;
+use ::serde::de::Unexpected;
+use ::serde::de::Visitor;
+use ::serde::de::value::BorrowedStrDeserializer;
+use ::serde::de::value::MapAccessDeserializer;
+use ::serde::forward_to_deserialize_any;
+
+use super::NumberHint;
+use super::deserialize_numbers;
+use super::spanned::SPANNED_NAME;
+use super::spanned::SpannedMapAccess;
+use super::spanned::SpannedValue;
+use super::visit_number;
+use crate::ast;
+use crate::common::Range;
+use crate::common::Ranged;
+use crate::errors::ParseError;
+use crate::errors::get_line_and_column_display;
+
+/// Deserializes a type implementing `serde::Deserialize` from an AST value.
+///
+/// Errors have the range of the value they occurred at along with its
+/// line and column in the provided text, which should be the text the
+/// AST was parsed from.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::ParseOptions;
+/// use jsonc_parser::from_ast_value;
+/// use jsonc_parser::parse_to_ast;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///   version: u32,
+/// }
+///
+/// let text = r#"{
+///   "version": "1"
+/// }"#;
+/// let ast = parse_to_ast(text, &Default::default(), &ParseOptions::default()).unwrap();
+/// let err = from_ast_value::<Config>(ast.value.as_ref().unwrap(), text).err().unwrap();
+/// assert_eq!(
+///   err.to_string(),
+///   "invalid type: string \"1\", expected u32 on line 2 column 14",
+/// );
+/// ```
+pub fn from_ast_value<'de, T: Deserialize<'de>>(value: &ast::Value<'de>, text: &str) -> Result<T, ParseError> {
+  T::deserialize(AstValueDeserializer::new(value, text))
+}
+
+/// Deserializer for an AST value.
+///
+/// See `from_ast_value` for details.
+pub struct AstValueDeserializer<'a, 'de> {
+  value: &'a ast::Value<'de>,
+  text: &'a str,
+}
+
+impl<'a, 'de> AstValueDeserializer<'a, 'de> {
+  /// Creates a deserializer for the value that was parsed from the provided text.
+  pub fn new(value: &'a ast::Value<'de>, text: &'a str) -> Self {
+    AstValueDeserializer { value, text }
+  }
+
+  fn with_position<T>(&self, result: Result<T, ParseError>) -> Result<T, ParseError> {
+    result.map_err(|err| err.with_position(self.value.range(), self.text))
+  }
+
+  fn deserialize_with_hint<V: Visitor<'de>>(self, hint: NumberHint, visitor: V) -> Result<V::Value, ParseError> {
+    let result = match self.value {
+      ast::Value::StringLit(lit) => match &lit.value {
+        Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+        Cow::Owned(value) => visitor.visit_str(value),
+      },
+      ast::Value::NumberLit(lit) => visit_number(lit.value, hint, visitor),
+      ast::Value::BooleanLit(lit) => visitor.visit_bool(lit.value),
+      ast::Value::NullKeyword(_) => visitor.visit_unit(),
+      ast::Value::MissingValue(_) => Err(ParseError::custom_err("missing value".to_string())),
+      ast::Value::Array(arr) => {
+        let mut access = AstSeqAccess {
+          elements: arr.elements.iter(),
+          text: self.text,
+        };
+        visitor
+          .visit_seq(&mut access)
+          .and_then(|value| match access.elements.len() {
+            0 => Ok(value),
+            remaining => Err(de::Error::invalid_length(
+              arr.elements.len(),
+              &format!("{} elements in sequence", arr.elements.len() - remaining).as_str(),
+            )),
+          })
+      }
+      ast::Value::Object(obj) => visitor.visit_map(AstMapAccess::new(obj, self.text)),
+    };
+    self.with_position(result)
+  }
+}
+
+impl<'de> IntoDeserializer<'de, ParseError> for AstValueDeserializer<'_, 'de> {
+  type Deserializer = Self;
+
+  fn into_deserializer(self) -> Self::Deserializer {
+    self
+  }
+}
+
+impl<'de> Deserializer<'de> for AstValueDeserializer<'_, 'de> {
+  type Error = ParseError;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_with_hint(NumberHint::Any, visitor)
+  }
+
+  deserialize_numbers!(|self, hint, visitor| self.deserialize_with_hint(hint, visitor));
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      ast::Value::NullKeyword(_) => visitor.visit_none(),
+      _ => visitor.visit_some(self),
+    }
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    let result = match self.value {
+      ast::Value::StringLit(lit) => match &lit.value {
+        Cow::Borrowed(value) => visitor.visit_enum(BorrowedStrDeserializer::new(value)),
+        Cow::Owned(value) => visitor.visit_enum(IntoDeserializer::<ParseError>::into_deserializer(value.as_str())),
+      },
+      // { "Variant": data }
+      ast::Value::Object(obj) if obj.properties.len() == 1 => {
+        visitor.visit_enum(MapAccessDeserializer::new(AstMapAccess::new(obj, self.text)))
+      }
+      other => Err(de::Error::invalid_type(
+        unexpected(other),
+        &"a string or an object with a single property",
+      )),
+    };
+    self.with_position(result)
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    _fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    if name == SPANNED_NAME {
+      visitor.visit_map(SpannedMapAccess::new(self))
+    } else {
+      self.deserialize_any(visitor)
+    }
+  }
+
+  forward_to_deserialize_any! {
+    bool char str string bytes byte_buf unit unit_struct
+    seq tuple tuple_struct map identifier ignored_any
+  }
+}
+
+impl<'de> SpannedValue<'de> for AstValueDeserializer<'_, 'de> {
+  fn deserialize_value<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, ParseError> {
+    seed.deserialize(AstValueDeserializer::new(self.value, self.text))
+  }
+
+  fn range(&self) -> Range {
+    self.value.range()
+  }
+
+  fn line_and_column_display(&self) -> (usize, usize) {
+    get_line_and_column_display(self.value.range(), self.text)
+  }
+}
+
+fn unexpected<'a>(value: &'a ast::Value) -> Unexpected<'a> {
+  match value {
+    ast::Value::StringLit(lit) => Unexpected::Str(&lit.value),
+    ast::Value::NumberLit(lit) => Unexpected::Other(lit.value),
+    ast::Value::BooleanLit(lit) => Unexpected::Bool(lit.value),
+    ast::Value::Object(_) => Unexpected::Map,
+    ast::Value::Array(_) => Unexpected::Seq,
+    ast::Value::NullKeyword(_) => Unexpected::Unit,
+    ast::Value::MissingValue(_) => Unexpected::Other("missing value"),
+  }
+}
+
+struct AstSeqAccess<'a, 'de> {
+  elements: slice::Iter<'a, ast::Value<'de>>,
+  text: &'a str,
+}
+
+impl<'de> SeqAccess<'de> for AstSeqAccess<'_, 'de> {
+  type Error = ParseError;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+    match self.elements.next() {
+      Some(value) => seed.deserialize(AstValueDeserializer::new(value, self.text)).map(Some),
+      None => Ok(None),
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.elements.len())
+  }
+}
+
+struct AstMapAccess<'a, 'de> {
+  properties: slice::Iter<'a, ast::ObjectProp<'de>>,
+  value: Option<&'a ast::Value<'de>>,
+  text: &'a str,
+}
+
+impl<'a, 'de> AstMapAccess<'a, 'de> {
+  fn new(obj: &'a ast::Object<'de>, text: &'a str) -> Self {
+    AstMapAccess {
+      properties: obj.properties.iter(),
+      value: None,
+      text,
+    }
+  }
+}
+
+impl<'de> MapAccess<'de> for AstMapAccess<'_, 'de> {
+  type Error = ParseError;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+    let Some(prop) = self.properties.next() else {
+      return Ok(None);
+    };
+    self.value = Some(&prop.value);
+    let result = match &prop.name {
+      ast::ObjectPropName::String(ast::StringLit { value, .. })
+      | ast::ObjectPropName::Word(ast::WordLit { value, .. }) => match value {
+        Cow::Borrowed(name) => seed.deserialize(BorrowedStrDeserializer::new(name)),
+        Cow::Owned(name) => seed.deserialize(IntoDeserializer::<ParseError>::into_deserializer(name.as_str())),
+      },
+    };
+    result
+      .map(Some)
+      .map_err(|err| err.with_position(prop.name.range(), self.text))
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    let value = self.value.take().expect("next_value_seed called before next_key_seed");
+    seed.deserialize(AstValueDeserializer::new(value, self.text))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.properties.len())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::ParseOptions;
+  use crate::parse_to_ast;
+
+  #[derive(Debug, PartialEq, serde::Deserialize)]
+  #[serde(crate = "::serde", deny_unknown_fields)]
+  struct Config<'a> {
+    name: &'a str,
+    values: Vec<u8>,
+    kind: Kind,
+    other: Option<(bool, f64)>,
+  }
+
+  #[derive(Debug, PartialEq, serde::Deserialize)]
+  #[serde(crate = "::serde")]
+  enum Kind {
+    A,
+    B(f64),
+    C { hex: i32 },
+  }
+
+  fn deserialize<'a, T: Deserialize<'a>>(text: &'a str) -> Result<T, ParseError> {
+    let ast = parse_to_ast(text, &Default::default(), &ParseOptions::default()).unwrap();
+    from_ast_value(ast.value.as_ref().unwrap(), text)
+  }
+
+  #[test]
+  fn it_should_deserialize() {
+    let text = r#"{
+      // comment
+      name: "test",
+      "values": [1, 2,],
+      "kind": { "C": { "hex": 0x10 } },
+      "other": [true, 1e2]
+    }"#;
+    assert_eq!(
+      deserialize::<Config>(text).unwrap(),
+      Config {
+        name: "test",
+        values: vec![1, 2],
+        kind: Kind::C { hex: 16 },
+        other: Some((true, 100.0)),
+      }
+    );
+    assert_eq!(
+      deserialize::<Vec<Kind>>(r#"["A", { "B": 1 }]"#).unwrap(),
+      vec![Kind::A, Kind::B(1.0)]
+    );
+  }
+
+  #[test]
+  fn it_should_error_with_position() {
+    let assert_error = |text: &str, message: &str| {
+      assert_eq!(deserialize::<Config>(text).unwrap_err().to_string(), message);
+    };
+    assert_error(
+      r#"{ "name": "test", "values": [1, 256], "kind": "A" }"#,
+      "invalid value: integer `256`, expected u8 on line 1 column 33",
+    );
+    assert_error(
+      r#"{ "name": "test", "values": [], "kind": "A", "unknown": 1 }"#,
+      "unknown field `unknown`, expected one of `name`, `values`, `kind`, `other` on line 1 column 46",
+    );
+    assert_error(
+      r#"{ "name": "test", "values": [] }"#,
+      "missing field `kind` on line 1 column 1",
+    );
+    assert_error(
+      r#"{ "name": "test", "values": [], "kind": { "C": { "hex": 1.5 } } }"#,
+      "invalid type: floating point `1.5`, expected i32 on line 1 column 57",
+    );
+    assert_error(
+      r#"{ "name": "test", "values": [], "kind": "A", "other": [true, 1, 2] }"#,
+      "invalid length 3, expected 2 elements in sequence on line 1 column 55",
+    );
+    assert_error(
+      r#"{ "name": "test", "values": [], "kind": { "A": null, "B": 1 } }"#,
+      "invalid type: map, expected a string or an object with a single property on line 1 column 41",
+    );
+  }
+}
@@ -0,0 +1,1186 @@
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashSet;
+
+use ::serde::de::DeserializeSeed;
+use ::serde::de::EnumAccess;
+use ::serde::de::IntoDeserializer;
+use ::serde::de::MapAccess;
+use ::serde::de::SeqAccess;
+use ::serde::de::VariantAccess;
+use ::serde::de::Visitor;
+use ::serde::de::value::BorrowedStrDeserializer;
+use ::serde::forward_to_deserialize_any;
+
+use super::DuplicateKeyPolicy;
+use super::ParseOptions;
+use super::common::Range;
+use super::errors::ParseError;
+use super::errors::ParseErrorKind;
+use super::tokens::Token;
+use crate::number;
+use crate::number::NumberConversionError;
+use crate::parser::JsoncParser;
+use crate::parser::TokenSource;
+use spanned::SPANNED_NAME;
+use spanned::SpannedMapAccess;
+use spanned::SpannedValue;
+
+mod ast;
+mod reader;
+mod ser;
+mod spanned;
+mod stream;
+mod value;
+
+pub use ast::*;
+pub use reader::from_reader;
+pub use ser::*;
+pub use spanned::Spanned;
+pub use stream::*;
+pub use value::JsonValueDeserializer;
+
+/// Parses a string containing JSONC to a `serde_json::Value` or any
+/// type that implements `serde::Deserialize`.
+///
+/// Requires the "serde" cargo feature:
+///
+/// ```toml
+/// jsonc-parser = { version = "...", features = ["serde"] }
+/// ```
+///
+/// # Example
+///
+/// Parsing to a `serde_json::Value`:
+///
+/// ```rs
+/// use jsonc_parser::parse_to_serde_value;
+///
+/// let json_value: serde_json::Value = parse_to_serde_value(
+///   r#"{ "test": 5 } // test"#,
+///   &Default::default(),
+/// ).unwrap();
+/// ```
+///
+/// Or to a concrete type:
+///
+/// ```rs
+/// use jsonc_parser::parse_to_serde_value;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///   test: u32,
+/// }
+///
+/// let config: Config = parse_to_serde_value(
+///   r#"{ "test": 5 } // test"#,
+///   &Default::default(),
+/// ).unwrap();
+/// ```
+///
+/// Empty or whitespace-only input deserializes as `null`, which means
+/// `Option<T>` will produce `None` while other types will return a
+/// type-mismatch error.
+pub fn parse_to_serde_value<T: ::serde::de::DeserializeOwned>(
+  text: &str,
+  parse_options: &ParseOptions,
+) -> Result<T, ParseError> {
+  parse_to_serde_value_borrowed(text, parse_options)
+}
+
+/// Parses a string containing JSONC to a type that implements
+/// `serde::Deserialize` and may borrow from the text.
+///
+/// Strings and keys without escape sequences are provided to the visitor
+/// as borrowed slices of the text, so fields such as `&'de str` and
+/// `Cow<'de, str>` don't allocate. A `&'de str` field will error when the
+/// string in the text contains an escape sequence.
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+/// use jsonc_parser::parse_to_serde_value_borrowed;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config<'a> {
+///   name: &'a str,
+///   #[serde(borrow)]
+///   description: Cow<'a, str>,
+/// }
+///
+/// let text = r#"{ "name": "test", "description": "a \"quoted\" value" }"#;
+/// let config: Config = parse_to_serde_value_borrowed(text, &Default::default()).unwrap();
+/// assert_eq!(config.name, "test");
+/// assert_eq!(config.description, "a \"quoted\" value");
+/// ```
+pub fn parse_to_serde_value_borrowed<'de, T: ::serde::Deserialize<'de>>(
+  text: &'de str,
+  parse_options: &ParseOptions,
+) -> Result<T, ParseError> {
+  deserialize_root(JsoncParser::new(text, parse_options))
+}
+
+/// Deserializes the single root value of the parser's text.
+fn deserialize_root<'de, S: TokenSource<'de>, T: ::serde::Deserialize<'de>>(
+  mut parser: JsoncParser<'de, S>,
+) -> Result<T, ParseError> {
+  parser.scan()?;
+  let value = if parser.current_token().is_none() {
+    T::deserialize(IntoDeserializer::<ParseError>::into_deserializer(()))?
+  } else {
+    parser.put_back();
+    T::deserialize(&mut parser)?
+  };
+
+  parser.scan()?;
+  if parser.current_token().is_some() {
+    return Err(
+      parser
+        .scanner
+        .create_error_for_current_token(ParseErrorKind::MultipleRootJsonValues),
+    );
+  }
+  Ok(value)
+}
+
+impl ::serde::de::Error for ParseError {
+  fn custom<T: std::fmt::Display>(msg: T) -> Self {
+    ParseError::custom_err(msg.to_string())
+  }
+}
+
+impl<'de, S: TokenSource<'de>> ::serde::Deserializer<'de> for &mut JsoncParser<'de, S> {
+  type Error = ParseError;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    deserialize_value(self, NumberHint::Any, visitor)
+  }
+
+  deserialize_numbers!(|self, hint, visitor| deserialize_value(self, hint, visitor));
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.scan()?;
+    match self.current_token() {
+      Some(Token::Null) | None => visitor.visit_none(),
+      Some(_) => {
+        self.put_back();
+        visitor.visit_some(self)
+      }
+    }
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    self.scan()?;
+    let position = self.scanner.token_position();
+    let result = match self.current_token() {
+      Some(Token::String(s)) => {
+        let variant: String = s.into_owned();
+        visitor.visit_enum(variant.into_deserializer())
+      }
+      Some(Token::OpenBrace) => {
+        // expect exactly one property: { "Variant": data }
+        self.scan()?;
+        let variant = match self.current_token() {
+          Some(Token::String(s)) => s.into_owned(),
+          _ => {
+            return Err(self.scanner.create_error_at(
+              position,
+              ParseErrorKind::Custom("expected a string key for enum variant".to_string()),
+            ));
+          }
+        };
+
+        // expect colon
+        self.scan_object_colon()?;
+
+        let result = visitor.visit_enum(ObjectEnumAccess { parser: self, variant });
+        result.and_then(|v| {
+          // expect close brace, allowing a trailing comma
+          match self.scan_object_entry(false)? {
+            false => Ok(v),
+            true => Err(
+              self
+                .scanner
+                .create_error_for_current_token(ParseErrorKind::UnterminatedObject),
+            ),
+          }
+        })
+      }
+      _ => {
+        return Err(self.scanner.create_error_at(
+          position,
+          ParseErrorKind::Custom("expected a string or object for enum".to_string()),
+        ));
+      }
+    };
+    result.map_err(|e| self.with_position(e, position))
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    _fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    if name != SPANNED_NAME {
+      return self.deserialize_any(visitor);
+    }
+    self.scan()?;
+    if self.current_token().is_none() {
+      return Err(ParseError::custom_err("unexpected end of input".to_string()));
+    }
+    let start = self.scanner.token_start();
+    let start_position = self.scanner.token_position();
+    self.put_back();
+    visitor.visit_map(SpannedMapAccess::new(ScannerSpannedValue {
+      parser: self,
+      start,
+      start_position,
+    }))
+  }
+
+  forward_to_deserialize_any! {
+    bool char str string bytes byte_buf unit unit_struct
+    seq tuple tuple_struct map identifier ignored_any
+  }
+}
+
+struct ScannerSpannedValue<'de, 'b, S: TokenSource<'de>> {
+  parser: &'b mut JsoncParser<'de, S>,
+  start: usize,
+  start_position: S::Position,
+}
+
+impl<'de, S: TokenSource<'de>> SpannedValue<'de> for ScannerSpannedValue<'de, '_, S> {
+  fn deserialize_value<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, ParseError> {
+    seed.deserialize(&mut *self.parser)
+  }
+
+  fn range(&self) -> Range {
+    Range::new(self.start, self.parser.scanner.token_end())
+  }
+
+  fn line_and_column_display(&self) -> (usize, usize) {
+    self.parser.scanner.line_and_column_display(self.start_position)
+  }
+}
+
+fn deserialize_value<'de, S: TokenSource<'de>, V: Visitor<'de>>(
+  parser: &mut JsoncParser<'de, S>,
+  hint: NumberHint,
+  visitor: V,
+) -> Result<V::Value, ParseError> {
+  parser.scan()?;
+  deserialize_token(parser, hint, visitor)
+}
+
+/// Deserializes the value starting at the current token.
+fn deserialize_token<'de, S: TokenSource<'de>, V: Visitor<'de>>(
+  parser: &mut JsoncParser<'de, S>,
+  hint: NumberHint,
+  visitor: V,
+) -> Result<V::Value, ParseError> {
+  let position = parser.scanner.token_position();
+  let result = match parser.current_token() {
+    None => return Err(ParseError::custom_err("unexpected end of input".to_string())),
+    Some(Token::Null) => visitor.visit_unit(),
+    Some(Token::Boolean(b)) => visitor.visit_bool(b),
+    Some(Token::Number(n)) => visit_number(n, hint, visitor),
+    Some(Token::String(s)) => match parser.scanner.borrowed_token() {
+      Some(Token::String(Cow::Borrowed(b))) => visitor.visit_borrowed_str(b),
+      Some(Token::String(Cow::Owned(o))) => visitor.visit_string(o),
+      _ => visitor.visit_str(&s),
+    },
+    Some(Token::OpenBracket) => {
+      parser.enter_container()?;
+      let finished = Cell::new(false);
+      let result = visitor.visit_seq(ScannerSeqAccess {
+        parser,
+        first: true,
+        finished: &finished,
+      });
+      if result.is_ok()
+        && !finished.get()
+        && let Err(e) = drain_array(parser)
+      {
+        parser.exit_container();
+        return Err(e);
+      }
+      parser.exit_container();
+      result
+    }
+    Some(Token::OpenBrace) => {
+      parser.enter_container()?;
+      let result = visitor.visit_map(ScannerMapAccess::new(parser));
+      parser.exit_container();
+      result
+    }
+    Some(_) => return Err(parser.unexpected_token_error()),
+  };
+  result.map_err(|e| parser.with_position(e, position))
+}
+
+// number handling
+
+/// The kind of number requested by the visitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberHint {
+  /// `deserialize_any`, which may provide any kind of number.
+  Any,
+  /// A number type with at most 64 bits.
+  Primitive,
+  I128,
+  U128,
+}
+
+/// Implements the deserialize methods for numbers by calling the provided
+/// expression with the `NumberHint` for the type.
+macro_rules! deserialize_numbers {
+  (|$self:ident, $hint:ident, $visitor:ident| $deserialize:expr) => {
+    $crate::serde::deserialize_numbers!(@impl |$self, $hint, $visitor| $deserialize,
+      deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+      deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+      deserialize_f32 deserialize_f64 => $crate::serde::NumberHint::Primitive,
+      deserialize_i128 => $crate::serde::NumberHint::I128,
+      deserialize_u128 => $crate::serde::NumberHint::U128
+    );
+  };
+  (@impl |$self:ident, $hint:ident, $visitor:ident| $deserialize:expr, $($($method:ident)+ => $hint_value:expr),+) => {
+    $($(
+      fn $method<V: ::serde::de::Visitor<'de>>($self, $visitor: V) -> Result<V::Value, Self::Error> {
+        let $hint = $hint_value;
+        $deserialize
+      }
+    )+)+
+  };
+}
+
+use deserialize_numbers;
+
+/// Gets if the number has no fractional part or exponent.
+fn visit_number<'de, V: Visitor<'de>, E: ::serde::de::Error>(
+  raw: &str,
+  hint: NumberHint,
+  visitor: V,
+) -> Result<V::Value, E> {
+  if number::is_integer_literal(raw) {
+    match hint {
+      NumberHint::I128 => {
+        if let Ok(value) = number::to_i128(raw) {
+          return visitor.visit_i128(value);
+        }
+      }
+      NumberHint::U128 => {
+        if let Ok(value) = number::to_u128(raw) {
+          return visitor.visit_u128(value);
+        }
+      }
+      NumberHint::Any | NumberHint::Primitive => {}
+    }
+    if let Ok(value) = number::to_i64(raw) {
+      return visitor.visit_i64(value);
+    }
+    if let Ok(value) = number::to_u64(raw) {
+      return visitor.visit_u64(value);
+    }
+  }
+
+  #[cfg(feature = "arbitrary_precision")]
+  if hint == NumberHint::Any
+    && let Ok(text) = number::to_json_text(raw)
+  {
+    return visitor.visit_map(ArbitraryPrecisionNumberAccess::<E>::new(text));
+  }
+
+  let is_negative = raw.starts_with('-');
+  match number::to_closest_f64(raw) {
+    Ok(value) => visitor.visit_f64(value),
+    Err(NumberConversionError::Overflow) => {
+      visitor.visit_f64(if is_negative { f64::NEG_INFINITY } else { f64::INFINITY })
+    }
+    Err(NumberConversionError::PrecisionLoss) => visitor.visit_f64(if is_negative { -0.0 } else { 0.0 }),
+    // fallback for unparseable numbers
+    Err(_) => visitor.visit_str(raw),
+  }
+}
+
+/// Name of the struct and its single field that serde_json's `arbitrary_precision`
+/// feature uses to provide a number as text.
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) const ARBITRARY_PRECISION_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// The map that serde_json's `arbitrary_precision` feature uses to provide a
+/// number as text.
+#[cfg(feature = "arbitrary_precision")]
+struct ArbitraryPrecisionNumberAccess<E> {
+  text: Option<String>,
+  marker: std::marker::PhantomData<E>,
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl<E> ArbitraryPrecisionNumberAccess<E> {
+  fn new(text: String) -> Self {
+    ArbitraryPrecisionNumberAccess {
+      text: Some(text),
+      marker: std::marker::PhantomData,
+    }
+  }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl<'de, E: ::serde::de::Error> MapAccess<'de> for ArbitraryPrecisionNumberAccess<E> {
+  type Error = E;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+    if self.text.is_none() {
+      return Ok(None);
+    }
+    seed
+      .deserialize(BorrowedStrDeserializer::new(ARBITRARY_PRECISION_NUMBER_TOKEN))
+      .map(Some)
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    let text = self.text.take().expect("next_value_seed called before next_key_seed");
+    seed.deserialize(text.into_deserializer())
+  }
+}
+
+// array handling
+
+/// Consumes remaining array elements and the closing `]` when a visitor
+/// (e.g. for a tuple) stops reading before the end of the array.
+fn drain_array<'de, S: TokenSource<'de>>(parser: &mut JsoncParser<'de, S>) -> Result<(), ParseError> {
+  loop {
+    parser.scan_array_comma()?;
+    match parser.current_token() {
+      Some(Token::CloseBracket) => return Ok(()),
+      Some(_) => {
+        parser.put_back();
+        <::serde::de::IgnoredAny as ::serde::Deserialize>::deserialize(&mut *parser)?;
+      }
+      None => {
+        return Err(
+          parser
+            .scanner
+            .create_error_for_current_token(ParseErrorKind::UnterminatedArray),
+        );
+      }
+    }
+  }
+}
+
+struct ScannerSeqAccess<'a, 'b, S> {
+  parser: &'b mut JsoncParser<'a, S>,
+  first: bool,
+  finished: &'b Cell<bool>,
+}
+
+impl<'de, 'b, S: TokenSource<'de>> SeqAccess<'de> for ScannerSeqAccess<'de, 'b, S> {
+  type Error = ParseError;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+    if self.first {
+      self.first = false;
+      self.parser.scan()?;
+    } else {
+      self.parser.scan_array_comma()?;
+    }
+
+    match self.parser.current_token() {
+      Some(Token::CloseBracket) => {
+        self.finished.set(true);
+        Ok(None)
+      }
+      Some(_) => {
+        self.parser.put_back();
+        seed.deserialize(&mut *self.parser).map(Some)
+      }
+      None => Err(
+        self
+          .parser
+          .scanner
+          .create_error_for_current_token(ParseErrorKind::UnterminatedArray),
+      ),
+    }
+  }
+}
+
+// object handling
+
+struct ScannerMapAccess<'a, 'b, S> {
+  parser: &'b mut JsoncParser<'a, S>,
+  first: bool,
+  /// Keys seen so far. Only tracked when the duplicate key policy isn't last wins,
+  /// which forwards every occurrence to the visitor.
+  seen_keys: Option<HashSet<Cow<'a, str>>>,
+}
+
+impl<'a, 'b, S: TokenSource<'a>> ScannerMapAccess<'a, 'b, S> {
+  fn new(parser: &'b mut JsoncParser<'a, S>) -> Self {
+    let seen_keys = (parser.duplicate_key_policy != DuplicateKeyPolicy::LastWins).then(HashSet::new);
+    Self {
+      parser,
+      first: true,
+      seen_keys,
+    }
+  }
+}
+
+impl<'de, 'b, S: TokenSource<'de>> MapAccess<'de> for ScannerMapAccess<'de, 'b, S> {
+  type Error = ParseError;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+    loop {
+      let has_key = self.parser.scan_object_entry(self.first)?;
+      self.first = false;
+      if !has_key {
+        return Ok(None);
+      }
+
+      let key = self.parser.object_key();
+      if let Some(seen_keys) = self.seen_keys.as_mut()
+        && !seen_keys.insert(key.clone())
+      {
+        if self.parser.duplicate_key_policy == DuplicateKeyPolicy::Error {
+          return Err(self.parser.duplicate_key_error(&key));
+        }
+        // first wins, so skip over this occurrence's value
+        self.parser.scan_object_colon()?;
+        <::serde::de::IgnoredAny as ::serde::Deserialize>::deserialize(&mut *self.parser)?;
+        continue;
+      }
+
+      // borrow the key from the source when it's clean, only allocating for
+      // owned (escaped) keys — mirrors the value path's `Cow` keys
+      return match key {
+        Cow::Borrowed(key) => seed.deserialize(BorrowedStrDeserializer::new(key)),
+        Cow::Owned(key) => seed.deserialize(<String as IntoDeserializer<Self::Error>>::into_deserializer(key)),
+      }
+      .map(Some);
+    }
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    self.parser.scan_object_colon()?;
+    seed.deserialize(&mut *self.parser)
+  }
+}
+
+// enum handling
+
+struct ObjectEnumAccess<'a, 'b, S> {
+  parser: &'b mut JsoncParser<'a, S>,
+  variant: String,
+}
+
+impl<'de, 'b, S: TokenSource<'de>> EnumAccess<'de> for ObjectEnumAccess<'de, 'b, S> {
+  type Error = ParseError;
+  type Variant = ObjectVariantAccess<'de, 'b, S>;
+
+  fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+    let variant = seed.deserialize(<String as IntoDeserializer<Self::Error>>::into_deserializer(
+      self.variant,
+    ))?;
+    Ok((variant, ObjectVariantAccess { parser: self.parser }))
+  }
+}
+
+struct ObjectVariantAccess<'a, 'b, S> {
+  parser: &'b mut JsoncParser<'a, S>,
+}
+
+impl<'de, 'b, S: TokenSource<'de>> VariantAccess<'de> for ObjectVariantAccess<'de, 'b, S> {
+  type Error = ParseError;
+
+  fn unit_variant(self) -> Result<(), Self::Error> {
+    ::serde::Deserialize::deserialize(&mut *self.parser)
+  }
+
+  fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+    seed.deserialize(&mut *self.parser)
+  }
+
+  fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+    self.parser.scan()?;
+    match self.parser.current_token() {
+      Some(Token::OpenBracket) => deserialize_token(self.parser, NumberHint::Any, visitor),
+      _ => Err(ParseError::custom_err(
+        "expected an array for tuple variant".to_string(),
+      )),
+    }
+  }
+
+  fn struct_variant<V: Visitor<'de>>(
+    self,
+    _fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    self.parser.scan()?;
+    match self.parser.current_token() {
+      Some(Token::OpenBrace) => deserialize_token(self.parser, NumberHint::Any, visitor),
+      _ => Err(ParseError::custom_err(
+        "expected an object for struct variant".to_string(),
+      )),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::Value as SerdeValue;
+  use std::str::FromStr;
+
+  use super::*;
+
+  #[test]
+  fn it_should_error_when_has_error() {
+    assert_has_error(
+      "[][]",
+      "Text cannot contain more than one JSON value on line 1 column 3",
+    );
+  }
+
+  fn assert_has_error(text: &str, message: &str) {
+    let result = parse_to_serde_value::<SerdeValue>(text, &Default::default());
+    match result {
+      Ok(_) => panic!("Expected error, but did not find one."),
+      Err(err) => assert_eq!(err.to_string(), message),
+    }
+  }
+
+  #[test]
+  fn it_should_parse_to_serde_value() {
+    let result = parse_to_serde_value::<SerdeValue>(
+      r#"{ "a": { "a1": 5 }, "b": [0.3e+025], "c": "c1", "d": true, "e": false, "f": null }"#,
+      &Default::default(),
+    )
+    .unwrap();
+
+    let mut expected_value = serde_json::map::Map::new();
+    expected_value.insert("a".to_string(), {
+      let mut inner_obj = serde_json::map::Map::new();
+      inner_obj.insert(
+        "a1".to_string(),
+        SerdeValue::Number(serde_json::Number::from_str("5").unwrap()),
+      );
+      SerdeValue::Object(inner_obj)
+    });
+    expected_value.insert("b".to_string(), {
+      let inner_array = vec![SerdeValue::Number(serde_json::Number::from_str("0.3e+025").unwrap())];
+      SerdeValue::Array(inner_array)
+    });
+    expected_value.insert("c".to_string(), SerdeValue::String("c1".to_string()));
+    expected_value.insert("d".to_string(), SerdeValue::Bool(true));
+    expected_value.insert("e".to_string(), SerdeValue::Bool(false));
+    expected_value.insert("f".to_string(), SerdeValue::Null);
+
+    assert_eq!(result, SerdeValue::Object(expected_value));
+  }
+
+  #[test]
+  fn it_should_parse_hexadecimal_numbers_to_decimal() {
+    let result = parse_to_serde_value::<SerdeValue>(
+      r#"{
+        "hex1": 0x7DF,
+        "hex2": 0xFF,
+        "hex3": 0x10
+      }"#,
+      &Default::default(),
+    )
+    .unwrap();
+
+    let mut expected_value = serde_json::map::Map::new();
+    expected_value.insert("hex1".to_string(), SerdeValue::Number(serde_json::Number::from(2015)));
+    expected_value.insert("hex2".to_string(), SerdeValue::Number(serde_json::Number::from(255)));
+    expected_value.insert("hex3".to_string(), SerdeValue::Number(serde_json::Number::from(16)));
+
+    assert_eq!(result, SerdeValue::Object(expected_value));
+  }
+
+  #[test]
+  fn it_should_deserialize_large_and_hexadecimal_integers() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(crate = "::serde")]
+    struct Numbers {
+      a: u64,
+      b: i64,
+      c: u128,
+      d: i128,
+      e: u128,
+      f: f64,
+    }
+
+    let text = r#"{
+      "a": 0xFFFFFFFFFFFFFFFF,
+      "b": -0x8000000000000000,
+      "c": 340282366920938463463374607431768211455,
+      "d": -0x80000000000000000000000000000000,
+      "e": +0x1F,
+      "f": 0x20000000000001,
+    }"#;
+    let expected = Numbers {
+      a: u64::MAX,
+      b: i64::MIN,
+      c: u128::MAX,
+      d: i128::MIN,
+      e: 31,
+      f: 9007199254740992.0,
+    };
+    assert_eq!(
+      parse_to_serde_value::<Numbers>(text, &Default::default()).unwrap(),
+      expected
+    );
+    let ast = crate::parse_to_ast(text, &Default::default(), &Default::default()).unwrap();
+    assert_eq!(
+      from_ast_value::<Numbers>(ast.value.as_ref().unwrap(), text).unwrap(),
+      expected
+    );
+    assert_eq!(
+      from_reader::<_, Numbers>(text.as_bytes(), &Default::default()).unwrap(),
+      expected
+    );
+
+    let err = parse_to_serde_value::<u128>("-1", &Default::default()).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "invalid value: integer `-1`, expected u128 on line 1 column 1"
+    );
+    let err = parse_to_serde_value::<i128>("1.5", &Default::default()).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "invalid type: floating point `1.5`, expected i128 on line 1 column 1"
+    );
+  }
+
+  #[cfg(feature = "arbitrary_precision")]
+  #[test]
+  fn it_should_deserialize_arbitrary_precision_numbers() {
+    let result = parse_to_serde_value::<SerdeValue>(
+      "[123456789012345678901234567890, 1.50, -.5, 0x100000000000000000000000000000000, 5, Infinity]",
+      &ParseOptions::json5(),
+    )
+    .unwrap();
+    assert_eq!(
+      result.to_string(),
+      "[123456789012345678901234567890,1.50,-0.5,340282366920938463463374607431768211456,5,null]"
+    );
+  }
+
+  #[test]
+  fn it_should_parse_unary_plus_numbers() {
+    let result = parse_to_serde_value::<SerdeValue>(
+      r#"{
+        "pos1": +42,
+        "pos2": +0.5,
+        "pos3": +1e10
+      }"#,
+      &Default::default(),
+    )
+    .unwrap();
+
+    let mut expected_value = serde_json::map::Map::new();
+    expected_value.insert("pos1".to_string(), SerdeValue::Number(serde_json::Number::from(42)));
+    expected_value.insert(
+      "pos2".to_string(),
+      SerdeValue::Number(serde_json::Number::from_str("0.5").unwrap()),
+    );
+    expected_value.insert(
+      "pos3".to_string(),
+      SerdeValue::Number(serde_json::Number::from_str("1e10").unwrap()),
+    );
+
+    assert_eq!(result, SerdeValue::Object(expected_value));
+  }
+
+  #[test]
+  fn it_should_handle_duplicate_keys_with_policy() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      a: u32,
+    }
+
+    let text = r#"{ "a": 1, "b": [2], "a": 3 }"#;
+    let options = |policy| ParseOptions {
+      duplicate_key_policy: policy,
+      ..Default::default()
+    };
+    let value: SerdeValue = parse_to_serde_value(text, &options(DuplicateKeyPolicy::LastWins)).unwrap();
+    assert_eq!(value["a"], 3);
+    let value: SerdeValue = parse_to_serde_value(text, &options(DuplicateKeyPolicy::FirstWins)).unwrap();
+    assert_eq!(value["a"], 1);
+    assert_eq!(value["b"], serde_json::json!([2]));
+    let config: Config = parse_to_serde_value(text, &options(DuplicateKeyPolicy::FirstWins)).unwrap();
+    assert_eq!(config, Config { a: 1 });
+    let err = parse_to_serde_value::<SerdeValue>(text, &options(DuplicateKeyPolicy::Error)).unwrap_err();
+    assert_eq!(err.to_string(), "Duplicate object key 'a' on line 1 column 21");
+
+    // every occurrence reaches the visitor, which errors for a duplicate field
+    let err = parse_to_serde_value::<Config>(text, &options(DuplicateKeyPolicy::LastWins)).unwrap_err();
+    assert_eq!(err.to_string(), "duplicate field `a` on line 1 column 1");
+    let err = from_reader::<_, Config>(text.as_bytes(), &options(DuplicateKeyPolicy::LastWins)).unwrap_err();
+    assert_eq!(err.to_string(), "duplicate field `a` on line 1 column 1");
+  }
+
+  #[test]
+  fn it_should_deserialize_each_occurrence_of_duplicate_keys_once() {
+    use std::collections::BTreeMap;
+
+    thread_local! {
+      static COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Counts how many times a value is deserialized.
+    #[derive(Debug, PartialEq)]
+    struct Counted(u32);
+
+    impl<'de> ::serde::Deserialize<'de> for Counted {
+      fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        COUNT.with(|count| count.set(count.get() + 1));
+        u32::deserialize(deserializer).map(Counted)
+      }
+    }
+
+    type Config = BTreeMap<String, BTreeMap<String, Counted>>;
+
+    let text = r#"{
+      "a": { "c": 1, "c": 2 },
+      "b": { "c": 3, /* c */ c: 4, "\u0063": 5 },
+      "a": { "c": 6, "d": 7, "c": 8 },
+    }"#;
+    let expected = Config::from([
+      (
+        "a".to_string(),
+        BTreeMap::from([("c".to_string(), Counted(8)), ("d".to_string(), Counted(7))]),
+      ),
+      ("b".to_string(), BTreeMap::from([("c".to_string(), Counted(5))])),
+    ]);
+    COUNT.with(|count| count.set(0));
+    assert_eq!(
+      parse_to_serde_value::<Config>(text, &Default::default()).unwrap(),
+      expected
+    );
+    assert_eq!(COUNT.with(Cell::get), 8);
+    COUNT.with(|count| count.set(0));
+    assert_eq!(
+      from_reader::<_, Config>(text.as_bytes(), &Default::default()).unwrap(),
+      expected
+    );
+    assert_eq!(COUNT.with(Cell::get), 8);
+  }
+
+  #[test]
+  fn it_should_deserialize_json5() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      name: String,
+      ratio: f64,
+      max: f64,
+    }
+
+    let result: Config = parse_to_serde_value(
+      "{ name: 'multi\\\nline\\x21', ratio: .5, max: +Infinity, }",
+      &crate::ParseOptions::json5(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      result,
+      Config {
+        name: "multiline!".to_string(),
+        ratio: 0.5,
+        max: f64::INFINITY,
+      }
+    );
+  }
+
+  #[test]
+  fn it_should_deserialize_exponents_out_of_range_to_f64() {
+    let values: Vec<f64> = parse_to_serde_value(
+      "[1e400, -1e99999999999999999999, 1e-400, 1e-99999999999999999999]",
+      &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(values, [f64::INFINITY, f64::NEG_INFINITY, 0.0, 0.0]);
+  }
+
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn it_should_deserialize_infinity_and_nan_to_null_on_every_path() {
+    let text = "[Infinity, -Infinity, NaN, .5]";
+    let options = ParseOptions::json5();
+    let expected = serde_json::json!([null, null, null, 0.5]);
+    let value = parse_to_serde_value::<SerdeValue>(text, &options).unwrap();
+    assert_eq!(value, expected);
+    let ast_value = crate::parse_to_ast(text, &Default::default(), &options)
+      .unwrap()
+      .value
+      .unwrap();
+    assert_eq!(SerdeValue::try_from(ast_value).unwrap(), expected);
+  }
+
+  #[test]
+  fn it_should_deserialize_to_struct() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      name: String,
+      value: u32,
+      enabled: bool,
+    }
+
+    let result: Config = parse_to_serde_value(
+      r#"{ "name": "test", "value": 42, "enabled": true }"#,
+      &Default::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      result,
+      Config {
+        name: "test".to_string(),
+        value: 42,
+        enabled: true,
+      }
+    );
+  }
+
+  #[test]
+  fn it_should_report_position_on_type_error() {
+    #[derive(::serde::Deserialize, Debug)]
+    #[serde(crate = "::serde")]
+    #[allow(dead_code)]
+    struct Config {
+      name: String,
+    }
+
+    let text = r#"{
+  "name": true
+}"#;
+    let err = parse_to_serde_value::<Config>(text, &Default::default()).unwrap_err();
+    // the error should point at `true` (line 2, column 11)
+    assert_eq!(err.line_display(), 2);
+    assert_eq!(err.column_display(), 11);
+    assert!(err.to_string().contains("invalid type"), "got: {}", err);
+  }
+
+  #[test]
+  fn it_should_deserialize_option_fields() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      a: Option<u32>,
+      b: Option<u32>,
+    }
+
+    let result: Config = parse_to_serde_value(r#"{ "a": 5, "b": null }"#, &Default::default()).unwrap();
+
+    assert_eq!(result, Config { a: Some(5), b: None });
+  }
+
+  #[test]
+  fn it_should_deserialize_enum() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    enum Color {
+      Red,
+      Green,
+      Blue,
+    }
+
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      color: Color,
+    }
+
+    let result: Config = parse_to_serde_value(r#"{ "color": "Red" }"#, &Default::default()).unwrap();
+
+    assert_eq!(result, Config { color: Color::Red });
+  }
+
+  #[test]
+  fn it_should_deserialize_complex_enum() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    enum Shape {
+      Circle(f64),
+      Rectangle { width: f64, height: f64 },
+    }
+
+    let result: Shape = parse_to_serde_value(r#"{ "Circle": 5.0 }"#, &Default::default()).unwrap();
+    assert_eq!(result, Shape::Circle(5.0));
+
+    let result: Shape = parse_to_serde_value(
+      r#"{ "Rectangle": { "width": 3.0, "height": 4.0 } }"#,
+      &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+      result,
+      Shape::Rectangle {
+        width: 3.0,
+        height: 4.0
+      }
+    );
+  }
+
+  #[test]
+  fn it_should_handle_trailing_comma_in_enum_object() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    enum Shape {
+      Circle(f64),
+    }
+
+    let result: Shape = parse_to_serde_value(r#"{ "Circle": 5.0, }"#, &Default::default()).unwrap();
+    assert_eq!(result, Shape::Circle(5.0));
+
+    let options = ParseOptions {
+      allow_trailing_commas: false,
+      ..Default::default()
+    };
+    let err = parse_to_serde_value::<Shape>(r#"{ "Circle": 5.0, }"#, &options).unwrap_err();
+    assert!(matches!(err.kind(), ParseErrorKind::TrailingCommasNotAllowed));
+
+    let err = parse_to_serde_value::<Shape>(r#"{ "Circle": 5.0, "Other": 1 }"#, &Default::default()).unwrap_err();
+    assert!(matches!(err.kind(), ParseErrorKind::UnterminatedObject));
+  }
+
+  #[test]
+  fn it_should_return_null_for_empty_input() {
+    let result = parse_to_serde_value::<SerdeValue>("", &Default::default()).unwrap();
+    assert_eq!(result, SerdeValue::Null);
+  }
+
+  #[test]
+  fn it_should_return_none_for_empty_input_with_option() {
+    let result: Option<SerdeValue> = parse_to_serde_value("", &Default::default()).unwrap();
+    assert_eq!(result, None);
+  }
+
+  #[test]
+  fn it_should_return_some_for_non_empty_input_with_option() {
+    let result: Option<SerdeValue> = parse_to_serde_value(r#"{ "a": 1 }"#, &Default::default()).unwrap();
+    assert!(result.is_some());
+  }
+
+  #[test]
+  fn it_should_return_none_for_empty_input_with_option_struct() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      value: u32,
+    }
+
+    let result: Option<Config> = parse_to_serde_value("", &Default::default()).unwrap();
+    assert_eq!(result, None);
+
+    let result: Option<Config> = parse_to_serde_value("  \n  ", &Default::default()).unwrap();
+    assert_eq!(result, None);
+  }
+
+  #[test]
+  fn it_should_deserialize_tuples() {
+    // 1-element
+    let result: (u8,) = parse_to_serde_value("[1]", &Default::default()).unwrap();
+    assert_eq!(result, (1,));
+
+    // 2-element with mixed types
+    let result: (u8, String) = parse_to_serde_value(r#"[1, "hello"]"#, &Default::default()).unwrap();
+    assert_eq!(result, (1, "hello".to_string()));
+
+    // 3-element in an object
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      version: (u8, u8, u8),
+    }
+    let result: Config = parse_to_serde_value(r#"{ "version": [0, 3, 0] }"#, &Default::default()).unwrap();
+    assert_eq!(result, Config { version: (0, 3, 0) });
+
+    // 6-element
+    let result: (u8, u8, u8, u8, u8, u8) = parse_to_serde_value("[1, 2, 3, 4, 5, 6]", &Default::default()).unwrap();
+    assert_eq!(result, (1, 2, 3, 4, 5, 6));
+
+    // 9-element
+    let result: (u8, u8, u8, u8, u8, u8, u8, u8, u8) =
+      parse_to_serde_value("[1, 2, 3, 4, 5, 6, 7, 8, 9]", &Default::default()).unwrap();
+    assert_eq!(result, (1, 2, 3, 4, 5, 6, 7, 8, 9));
+
+    // 12-element
+    let result: (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8) =
+      parse_to_serde_value("[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]", &Default::default()).unwrap();
+    assert_eq!(result, (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12));
+  }
+
+  #[test]
+  fn it_should_handle_comments_in_jsonc() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config {
+      value: u32,
+    }
+
+    let result: Config = parse_to_serde_value(
+      r#"{
+        // this is a comment
+        "value": 42 /* inline comment */
+      }"#,
+      &Default::default(),
+    )
+    .unwrap();
+
+    assert_eq!(result, Config { value: 42 });
+  }
+
+  #[test]
+  fn it_should_borrow_strings_from_text() {
+    #[derive(::serde::Deserialize, Debug, PartialEq)]
+    #[serde(crate = "::serde")]
+    struct Config<'a> {
+      name: &'a str,
+      #[serde(borrow)]
+      clean: Cow<'a, str>,
+      #[serde(borrow)]
+      escaped: Cow<'a, str>,
+      #[serde(borrow)]
+      map: std::collections::BTreeMap<&'a str, &'a str>,
+    }
+
+    let text = r#"{
+      "name": "test",
+      "clean": "value",
+      "escaped": "a\nb",
+      "map": { "key": "value" }
+    }"#;
+    let config: Config = parse_to_serde_value_borrowed(text, &Default::default()).unwrap();
+    assert_eq!(config.name, "test");
+    assert!(matches!(config.clean, Cow::Borrowed("value")));
+    assert!(matches!(config.escaped, Cow::Owned(ref value) if value == "a\nb"));
+    assert_eq!(config.map.into_iter().collect::<Vec<_>>(), vec![("key", "value")]);
+
+    let err = parse_to_serde_value_borrowed::<&str>(r#""a\nb""#, &Default::default()).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "invalid type: string \"a\\nb\", expected a borrowed string on line 1 column 1"
+    );
+  }
+}
@@ -0,0 +1,186 @@
+use std::io::Read;
+
+use ::serde::de::DeserializeOwned;
+
+use super::deserialize_root;
+use crate::ParseOptions;
+use crate::ReaderScanner;
+use crate::errors::ParseError;
+use crate::parser::JsoncParser;
+
+/// Parses JSONC read from a reader to a `serde_json::Value` or any
+/// type that implements `serde::de::DeserializeOwned`.
+///
+/// The text is read in chunks as it's deserialized, so the full text is
+/// never in memory at once. Wrapping the reader in a `BufReader` isn't
+/// necessary.
+///
+/// Empty or whitespace-only input deserializes as `null` the same as
+/// `parse_to_serde_value`.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::from_reader;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///   test: u32,
+/// }
+///
+/// let reader = r#"{ "test": 5 } // test"#.as_bytes();
+/// let config: Config = from_reader(reader, &Default::default()).unwrap();
+/// assert_eq!(config.test, 5);
+/// ```
+pub fn from_reader<R: Read, T: DeserializeOwned>(reader: R, parse_options: &ParseOptions) -> Result<T, ParseError> {
+  let scanner = ReaderScanner::new(reader, &parse_options.scanner_options());
+  deserialize_root(JsoncParser::with_source(scanner, parse_options))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use pretty_assertions::assert_eq;
+  use serde_json::Value as SerdeValue;
+
+  use super::*;
+  use crate::DuplicateKeyPolicy;
+  use crate::Spanned;
+  use crate::errors::ParseErrorKind;
+  use crate::parse_to_serde_value;
+
+  #[derive(Debug, PartialEq, ::serde::Deserialize)]
+  #[serde(crate = "::serde")]
+  struct Config {
+    name: String,
+    values: Vec<u8>,
+    pair: (bool, f64),
+    kind: Kind,
+    other: Option<HashMap<String, i64>>,
+  }
+
+  #[derive(Debug, PartialEq, ::serde::Deserialize)]
+  #[serde(crate = "::serde")]
+  enum Kind {
+    A,
+    B(u8),
+    C { hex: i32 },
+  }
+
+  /// Reader that returns one byte at a time.
+  struct ByteReader<'a>(&'a [u8]);
+
+  impl Read for ByteReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+      match (self.0.split_first(), buf.first_mut()) {
+        (Some((&b, rest)), Some(first)) => {
+          *first = b;
+          self.0 = rest;
+          Ok(1)
+        }
+        _ => Ok(0),
+      }
+    }
+  }
+
+  fn assert_same_as_str<T: DeserializeOwned + PartialEq + std::fmt::Debug>(text: &str, options: &ParseOptions) {
+    let expected = parse_to_serde_value::<T>(text, options).map_err(|err| err.to_string());
+    let actual = from_reader::<_, T>(ByteReader(text.as_bytes()), options).map_err(|err| err.to_string());
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn it_should_deserialize_from_reader() {
+    let text = r#"{
+      // comment
+      "name": "te\"st",
+      values: [1, 2,],
+      "pair": [true, 1.5, /* ignored */],
+      "kind": { "C": { "hex": 0x10 } },
+      "other": { "a": -1, "a": 2 },
+    }"#;
+    let config: Config = from_reader(ByteReader(text.as_bytes()), &Default::default()).unwrap();
+    assert_eq!(
+      config,
+      Config {
+        name: "te\"st".to_string(),
+        values: vec![1, 2],
+        pair: (true, 1.5),
+        kind: Kind::C { hex: 16 },
+        other: Some(HashMap::from([("a".to_string(), 2)])),
+      }
+    );
+    assert_same_as_str::<Config>(text, &Default::default());
+    assert_same_as_str::<Vec<Kind>>(r#"["A", { "B": 1 }, { "C": { "hex": 1 } }]"#, &Default::default());
+    assert_same_as_str::<SerdeValue>(r#"{ "a": [1, { "b": null }], "c": "d" }"#, &Default::default());
+    assert_same_as_str::<Kind>(r#"{ "C": { "hex": 1, /* a */ "hex": 2 } }"#, &Default::default());
+    assert_same_as_str::<(u8, u8)>("[1, 2, 3, 4]", &Default::default());
+    assert_same_as_str::<Option<u8>>("", &Default::default());
+    assert_same_as_str::<SerdeValue>("  ", &Default::default());
+  }
+
+  #[test]
+  fn it_should_error_same_as_str() {
+    assert_same_as_str::<Config>(r#"{ "name": 5 }"#, &Default::default());
+    assert_same_as_str::<Config>("{\n  \"name\": \"a\",\n  \"values\": [1, 256] }", &Default::default());
+    assert_same_as_str::<SerdeValue>("[1, 2", &Default::default());
+    assert_same_as_str::<SerdeValue>("{ \"a\": 1", &Default::default());
+    assert_same_as_str::<SerdeValue>("{ \"a\" 1 }", &Default::default());
+    assert_same_as_str::<SerdeValue>("[1, }", &Default::default());
+    assert_same_as_str::<SerdeValue>("[] []", &Default::default());
+    assert_same_as_str::<SerdeValue>("[1,]", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>("[007]", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>("{ \"a\": 1, }", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>("// test\n1", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>("{ a: 1 }", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>(
+      "{ \"a\": 1, \"a\": 2 }",
+      &ParseOptions {
+        duplicate_key_policy: DuplicateKeyPolicy::Error,
+        ..Default::default()
+      },
+    );
+    assert_same_as_str::<SerdeValue>(&"[".repeat(600), &Default::default());
+    assert_same_as_str::<SerdeValue>("[1\n  2]", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>("{ \"a\": \"b\"\n  \"c\": 1 }", &ParseOptions::strict_json());
+    assert_same_as_str::<SerdeValue>("[1,\n  ]", &ParseOptions::strict_json());
+  }
+
+  #[test]
+  fn it_should_deserialize_spans() {
+    #[derive(Debug, PartialEq, ::serde::Deserialize)]
+    #[serde(crate = "::serde")]
+    struct SpannedConfig {
+      name: Spanned<String>,
+      values: Spanned<Vec<Spanned<u8>>>,
+    }
+
+    let text = "{\n  \"name\": \"test\",\n  \"values\": [1, /* a */ 2]\n}";
+    let config: SpannedConfig = from_reader(ByteReader(text.as_bytes()), &Default::default()).unwrap();
+    let spans = [
+      &config.name.range(),
+      &config.values.range(),
+      &config.values.get_ref()[1].range(),
+    ]
+    .map(|range| &text[range.start..range.end]);
+    assert_eq!(spans, ["\"test\"", "[1, /* a */ 2]", "2"]);
+    assert_eq!((config.values.line_display(), config.values.column_display()), (3, 13));
+    assert_same_as_str::<SpannedConfig>(text, &Default::default());
+  }
+
+  #[test]
+  fn it_should_error_for_io_errors() {
+    struct ErrorReader;
+
+    impl Read for ErrorReader {
+      fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("failed"))
+      }
+    }
+
+    let err = from_reader::<_, SerdeValue>(ErrorReader, &Default::default()).unwrap_err();
+    assert!(matches!(err.kind(), ParseErrorKind::Io(_)));
+    assert_eq!(err.to_string(), "failed on line 1 column 1");
+  }
+}
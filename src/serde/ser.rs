@@ -0,0 +1,843 @@
+use std::io;
+
+use ::serde::Serialize;
+use ::serde::ser;
+use ::serde::ser::Impossible;
+
+use crate::common::CstNewlineKind;
+use crate::common::TrailingCommaMode;
+use crate::errors::SerializeError;
+
+/// Kind of quote to use for strings and object property names.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteKind {
+  /// Use double quotes (`"value"`).
+  #[default]
+  Double,
+  /// Use single quotes (`'value'`).
+  Single,
+}
+
+/// Options for serializing to JSONC text.
+#[derive(Debug, Clone)]
+pub struct SerializeOptions {
+  /// Text to use for a single level of indentation.
+  pub indent_text: String,
+  /// Kind of newline to emit.
+  pub newline_kind: CstNewlineKind,
+  /// Whether to add trailing commas to objects and arrays.
+  pub trailing_commas: TrailingCommaMode,
+  /// Kind of quote to use for strings and property names.
+  pub quote_kind: QuoteKind,
+}
+
+impl Default for SerializeOptions {
+  fn default() -> Self {
+    Self {
+      indent_text: "  ".to_string(),
+      newline_kind: CstNewlineKind::LineFeed,
+      trailing_commas: TrailingCommaMode::Never,
+      quote_kind: QuoteKind::Double,
+    }
+  }
+}
+
+/// Serializes a value implementing `serde::Serialize` to a JSONC string.
+///
+/// Non-empty objects and arrays are written on multiple lines using
+/// the indentation, newline kind, trailing comma mode, and quote kind
+/// from the provided options.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::to_jsonc_string;
+///
+/// #[derive(serde::Serialize)]
+/// struct Config {
+///   name: String,
+///   values: Vec<u32>,
+/// }
+///
+/// let text = to_jsonc_string(
+///   &Config { name: "test".to_string(), values: vec![1, 2] },
+///   &Default::default(),
+/// ).unwrap();
+/// assert_eq!(text, "{\n  \"name\": \"test\",\n  \"values\": [\n    1,\n    2\n  ]\n}");
+/// ```
+pub fn to_jsonc_string<T: Serialize + ?Sized>(value: &T, options: &SerializeOptions) -> Result<String, SerializeError> {
+  let mut bytes = Vec::with_capacity(128);
+  to_jsonc_writer(&mut bytes, value, options)?;
+  // the serializer only writes valid utf-8
+  Ok(String::from_utf8(bytes).expect("serializer wrote invalid utf-8"))
+}
+
+/// Serializes a value implementing `serde::Serialize` as JSONC text
+/// to the provided writer.
+///
+/// See `to_jsonc_string` for details.
+pub fn to_jsonc_writer<W: io::Write, T: Serialize + ?Sized>(
+  writer: W,
+  value: &T,
+  options: &SerializeOptions,
+) -> Result<(), SerializeError> {
+  let mut serializer = JsoncSerializer {
+    writer,
+    options,
+    depth: 0,
+  };
+  value.serialize(&mut serializer)
+}
+
+impl ser::Error for SerializeError {
+  fn custom<T: std::fmt::Display>(msg: T) -> Self {
+    SerializeError::Custom(msg.to_string())
+  }
+}
+
+struct JsoncSerializer<'o, W: io::Write> {
+  writer: W,
+  options: &'o SerializeOptions,
+  depth: usize,
+}
+
+impl<W: io::Write> JsoncSerializer<'_, W> {
+  fn write_str(&mut self, text: &str) -> Result<(), SerializeError> {
+    self.writer.write_all(text.as_bytes()).map_err(SerializeError::Io)
+  }
+
+  fn write_newline_and_indent(&mut self) -> Result<(), SerializeError> {
+    match self.options.newline_kind {
+      CstNewlineKind::LineFeed => self.write_str("\n")?,
+      CstNewlineKind::CarriageReturnLineFeed => self.write_str("\r\n")?,
+    }
+    for _ in 0..self.depth {
+      self
+        .writer
+        .write_all(self.options.indent_text.as_bytes())
+        .map_err(SerializeError::Io)?;
+    }
+    Ok(())
+  }
+
+  fn write_quoted(&mut self, value: &str) -> Result<(), SerializeError> {
+    let quote = match self.options.quote_kind {
+      QuoteKind::Double => '"',
+      QuoteKind::Single => '\'',
+    };
+    let mut text = String::with_capacity(value.len() + 2);
+    text.push(quote);
+    for c in value.chars() {
+      match c {
+        '\\' => text.push_str("\\\\"),
+        '\n' => text.push_str("\\n"),
+        '\r' => text.push_str("\\r"),
+        '\t' => text.push_str("\\t"),
+        '\u{08}' => text.push_str("\\b"),
+        '\u{0C}' => text.push_str("\\f"),
+        c if c == quote => {
+          text.push('\\');
+          text.push(c);
+        }
+        c if (c as u32) < 0x20 => text.push_str(&format!("\\u{:04x}", c as u32)),
+        c => text.push(c),
+      }
+    }
+    text.push(quote);
+    self.write_str(&text)
+  }
+
+  fn begin_container(&mut self, open: &str) -> Result<(), SerializeError> {
+    self.write_str(open)?;
+    self.depth += 1;
+    Ok(())
+  }
+
+  fn begin_item(&mut self, first: bool) -> Result<(), SerializeError> {
+    if !first {
+      self.write_str(",")?;
+    }
+    self.write_newline_and_indent()
+  }
+
+  fn end_container(&mut self, has_items: bool, close: &str) -> Result<(), SerializeError> {
+    self.depth -= 1;
+    if has_items {
+      if matches!(self.options.trailing_commas, TrailingCommaMode::IfMultiline) {
+        self.write_str(",")?;
+      }
+      self.write_newline_and_indent()?;
+    }
+    self.write_str(close)
+  }
+
+  /// Writes the start of an object with a single property used for
+  /// externally tagged enum variants (ex. `{ "Variant": ... }`).
+  fn begin_variant(&mut self, variant: &str) -> Result<(), SerializeError> {
+    self.begin_container("{")?;
+    self.begin_item(true)?;
+    self.write_quoted(variant)?;
+    self.write_str(": ")
+  }
+}
+
+impl<'a, 'o, W: io::Write> ser::Serializer for &'a mut JsoncSerializer<'o, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  type SerializeSeq = Compound<'a, 'o, W>;
+  type SerializeTuple = Compound<'a, 'o, W>;
+  type SerializeTupleStruct = Compound<'a, 'o, W>;
+  type SerializeTupleVariant = Compound<'a, 'o, W>;
+  type SerializeMap = Compound<'a, 'o, W>;
+  type SerializeStruct = Compound<'a, 'o, W>;
+  type SerializeStructVariant = Compound<'a, 'o, W>;
+
+  fn serialize_bool(self, v: bool) -> Result<(), SerializeError> {
+    self.write_str(if v { "true" } else { "false" })
+  }
+
+  fn serialize_i8(self, v: i8) -> Result<(), SerializeError> {
+    self.serialize_i64(v as i64)
+  }
+
+  fn serialize_i16(self, v: i16) -> Result<(), SerializeError> {
+    self.serialize_i64(v as i64)
+  }
+
+  fn serialize_i32(self, v: i32) -> Result<(), SerializeError> {
+    self.serialize_i64(v as i64)
+  }
+
+  fn serialize_i64(self, v: i64) -> Result<(), SerializeError> {
+    self.write_str(&v.to_string())
+  }
+
+  fn serialize_i128(self, v: i128) -> Result<(), SerializeError> {
+    self.write_str(&v.to_string())
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<(), SerializeError> {
+    self.serialize_u64(v as u64)
+  }
+
+  fn serialize_u16(self, v: u16) -> Result<(), SerializeError> {
+    self.serialize_u64(v as u64)
+  }
+
+  fn serialize_u32(self, v: u32) -> Result<(), SerializeError> {
+    self.serialize_u64(v as u64)
+  }
+
+  fn serialize_u64(self, v: u64) -> Result<(), SerializeError> {
+    self.write_str(&v.to_string())
+  }
+
+  fn serialize_u128(self, v: u128) -> Result<(), SerializeError> {
+    self.write_str(&v.to_string())
+  }
+
+  fn serialize_f32(self, v: f32) -> Result<(), SerializeError> {
+    if v.is_finite() {
+      // debug formatting outputs the shortest text that round trips (ex. `1.0`)
+      self.write_str(&format!("{:?}", v))
+    } else {
+      self.serialize_unit()
+    }
+  }
+
+  fn serialize_f64(self, v: f64) -> Result<(), SerializeError> {
+    if v.is_finite() {
+      self.write_str(&format!("{:?}", v))
+    } else {
+      self.serialize_unit()
+    }
+  }
+
+  fn serialize_char(self, v: char) -> Result<(), SerializeError> {
+    self.write_quoted(v.encode_utf8(&mut [0; 4]))
+  }
+
+  fn serialize_str(self, v: &str) -> Result<(), SerializeError> {
+    self.write_quoted(v)
+  }
+
+  fn serialize_bytes(self, v: &[u8]) -> Result<(), SerializeError> {
+    use ser::SerializeSeq;
+    let mut seq = self.serialize_seq(Some(v.len()))?;
+    for byte in v {
+      seq.serialize_element(byte)?;
+    }
+    seq.end()
+  }
+
+  fn serialize_none(self) -> Result<(), SerializeError> {
+    self.serialize_unit()
+  }
+
+  fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), SerializeError> {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<(), SerializeError> {
+    self.write_str("null")
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<(), SerializeError> {
+    self.serialize_unit()
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+  ) -> Result<(), SerializeError> {
+    self.write_quoted(variant)
+  }
+
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    value: &T,
+  ) -> Result<(), SerializeError> {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    value: &T,
+  ) -> Result<(), SerializeError> {
+    self.begin_variant(variant)?;
+    value.serialize(&mut *self)?;
+    self.end_container(true, "}")
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerializeError> {
+    self.begin_container("[")?;
+    Ok(Compound {
+      ser: self,
+      first: true,
+      is_variant: false,
+      is_number: false,
+    })
+  }
+
+  fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, SerializeError> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_struct(
+    self,
+    _name: &'static str,
+    len: usize,
+  ) -> Result<Self::SerializeTupleStruct, SerializeError> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeTupleVariant, SerializeError> {
+    self.begin_variant(variant)?;
+    self.begin_container("[")?;
+    Ok(Compound {
+      ser: self,
+      first: true,
+      is_variant: true,
+      is_number: false,
+    })
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerializeError> {
+    self.begin_container("{")?;
+    Ok(Compound {
+      ser: self,
+      first: true,
+      is_variant: false,
+      is_number: false,
+    })
+  }
+
+  #[cfg_attr(not(feature = "arbitrary_precision"), allow(unused_variables))]
+  fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, SerializeError> {
+    #[cfg(feature = "arbitrary_precision")]
+    if name == super::ARBITRARY_PRECISION_NUMBER_TOKEN {
+      return Ok(Compound {
+        ser: self,
+        first: true,
+        is_variant: false,
+        is_number: true,
+      });
+    }
+    self.serialize_map(Some(len))
+  }
+
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeStructVariant, SerializeError> {
+    self.begin_variant(variant)?;
+    self.begin_container("{")?;
+    Ok(Compound {
+      ser: self,
+      first: true,
+      is_variant: true,
+      is_number: false,
+    })
+  }
+}
+
+struct Compound<'a, 'o, W: io::Write> {
+  ser: &'a mut JsoncSerializer<'o, W>,
+  first: bool,
+  /// Whether this is the inner value of an externally tagged enum variant,
+  /// which requires also closing the surrounding object.
+  is_variant: bool,
+  /// Whether this is serde_json's `arbitrary_precision` number struct,
+  /// whose field is written as the number text.
+  is_number: bool,
+}
+
+impl<W: io::Write> Compound<'_, '_, W> {
+  fn write_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+    self.ser.begin_item(self.first)?;
+    self.first = false;
+    value.serialize(&mut *self.ser)
+  }
+
+  fn write_field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), SerializeError> {
+    self.ser.begin_item(self.first)?;
+    self.first = false;
+    self.ser.write_quoted(key)?;
+    self.ser.write_str(": ")?;
+    value.serialize(&mut *self.ser)
+  }
+
+  fn finish(self, close: &str) -> Result<(), SerializeError> {
+    self.ser.end_container(!self.first, close)?;
+    if self.is_variant {
+      self.ser.end_container(true, "}")?;
+    }
+    Ok(())
+  }
+}
+
+impl<W: io::Write> ser::SerializeSeq for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+    self.write_element(value)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    self.finish("]")
+  }
+}
+
+impl<W: io::Write> ser::SerializeTuple for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+    self.write_element(value)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    self.finish("]")
+  }
+}
+
+impl<W: io::Write> ser::SerializeTupleStruct for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+    self.write_element(value)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    self.finish("]")
+  }
+}
+
+impl<W: io::Write> ser::SerializeTupleVariant for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+    self.write_element(value)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    self.finish("]")
+  }
+}
+
+impl<W: io::Write> ser::SerializeMap for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerializeError> {
+    let key = key.serialize(MapKeySerializer)?;
+    self.ser.begin_item(self.first)?;
+    self.first = false;
+    self.ser.write_quoted(&key)?;
+    self.ser.write_str(": ")
+  }
+
+  fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError> {
+    value.serialize(&mut *self.ser)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    self.finish("}")
+  }
+}
+
+impl<W: io::Write> ser::SerializeStruct for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError> {
+    if self.is_number {
+      let text = value.serialize(MapKeySerializer)?;
+      return self.ser.write_str(&text);
+    }
+    self.write_field(key, value)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    if self.is_number {
+      return Ok(());
+    }
+    self.finish("}")
+  }
+}
+
+impl<W: io::Write> ser::SerializeStructVariant for Compound<'_, '_, W> {
+  type Ok = ();
+  type Error = SerializeError;
+
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), SerializeError> {
+    self.write_field(key, value)
+  }
+
+  fn end(self) -> Result<(), SerializeError> {
+    self.finish("}")
+  }
+}
+
+/// Serializes object keys to a string. Only strings, chars, integers,
+/// and unit variants are supported as keys.
+struct MapKeySerializer;
+
+impl ser::Serializer for MapKeySerializer {
+  type Ok = String;
+  type Error = SerializeError;
+
+  type SerializeSeq = Impossible<String, SerializeError>;
+  type SerializeTuple = Impossible<String, SerializeError>;
+  type SerializeTupleStruct = Impossible<String, SerializeError>;
+  type SerializeTupleVariant = Impossible<String, SerializeError>;
+  type SerializeMap = Impossible<String, SerializeError>;
+  type SerializeStruct = Impossible<String, SerializeError>;
+  type SerializeStructVariant = Impossible<String, SerializeError>;
+
+  fn serialize_str(self, v: &str) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_char(self, v: char) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i8(self, v: i8) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i16(self, v: i16) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i32(self, v: i32) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i64(self, v: i64) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_i128(self, v: i128) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u16(self, v: u16) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u32(self, v: u32) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u64(self, v: u64) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_u128(self, v: u128) -> Result<String, SerializeError> {
+    Ok(v.to_string())
+  }
+
+  fn serialize_unit_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+  ) -> Result<String, SerializeError> {
+    Ok(variant.to_string())
+  }
+
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    value: &T,
+  ) -> Result<String, SerializeError> {
+    value.serialize(self)
+  }
+
+  fn serialize_bool(self, _v: bool) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_f32(self, _v: f32) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_f64(self, _v: f64) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_bytes(self, _v: &[u8]) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_none(self) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_unit(self) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    _variant: &'static str,
+    _value: &T,
+  ) -> Result<String, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_tuple_struct(
+    self,
+    _name: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeTupleStruct, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    _variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeTupleVariant, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    _variant: &'static str,
+    _len: usize,
+  ) -> Result<Self::SerializeStructVariant, SerializeError> {
+    Err(SerializeError::KeyMustBeAString)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::parse_to_serde_value;
+
+  #[derive(::serde::Serialize, ::serde::Deserialize, Debug, PartialEq)]
+  #[serde(crate = "::serde")]
+  struct Config {
+    name: String,
+    version: (u8, u8),
+    enabled: bool,
+    ratio: f64,
+    tags: Vec<String>,
+    empty: Vec<u32>,
+    shape: Shape,
+    missing: Option<u32>,
+  }
+
+  #[derive(::serde::Serialize, ::serde::Deserialize, Debug, PartialEq)]
+  #[serde(crate = "::serde")]
+  enum Shape {
+    Circle(f64),
+    Rectangle { width: u32, height: u32 },
+  }
+
+  fn test_config() -> Config {
+    Config {
+      name: "test".to_string(),
+      version: (1, 2),
+      enabled: true,
+      ratio: 1.0,
+      tags: vec!["a".to_string()],
+      empty: Vec::new(),
+      shape: Shape::Rectangle { width: 2, height: 3 },
+      missing: None,
+    }
+  }
+
+  #[test]
+  fn serializes_with_default_options() {
+    let text = to_jsonc_string(&test_config(), &Default::default()).unwrap();
+    assert_eq!(
+      text,
+      r#"{
+  "name": "test",
+  "version": [
+    1,
+    2
+  ],
+  "enabled": true,
+  "ratio": 1.0,
+  "tags": [
+    "a"
+  ],
+  "empty": [],
+  "shape": {
+    "Rectangle": {
+      "width": 2,
+      "height": 3
+    }
+  },
+  "missing": null
+}"#
+    );
+  }
+
+  #[test]
+  fn serializes_with_custom_options() {
+    let options = SerializeOptions {
+      indent_text: "\t".to_string(),
+      newline_kind: CstNewlineKind::CarriageReturnLineFeed,
+      trailing_commas: TrailingCommaMode::IfMultiline,
+      quote_kind: QuoteKind::Single,
+    };
+    let text = to_jsonc_string(&vec![Shape::Circle(1.5)], &options).unwrap();
+    assert_eq!(text, "[\r\n\t{\r\n\t\t'Circle': 1.5,\r\n\t},\r\n]");
+  }
+
+  #[test]
+  fn escapes_strings() {
+    let value = vec!["a\"b'c\\d\n\u{1}é"];
+    let text = to_jsonc_string(&value, &Default::default()).unwrap();
+    assert_eq!(text, "[\n  \"a\\\"b'c\\\\d\\n\\u0001é\"\n]");
+
+    let options = SerializeOptions {
+      quote_kind: QuoteKind::Single,
+      ..Default::default()
+    };
+    let text = to_jsonc_string(&value, &options).unwrap();
+    assert_eq!(text, "[\n  'a\"b\\'c\\\\d\\n\\u0001é'\n]");
+  }
+
+  #[test]
+  fn serializes_map_keys() {
+    let mut map = BTreeMap::new();
+    map.insert(1, "one");
+    map.insert(2, "two");
+    let text = to_jsonc_string(&map, &Default::default()).unwrap();
+    assert_eq!(text, "{\n  \"1\": \"one\",\n  \"2\": \"two\"\n}");
+
+    let mut map = BTreeMap::new();
+    map.insert(vec![1], 1);
+    let err = to_jsonc_string(&map, &Default::default()).unwrap_err();
+    assert_eq!(err.to_string(), "Object keys must be strings");
+  }
+
+  #[test]
+  fn serializes_non_finite_floats_as_null() {
+    let text = to_jsonc_string(&[f64::NAN, f64::INFINITY], &Default::default()).unwrap();
+    assert_eq!(text, "[\n  null,\n  null\n]");
+  }
+
+  #[test]
+  fn round_trips_through_parser() {
+    let options = SerializeOptions {
+      trailing_commas: TrailingCommaMode::IfMultiline,
+      quote_kind: QuoteKind::Single,
+      ..Default::default()
+    };
+    let text = to_jsonc_string(&test_config(), &options).unwrap();
+    let config: Config = parse_to_serde_value(&text, &Default::default()).unwrap();
+    assert_eq!(config, test_config());
+  }
+
+  #[test]
+  fn writes_to_writer() {
+    let mut bytes = Vec::new();
+    to_jsonc_writer(&mut bytes, &Shape::Circle(2.0), &Default::default()).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), "{\n  \"Circle\": 2.0\n}");
+  }
+}
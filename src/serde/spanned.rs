@@ -0,0 +1,279 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use ::serde::Deserialize;
+use ::serde::Deserializer;
+use ::serde::Serialize;
+use ::serde::Serializer;
+use ::serde::de;
+use ::serde::de::DeserializeSeed;
+use ::serde::de::IntoDeserializer;
+use ::serde::de::MapAccess;
+use ::serde::de::Visitor;
+use ::serde::de::value::BorrowedStrDeserializer;
+
+use crate::common::Range;
+use crate::errors::ParseError;
+
+pub(crate) const SPANNED_NAME: &str = "$__jsonc_parser_private_Spanned";
+const VALUE_FIELD: &str = "$__jsonc_parser_private_value";
+const START_FIELD: &str = "$__jsonc_parser_private_start";
+const END_FIELD: &str = "$__jsonc_parser_private_end";
+const LINE_FIELD: &str = "$__jsonc_parser_private_line";
+const COLUMN_FIELD: &str = "$__jsonc_parser_private_column";
+const SPANNED_FIELDS: &[&str] = &[VALUE_FIELD, START_FIELD, END_FIELD, LINE_FIELD, COLUMN_FIELD];
+
+/// A deserialized value along with its position in the text.
+///
+/// This is only supported when deserializing with `parse_to_serde_value`,
+/// `from_reader`, or `from_ast_value`. Other deserializers will error.
+///
+/// Comparing, hashing, and serializing only uses the value.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::Spanned;
+/// use jsonc_parser::parse_to_serde_value;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///   port: Spanned<u32>,
+/// }
+///
+/// let text = r#"{
+///   "port": 70000
+/// }"#;
+/// let config: Config = parse_to_serde_value(text, &Default::default()).unwrap();
+/// assert_eq!(*config.port.get_ref(), 70000);
+/// assert_eq!(config.port.range().start, 12);
+/// assert_eq!(config.port.line_display(), 2);
+/// assert_eq!(config.port.column_display(), 11);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Spanned<T> {
+  range: Range,
+  line_display: usize,
+  column_display: usize,
+  value: T,
+}
+
+impl<T> Spanned<T> {
+  /// Start and end position of the value.
+  pub fn range(&self) -> Range {
+    self.range
+  }
+
+  /// 1-indexed line number the value starts on.
+  pub fn line_display(&self) -> usize {
+    self.line_display
+  }
+
+  /// 1-indexed column number the value starts on.
+  pub fn column_display(&self) -> usize {
+    self.column_display
+  }
+
+  /// Gets a reference to the value.
+  pub fn get_ref(&self) -> &T {
+    &self.value
+  }
+
+  /// Gets a mutable reference to the value.
+  pub fn get_mut(&mut self) -> &mut T {
+    &mut self.value
+  }
+
+  /// Drops the position returning the value.
+  pub fn into_inner(self) -> T {
+    self.value
+  }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+  }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: std::hash::Hash> std::hash::Hash for Spanned<T> {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.value.hash(state);
+  }
+}
+
+impl<T: Serialize> Serialize for Spanned<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.value.serialize(serializer)
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Spanned<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct SpannedVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for SpannedVisitor<T> {
+      type Value = Spanned<T>;
+
+      fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a spanned value")
+      }
+
+      fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Spanned<T>, A::Error> {
+        let mut value = None;
+        let mut start = None;
+        let mut end = None;
+        let mut line_display = None;
+        let mut column_display = None;
+        while let Some(key) = map.next_key::<&str>()? {
+          match key {
+            VALUE_FIELD => value = Some(map.next_value()?),
+            START_FIELD => start = Some(map.next_value()?),
+            END_FIELD => end = Some(map.next_value()?),
+            LINE_FIELD => line_display = Some(map.next_value()?),
+            COLUMN_FIELD => column_display = Some(map.next_value()?),
+            _ => return Err(de::Error::custom("Spanned is not supported by this deserializer")),
+          }
+        }
+        match (value, start, end, line_display, column_display) {
+          (Some(value), Some(start), Some(end), Some(line_display), Some(column_display)) => Ok(Spanned {
+            range: Range::new(start, end),
+            line_display,
+            column_display,
+            value,
+          }),
+          _ => Err(de::Error::custom("Spanned is not supported by this deserializer")),
+        }
+      }
+    }
+
+    deserializer.deserialize_struct(SPANNED_NAME, SPANNED_FIELDS, SpannedVisitor(PhantomData))
+  }
+}
+
+/// A value that's deserialized for a `Spanned<T>`.
+pub(crate) trait SpannedValue<'de> {
+  fn deserialize_value<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, ParseError>;
+
+  /// Gets the range of the value, which is called after the value is deserialized.
+  fn range(&self) -> Range;
+
+  /// Gets the 1-indexed line and column of the start of the value.
+  fn line_and_column_display(&self) -> (usize, usize);
+}
+
+/// Provides the value followed by its position to the `Spanned<T>` visitor.
+pub(crate) struct SpannedMapAccess<S> {
+  value: S,
+  field_index: usize,
+}
+
+impl<S> SpannedMapAccess<S> {
+  pub fn new(value: S) -> Self {
+    SpannedMapAccess { value, field_index: 0 }
+  }
+}
+
+impl<'de, S: SpannedValue<'de>> MapAccess<'de> for SpannedMapAccess<S> {
+  type Error = ParseError;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+    match SPANNED_FIELDS.get(self.field_index) {
+      Some(field) => seed.deserialize(BorrowedStrDeserializer::new(field)).map(Some),
+      None => Ok(None),
+    }
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    let field = SPANNED_FIELDS[self.field_index];
+    self.field_index += 1;
+    if field == VALUE_FIELD {
+      return self.value.deserialize_value(seed);
+    }
+    let range = self.value.range();
+    let value = match field {
+      START_FIELD => range.start,
+      END_FIELD => range.end,
+      LINE_FIELD => self.value.line_and_column_display().0,
+      _ => self.value.line_and_column_display().1,
+    };
+    seed.deserialize(IntoDeserializer::<ParseError>::into_deserializer(value))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::ParseOptions;
+  use crate::from_ast_value;
+  use crate::parse_to_ast;
+  use crate::parse_to_serde_value;
+
+  #[derive(Debug, serde::Deserialize)]
+  #[serde(crate = "::serde")]
+  struct Config {
+    name: Spanned<String>,
+    values: Spanned<Vec<Spanned<u8>>>,
+    other: Option<Spanned<bool>>,
+  }
+
+  const TEXT: &str = r#"{
+  "name": "test",
+  "values": [1, /* a */ 2],
+  "other": null
+}"#;
+
+  fn assert_span<T>(spanned: &Spanned<T>, text: &str, expected_text: &str, line_display: usize, column_display: usize) {
+    assert_eq!(&text[spanned.range().start..spanned.range().end], expected_text);
+    assert_eq!(
+      (spanned.line_display(), spanned.column_display()),
+      (line_display, column_display)
+    );
+  }
+
+  fn assert_config(config: Config) {
+    assert_eq!(config.name.get_ref(), "test");
+    assert_span(&config.name, TEXT, "\"test\"", 2, 11);
+    assert_span(&config.values, TEXT, "[1, /* a */ 2]", 3, 13);
+    assert_eq!(config.values.get_ref().len(), 2);
+    assert_span(&config.values.get_ref()[0], TEXT, "1", 3, 14);
+    assert_span(&config.values.get_ref()[1], TEXT, "2", 3, 25);
+    assert!(config.other.is_none());
+  }
+
+  #[test]
+  fn it_should_deserialize_spans() {
+    assert_config(parse_to_serde_value(TEXT, &Default::default()).unwrap());
+
+    let value: Spanned<serde_json::Value> = parse_to_serde_value("  { \"a\": [] } ", &Default::default()).unwrap();
+    assert_eq!(value.range(), Range::new(2, 13));
+    assert_eq!(value.into_inner(), serde_json::json!({ "a": [] }));
+  }
+
+  #[test]
+  fn it_should_deserialize_spans_from_ast() {
+    let ast = parse_to_ast(TEXT, &Default::default(), &ParseOptions::default()).unwrap();
+    assert_config(from_ast_value(ast.value.as_ref().unwrap(), TEXT).unwrap());
+  }
+
+  #[test]
+  fn it_should_compare_and_serialize_value_only() {
+    let a: Spanned<u8> = parse_to_serde_value("1", &Default::default()).unwrap();
+    let b: Spanned<u8> = parse_to_serde_value("  1", &Default::default()).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(serde_json::to_string(&a).unwrap(), "1");
+  }
+
+  #[test]
+  fn it_should_error_for_unsupported_deserializers() {
+    let err = serde_json::from_str::<Spanned<u8>>("1").unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "invalid type: integer `1`, expected a spanned value at line 1 column 1"
+    );
+  }
+}
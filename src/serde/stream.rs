@@ -0,0 +1,104 @@
+use std::marker::PhantomData;
+
+use ::serde::Deserialize;
+
+use crate::ParseOptions;
+use crate::RootValue;
+use crate::ValuesSeparator;
+use crate::errors::ParseError;
+use crate::parse_values_iter::RootValuesParser;
+
+/// Parses a string containing multiple JSONC values to an iterator of
+/// a type that implements `serde::Deserialize`.
+///
+/// This is the serde equivalent of `parse_values_iter` and continues after
+/// errors the same way.
+///
+/// # Example
+///
+/// ```
+/// use jsonc_parser::ValuesSeparator;
+/// use jsonc_parser::parse_to_serde_values_iter;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Entry {
+///   level: String,
+/// }
+///
+/// let text = r#"{ "level": "info" }
+/// { "level": 5 }
+/// // comment
+/// { "level": "warn" }"#;
+/// let levels = parse_to_serde_values_iter::<Entry>(text, &Default::default(), ValuesSeparator::Newline)
+///   .filter_map(|result| result.ok())
+///   .map(|entry| entry.value.level)
+///   .collect::<Vec<_>>();
+/// assert_eq!(levels, ["info", "warn"]);
+/// ```
+pub fn parse_to_serde_values_iter<'a, T: Deserialize<'a>>(
+  text: &'a str,
+  options: &ParseOptions,
+  separator: ValuesSeparator,
+) -> StreamDeserializer<'a, T> {
+  StreamDeserializer {
+    values: RootValuesParser::new(text, options, separator),
+    _marker: PhantomData,
+  }
+}
+
+/// Iterator that deserializes the values in a string. See `parse_to_serde_values_iter`.
+pub struct StreamDeserializer<'a, T> {
+  values: RootValuesParser<'a>,
+  _marker: PhantomData<T>,
+}
+
+impl<'a, T: Deserialize<'a>> Iterator for StreamDeserializer<'a, T> {
+  type Item = Result<RootValue<T>, ParseError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.values.next(|parser, _| {
+      parser.put_back();
+      T::deserialize(parser)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::common::Range;
+
+  #[test]
+  fn it_should_deserialize_each_value() {
+    let text = "1\n\"two\"\n[3]\n4 5";
+    let results = parse_to_serde_values_iter::<u8>(text, &Default::default(), ValuesSeparator::Newline)
+      .map(|result| result.map_err(|err| err.to_string()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      results,
+      vec![
+        Ok(RootValue {
+          range: Range::new(0, 1),
+          value: 1
+        }),
+        Err("invalid type: string \"two\", expected u8 on line 2 column 1".to_string()),
+        Err("invalid type: sequence, expected u8 on line 3 column 1".to_string()),
+        Ok(RootValue {
+          range: Range::new(12, 13),
+          value: 4
+        }),
+        Err("Expected newline between values on line 4 column 3".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn it_should_borrow_from_text() {
+    let values = parse_to_serde_values_iter::<&str>(r#""a" "b""#, &Default::default(), ValuesSeparator::Whitespace)
+      .map(|result| result.unwrap().value)
+      .collect::<Vec<_>>();
+    assert_eq!(values, ["a", "b"]);
+  }
+}
@@ -0,0 +1,463 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use ::serde::Deserializer;
+use ::serde::Serialize;
+use ::serde::Serializer;
+use ::serde::de;
+use ::serde::de::IntoDeserializer;
+use ::serde::de::Unexpected;
+use ::serde::de::Visitor;
+use ::serde::de::value::MapAccessDeserializer;
+use ::serde::de::value::MapDeserializer;
+use ::serde::de::value::SeqDeserializer;
+use ::serde::forward_to_deserialize_any;
+use ::serde::ser::Error as _;
+use ::serde::ser::SerializeMap;
+use ::serde::ser::SerializeSeq;
+
+use super::NumberHint;
+use super::deserialize_numbers;
+use super::visit_number;
+use crate::JsonArray;
+use crate::JsonObject;
+use crate::JsonValue;
+use crate::map::Map;
+
+impl Serialize for JsonValue<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      JsonValue::String(value) => serializer.serialize_str(value),
+      JsonValue::Number(value) => serialize_number(value, serializer),
+      JsonValue::Boolean(value) => serializer.serialize_bool(*value),
+      JsonValue::Object(value) => value.serialize(serializer),
+      JsonValue::Array(value) => value.serialize(serializer),
+      JsonValue::Null => serializer.serialize_unit(),
+    }
+  }
+}
+
+impl Serialize for JsonObject<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(self.len()))?;
+    for (key, value) in self.iter() {
+      map.serialize_entry(key, value)?;
+    }
+    map.end()
+  }
+}
+
+impl Serialize for JsonArray<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(self.len()))?;
+    for element in self.iter() {
+      seq.serialize_element(element)?;
+    }
+    seq.end()
+  }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for Map<K, V> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(self.len()))?;
+    for (key, value) in self.iter() {
+      map.serialize_entry(key, value)?;
+    }
+    map.end()
+  }
+}
+
+/// Serializes a number literal, erroring for `Infinity` and `NaN` rather
+/// than writing them as `null`.
+///
+/// With the `arbitrary_precision` feature the text is kept as is, other than
+/// changes needed to make it a valid JSON number such as converting hexadecimal.
+fn serialize_number<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+  if matches!(text.trim_start_matches(['-', '+']), "Infinity" | "NaN") {
+    return Err(S::Error::custom(format!(
+      "Cannot serialize non-finite number '{}'",
+      text
+    )));
+  }
+
+  #[cfg(feature = "arbitrary_precision")]
+  return serialize_number_text(text, serializer);
+  #[cfg(not(feature = "arbitrary_precision"))]
+  serialize_number_primitive(text, serializer)
+}
+
+/// Serializes the number as serde_json's `arbitrary_precision` number type.
+#[cfg(feature = "arbitrary_precision")]
+fn serialize_number_text<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+  use ::serde::ser::SerializeStruct;
+
+  use super::ARBITRARY_PRECISION_NUMBER_TOKEN;
+
+  let json_text =
+    crate::number::to_json_text(text).map_err(|_| S::Error::custom(format!("Invalid number '{}'", text)))?;
+  let mut number = serializer.serialize_struct(ARBITRARY_PRECISION_NUMBER_TOKEN, 1)?;
+  number.serialize_field(ARBITRARY_PRECISION_NUMBER_TOKEN, &json_text)?;
+  number.end()
+}
+
+/// Serializes a number literal as the type that represents it exactly,
+/// which is an integer when the text is an integer (including hexadecimal)
+/// that fits in 128 bits and otherwise a float.
+///
+/// For example, `10` is serialized as `10` and not `10.0`.
+#[cfg(not(feature = "arbitrary_precision"))]
+fn serialize_number_primitive<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+  let (is_negative, unsigned_text) = match text.as_bytes().first() {
+    Some(b'-') => (true, &text[1..]),
+    Some(b'+') => (false, &text[1..]),
+    _ => (false, text),
+  };
+  let hex_digits = unsigned_text
+    .strip_prefix("0x")
+    .or_else(|| unsigned_text.strip_prefix("0X"));
+  let integer = match hex_digits {
+    Some(hex_digits) => u128::from_str_radix(hex_digits, 16).ok(),
+    None if !unsigned_text.is_empty() && unsigned_text.bytes().all(|b| b.is_ascii_digit()) => {
+      unsigned_text.parse::<u128>().ok()
+    }
+    None => None,
+  };
+  match integer {
+    Some(value) if !is_negative => match u64::try_from(value) {
+      Ok(value) => serializer.serialize_u64(value),
+      Err(_) => serializer.serialize_u128(value),
+    },
+    // negative zero is only represented by a float
+    Some(0) => serializer.serialize_f64(-0.0),
+    Some(value) => match i128::try_from(value).map(|value| -value) {
+      Ok(value) => match i64::try_from(value) {
+        Ok(value) => serializer.serialize_i64(value),
+        Err(_) => serializer.serialize_i128(value),
+      },
+      Err(_) => serializer.serialize_f64(-(value as f64)),
+    },
+    None => {
+      let invalid_number = || S::Error::custom(format!("Invalid number '{}'", text));
+      let value = match hex_digits {
+        // larger than 128 bits
+        Some(hex_digits) => hex_digits.chars().try_fold(0f64, |value, c| {
+          c.to_digit(16)
+            .map(|digit| value * 16.0 + digit as f64)
+            .ok_or_else(invalid_number)
+        })?,
+        None => unsigned_text.parse::<f64>().map_err(|_| invalid_number())?,
+      };
+      if !value.is_finite() {
+        return Err(S::Error::custom(format!("Number '{}' is out of range", text)));
+      }
+      serializer.serialize_f64(if is_negative { -value } else { value })
+    }
+  }
+}
+
+impl<'de> Deserializer<'de> for JsonValue<'de> {
+  type Error = de::value::Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    JsonValueDeserializer::new(self).deserialize_any(visitor)
+  }
+
+  deserialize_numbers!(|self, hint, visitor| JsonValueDeserializer::new(self).deserialize_with_hint(hint, visitor));
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    JsonValueDeserializer::new(self).deserialize_option(visitor)
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    JsonValueDeserializer::new(self).deserialize_enum(name, variants, visitor)
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  forward_to_deserialize_any! {
+    bool char str string bytes byte_buf unit unit_struct
+    seq tuple tuple_struct map struct identifier ignored_any
+  }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for JsonValue<'de> {
+  type Deserializer = JsonValueDeserializer<'de, E>;
+
+  fn into_deserializer(self) -> Self::Deserializer {
+    JsonValueDeserializer::new(self)
+  }
+}
+
+/// Deserializer for a `JsonValue` that's generic over the error type.
+///
+/// This is created by `IntoDeserializer::into_deserializer`. The
+/// `JsonValue` may also be used as a deserializer directly.
+pub struct JsonValueDeserializer<'de, E> {
+  value: JsonValue<'de>,
+  marker: PhantomData<E>,
+}
+
+impl<'de, E> JsonValueDeserializer<'de, E> {
+  pub fn new(value: JsonValue<'de>) -> Self {
+    JsonValueDeserializer {
+      value,
+      marker: PhantomData,
+    }
+  }
+}
+
+impl<'de, E: de::Error> JsonValueDeserializer<'de, E> {
+  fn deserialize_with_hint<V: Visitor<'de>>(self, hint: NumberHint, visitor: V) -> Result<V::Value, E> {
+    match self.value {
+      JsonValue::String(Cow::Borrowed(value)) => visitor.visit_borrowed_str(value),
+      JsonValue::String(Cow::Owned(value)) => visitor.visit_string(value),
+      JsonValue::Number(value) => visit_number(value, hint, visitor),
+      JsonValue::Boolean(value) => visitor.visit_bool(value),
+      JsonValue::Null => visitor.visit_unit(),
+      JsonValue::Array(value) => {
+        let mut seq = SeqDeserializer::new(value.into_iter());
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+      }
+      JsonValue::Object(value) => {
+        let mut map = MapDeserializer::new(value.into_iter());
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+      }
+    }
+  }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for JsonValueDeserializer<'de, E> {
+  type Error = E;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_with_hint(NumberHint::Any, visitor)
+  }
+
+  deserialize_numbers!(|self, hint, visitor| self.deserialize_with_hint(hint, visitor));
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      JsonValue::Null => visitor.visit_none(),
+      _ => visitor.visit_some(self),
+    }
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    match self.value {
+      JsonValue::String(variant) => visitor.visit_enum(IntoDeserializer::<E>::into_deserializer(variant)),
+      // { "Variant": data }
+      JsonValue::Object(value) if value.len() == 1 => {
+        visitor.visit_enum(MapAccessDeserializer::new(MapDeserializer::new(value.into_iter())))
+      }
+      other => Err(E::invalid_type(
+        unexpected(&other),
+        &"a string or an object with a single property",
+      )),
+    }
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  forward_to_deserialize_any! {
+    bool char str string bytes byte_buf unit unit_struct
+    seq tuple tuple_struct map struct identifier ignored_any
+  }
+}
+
+fn unexpected<'a>(value: &'a JsonValue) -> Unexpected<'a> {
+  match value {
+    JsonValue::String(value) => Unexpected::Str(value),
+    JsonValue::Number(value) => Unexpected::Other(value),
+    JsonValue::Boolean(value) => Unexpected::Bool(*value),
+    JsonValue::Object(_) => Unexpected::Map,
+    JsonValue::Array(_) => Unexpected::Seq,
+    JsonValue::Null => Unexpected::Unit,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ParseOptions;
+  use crate::errors::ParseError;
+  use crate::parse_to_value;
+  use crate::to_jsonc_string;
+  use pretty_assertions::assert_eq;
+
+  fn to_json(text: &str) -> String {
+    let value = parse_to_value(text, &ParseOptions::json5()).unwrap().unwrap();
+    serde_json::to_string(&value).unwrap()
+  }
+
+  fn to_json_err(text: &str) -> String {
+    let value = parse_to_value(text, &ParseOptions::json5()).unwrap().unwrap();
+    serde_json::to_string(&value).unwrap_err().to_string()
+  }
+
+  #[test]
+  fn it_should_serialize_values() {
+    assert_eq!(
+      to_json(r#"[null, true, "te\"xt", [], {}, { "a": [1] }] // comment"#),
+      r#"[null,true,"te\"xt",[],{},{"a":[1]}]"#
+    );
+  }
+
+  #[cfg(not(feature = "arbitrary_precision"))]
+  #[test]
+  fn it_should_serialize_numbers() {
+    assert_eq!(
+      to_json("[0, -0, 10, -10, 1.0, 1.5, -1.5e3, 0x1F, -0x10, +5, .5, 5.]"),
+      "[0,-0.0,10,-10,1.0,1.5,-1500.0,31,-16,5,0.5,5.0]"
+    );
+    assert_eq!(
+      to_json("[18446744073709551615, 18446744073709551616, -9223372036854775809]"),
+      "[18446744073709551615,18446744073709551616,-9223372036854775809]"
+    );
+    assert_eq!(
+      to_json("[340282366920938463463374607431768211456]"),
+      "[3.402823669209385e+38]"
+    );
+    assert_eq!(to_json_err("[1e400]"), "Number '1e400' is out of range");
+  }
+
+  #[cfg(feature = "arbitrary_precision")]
+  #[test]
+  fn it_should_serialize_numbers_exactly() {
+    let text = "[0, -0, 1.10, -1.5e3, 1e400, 0x1F, -0x10, +5, .5, 5., 340282366920938463463374607431768211456]";
+    let expected = "[0,-0,1.10,-1.5e3,1e400,31,-16,5,0.5,5.0,340282366920938463463374607431768211456]";
+    assert_eq!(to_json(text), expected);
+    let value = parse_to_value(text, &ParseOptions::json5()).unwrap().unwrap();
+    assert_eq!(
+      to_jsonc_string(&value, &Default::default())
+        .unwrap()
+        .replace(['\n', ' '], ""),
+      expected
+    );
+  }
+
+  #[test]
+  fn it_should_error_serializing_non_finite_numbers() {
+    assert_eq!(
+      to_json_err("[Infinity]"),
+      "Cannot serialize non-finite number 'Infinity'"
+    );
+    assert_eq!(to_json_err("[-NaN]"), "Cannot serialize non-finite number '-NaN'");
+  }
+
+  #[test]
+  fn it_should_serialize_to_jsonc() {
+    let value = parse_to_value(r#"{ "a": [1, 1.25] }"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    assert_eq!(
+      to_jsonc_string(&value, &Default::default()).unwrap(),
+      "{\n  \"a\": [\n    1,\n    1.25\n  ]\n}"
+    );
+  }
+
+  #[derive(Debug, PartialEq, serde::Deserialize)]
+  #[serde(crate = "::serde")]
+  struct Config {
+    name: String,
+    values: Vec<u8>,
+    kind: Kind,
+    other: Option<Kind>,
+  }
+
+  #[derive(Debug, PartialEq, serde::Deserialize)]
+  #[serde(crate = "::serde")]
+  enum Kind {
+    A,
+    B(f64),
+    C { hex: i32 },
+  }
+
+  #[test]
+  fn it_should_deserialize_from_value() {
+    let text = r#"{
+      "$schema": "https://example.com/schema.json",
+      "name": "test",
+      "values": [1, 2],
+      "kind": { "C": { "hex": -0x10 } },
+      "other": null
+    }"#;
+    let mut value = parse_to_value(text, &Default::default()).unwrap().unwrap();
+    let JsonValue::Object(obj) = &mut value else {
+      unreachable!();
+    };
+    assert_eq!(obj.take_string("$schema").unwrap(), "https://example.com/schema.json");
+    let config: Config = ::serde::Deserialize::deserialize(value).unwrap();
+    assert_eq!(
+      config,
+      Config {
+        name: "test".to_string(),
+        values: vec![1, 2],
+        kind: Kind::C { hex: -16 },
+        other: None,
+      }
+    );
+
+    let value = parse_to_value(r#"[{ "B": 1.5 }, "A"]"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    let kinds: Result<Vec<Kind>, ParseError> = ::serde::Deserialize::deserialize(value.into_deserializer());
+    assert_eq!(kinds.unwrap(), vec![Kind::B(1.5), Kind::A]);
+  }
+
+  #[test]
+  fn it_should_error_deserializing_from_value() {
+    let value = parse_to_value(
+      r#"{ "name": "test", "values": [256], "kind": "A" }"#,
+      &Default::default(),
+    )
+    .unwrap()
+    .unwrap();
+    let err = <Config as ::serde::Deserialize>::deserialize(value).unwrap_err();
+    assert_eq!(err.to_string(), "invalid value: integer `256`, expected u8");
+
+    let value = parse_to_value(r#"{ "A": null, "B": 1 }"#, &Default::default())
+      .unwrap()
+      .unwrap();
+    let err = <Kind as ::serde::Deserialize>::deserialize(value).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "invalid type: map, expected a string or an object with a single property"
+    );
+
+    let value = parse_to_value(r#"[1, 2, 3]"#, &Default::default()).unwrap().unwrap();
+    let err = <(u8, u8) as ::serde::Deserialize>::deserialize(value).unwrap_err();
+    assert_eq!(err.to_string(), "invalid length 3, expected 2 elements in sequence");
+  }
+
+  #[cfg(feature = "preserve_order")]
+  #[test]
+  fn it_should_serialize_in_order() {
+    assert_eq!(to_json(r#"{ "b": 1, "a": 2, "c": 3 }"#), r#"{"b":1,"a":2,"c":3}"#);
+  }
+}
@@ -10,6 +10,8 @@ pub enum ParseStringErrorKind {
   InvalidEscapeInSingleQuoteString,
   InvalidEscapeInDoubleQuoteString,
   ExpectedFourHexDigits,
+  ExpectedTwoHexDigits,
+  UnescapedControlCharacter,
   InvalidUnicodeEscapeSequence(String),
   InvalidEscape,
   UnterminatedStringLiteral,
@@ -29,6 +31,12 @@ impl std::fmt::Display for ParseStringErrorKind {
       ParseStringErrorKind::ExpectedFourHexDigits => {
         write!(f, "Expected four hex digits")
       }
+      ParseStringErrorKind::ExpectedTwoHexDigits => {
+        write!(f, "Expected two hex digits")
+      }
+      ParseStringErrorKind::UnescapedControlCharacter => {
+        write!(f, "Control characters must be escaped in strings")
+      }
       ParseStringErrorKind::InvalidUnicodeEscapeSequence(value) => {
         write!(
           f,
@@ -46,6 +54,17 @@ impl std::fmt::Display for ParseStringErrorKind {
   }
 }
 
+/// Options for decoding string literals.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseStringOptions {
+  /// Allow a backslash followed by a line terminator, which is removed from the string.
+  pub allow_line_continuations: bool,
+  /// Allow the JSON5 escapes `\xHH`, `\v`, `\0`, and escaping any other character.
+  pub allow_extended_escapes: bool,
+  /// Allow unescaped control characters (U+0000 through U+001F).
+  pub allow_control_characters: bool,
+}
+
 pub trait CharProvider<'a> {
   fn current_char(&mut self) -> Option<char>;
   fn byte_index(&self) -> usize;
@@ -53,50 +72,65 @@ pub trait CharProvider<'a> {
   fn text(&self) -> &'a str;
 }
 
-#[cfg(feature = "cst")]
-pub fn parse_string(text: &str) -> Result<Cow<'_, str>, ParseStringError> {
-  struct StringCharProvider<'a> {
-    text: &'a str,
-    byte_index: usize,
-    current_char: Option<char>,
-    chars: std::str::Chars<'a>,
-  }
+struct StringCharProvider<'a> {
+  text: &'a str,
+  byte_index: usize,
+  current_char: Option<char>,
+  chars: std::str::Chars<'a>,
+}
 
-  impl<'a> CharProvider<'a> for StringCharProvider<'a> {
-    fn current_char(&mut self) -> Option<char> {
-      self.current_char
+impl<'a> StringCharProvider<'a> {
+  fn new(text: &'a str) -> Self {
+    let mut chars = text.chars();
+    StringCharProvider {
+      text,
+      byte_index: 0,
+      current_char: chars.next(),
+      chars,
     }
+  }
+}
 
-    fn byte_index(&self) -> usize {
-      self.byte_index
-    }
+impl<'a> CharProvider<'a> for StringCharProvider<'a> {
+  fn current_char(&mut self) -> Option<char> {
+    self.current_char
+  }
 
-    fn move_next_char(&mut self) -> Option<char> {
-      if let Some(current_char) = self.current_char {
-        self.byte_index += current_char.len_utf8();
-      }
-      self.current_char = self.chars.next();
-      self.current_char
-    }
+  fn byte_index(&self) -> usize {
+    self.byte_index
+  }
 
-    fn text(&self) -> &'a str {
-      self.text
+  fn move_next_char(&mut self) -> Option<char> {
+    if let Some(current_char) = self.current_char {
+      self.byte_index += current_char.len_utf8();
     }
+    self.current_char = self.chars.next();
+    self.current_char
   }
 
-  let mut chars = text.chars();
-  let mut provider = StringCharProvider {
-    text,
-    byte_index: 0,
-    current_char: chars.next(),
-    chars,
-  };
+  fn text(&self) -> &'a str {
+    self.text
+  }
+}
+
+#[cfg(feature = "cst")]
+pub fn parse_string(text: &str) -> Result<Cow<'_, str>, ParseStringError> {
+  let mut provider = StringCharProvider::new(text);
 
-  parse_string_with_char_provider(&mut provider)
+  // the text was validated when parsed, so decode it with the most permissive options
+  parse_string_with_char_provider(
+    &mut provider,
+    ParseStringOptions {
+      allow_line_continuations: true,
+      allow_extended_escapes: true,
+      allow_control_characters: true,
+    },
+  )
 }
 
 pub fn parse_string_with_char_provider<'a, T: CharProvider<'a>>(
   chars: &mut T,
+  options: ParseStringOptions,
 ) -> Result<Cow<'a, str>, ParseStringError> {
   debug_assert!(
     chars.current_char() == Some('\'') || chars.current_char() == Some('"'),
@@ -108,63 +142,92 @@ pub fn parse_string_with_char_provider<'a, T: CharProvider<'a>>(
   let mut text: Option<String> = None;
   let mut last_was_backslash = false;
   let mut found_end_string = false;
+  // set after a line continuation of \r in order to also remove a following \n
+  let mut skip_line_feed = false;
+  // start of the last `\0` escape, which must not be followed by a digit
+  let mut null_escape_start: Option<usize> = None;
   let token_start = chars.byte_index();
 
   while let Some(current_char) = chars.move_next_char() {
+    if let Some(escape_start) = null_escape_start.take()
+      && current_char.is_ascii_digit()
+    {
+      return Err(ParseStringError {
+        byte_index: escape_start,
+        kind: ParseStringErrorKind::InvalidEscape,
+      });
+    }
+    if std::mem::take(&mut skip_line_feed) && current_char == '\n' {
+      last_start_byte_index = chars.byte_index() + 1;
+      continue;
+    }
+
     if last_was_backslash {
       let escape_start = chars.byte_index() - 1; // -1 for backslash
-      match current_char {
-        '"' | '\'' | '\\' | '/' | 'b' | 'f' | 'u' | 'r' | 'n' | 't' => {
-          if current_char == '"' {
-            if !is_double_quote {
-              return Err(ParseStringError {
-                byte_index: escape_start,
-                kind: ParseStringErrorKind::InvalidEscapeInSingleQuoteString,
-              });
-            }
-          } else if current_char == '\'' && is_double_quote {
-            return Err(ParseStringError {
-              byte_index: escape_start,
-              kind: ParseStringErrorKind::InvalidEscapeInDoubleQuoteString,
-            });
-          }
-
-          let previous_text = &chars.text()[last_start_byte_index..escape_start];
-          if text.is_none() {
-            text = Some(String::new());
-          }
-          let text = text.as_mut().unwrap();
-          text.push_str(previous_text);
-          if current_char == 'u' {
-            let hex_char = parse_hex_char(chars).map_err(|kind| ParseStringError {
-              byte_index: escape_start,
-              kind,
-            })?;
-            text.push(hex_char);
-            last_start_byte_index = chars.byte_index() + chars.current_char().map(|c| c.len_utf8()).unwrap_or(0);
-          } else {
-            text.push(match current_char {
-              'b' => '\u{08}',
-              'f' => '\u{0C}',
-              't' => '\t',
-              'r' => '\r',
-              'n' => '\n',
-              _ => current_char,
-            });
-            last_start_byte_index = chars.byte_index() + current_char.len_utf8();
-          }
+      let decoded_char = match current_char {
+        '"' if !is_double_quote && !options.allow_extended_escapes => {
+          return Err(ParseStringError {
+            byte_index: escape_start,
+            kind: ParseStringErrorKind::InvalidEscapeInSingleQuoteString,
+          });
         }
+        '\'' if is_double_quote && !options.allow_extended_escapes => {
+          return Err(ParseStringError {
+            byte_index: escape_start,
+            kind: ParseStringErrorKind::InvalidEscapeInDoubleQuoteString,
+          });
+        }
+        '"' | '\'' | '\\' | '/' => Some(current_char),
+        'b' => Some('\u{08}'),
+        'f' => Some('\u{0C}'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        'n' => Some('\n'),
+        'u' => Some(parse_hex_char(chars).map_err(|kind| ParseStringError {
+          byte_index: escape_start,
+          kind,
+        })?),
+        'x' if options.allow_extended_escapes => {
+          Some(parse_two_hex_digit_char(chars).map_err(|kind| ParseStringError {
+            byte_index: escape_start,
+            kind,
+          })?)
+        }
+        'v' if options.allow_extended_escapes => Some('\u{0B}'),
+        '0' if options.allow_extended_escapes => {
+          null_escape_start = Some(escape_start);
+          Some('\0')
+        }
+        '\n' | '\u{2028}' | '\u{2029}' if options.allow_line_continuations => None,
+        '\r' if options.allow_line_continuations => {
+          skip_line_feed = true;
+          None
+        }
+        c if options.allow_extended_escapes && !c.is_ascii_digit() && !is_line_terminator(c) => Some(c),
         _ => {
           return Err(ParseStringError {
             byte_index: escape_start,
             kind: ParseStringErrorKind::InvalidEscape,
           });
         }
+      };
+
+      let previous_text = &chars.text()[last_start_byte_index..escape_start];
+      let text = text.get_or_insert_with(String::new);
+      text.push_str(previous_text);
+      if let Some(decoded_char) = decoded_char {
+        text.push(decoded_char);
       }
+      last_start_byte_index = chars.byte_index() + chars.current_char().map(|c| c.len_utf8()).unwrap_or(0);
       last_was_backslash = false;
     } else if is_double_quote && current_char == '"' || !is_double_quote && current_char == '\'' {
       found_end_string = true;
       break;
+    } else if current_char < '\u{20}' && !options.allow_control_characters {
+      return Err(ParseStringError {
+        byte_index: chars.byte_index(),
+        kind: ParseStringErrorKind::UnescapedControlCharacter,
+      });
     } else {
       last_was_backslash = current_char == '\\';
     }
@@ -188,6 +251,74 @@ pub fn parse_string_with_char_provider<'a, T: CharProvider<'a>>(
   }
 }
 
+/// Gets if the character may be part of an unquoted word.
+///
+/// This follows the JSON5 IdentifierPart production using the Unicode
+/// Alphabetic and Numeric properties for letters and digits, along with
+/// ZWNJ, ZWJ, connector punctuation, and combining diacritical marks. Words
+/// may also contain `-`, which is allowed for compatibility.
+pub(crate) fn is_word_char(c: char, allow_dollar_sign: bool) -> bool {
+  match c {
+    '-' | '_' => true,
+    '$' => allow_dollar_sign,
+    '\u{200C}' | '\u{200D}' => true,
+    '\u{203F}' | '\u{2040}' | '\u{2054}' | '\u{FE33}' | '\u{FE34}' | '\u{FE4D}'..='\u{FE4F}' | '\u{FF3F}' => true,
+    '\u{0300}'..='\u{036F}'
+    | '\u{1AB0}'..='\u{1AFF}'
+    | '\u{1DC0}'..='\u{1DFF}'
+    | '\u{20D0}'..='\u{20FF}'
+    | '\u{FE20}'..='\u{FE2F}' => true,
+    c => c.is_alphanumeric(),
+  }
+}
+
+/// Parses a `\uXXXX` escape in a word, which must start at the current
+/// character. The last hex digit is the current character afterwards.
+pub(crate) fn parse_word_escape<'a, T: CharProvider<'a>>(chars: &mut T) -> Option<char> {
+  debug_assert_eq!(chars.current_char(), Some('\\'));
+  if chars.move_next_char() != Some('u') {
+    return None;
+  }
+  parse_hex_char(chars).ok()
+}
+
+/// Decodes the `\uXXXX` escapes in a word that was validated when scanned.
+pub(crate) fn decode_word(text: &str) -> Cow<'_, str> {
+  if !text.contains('\\') {
+    return Cow::Borrowed(text);
+  }
+  let mut provider = StringCharProvider::new(text);
+  let mut decoded = String::with_capacity(text.len());
+  while let Some(c) = provider.current_char() {
+    if c == '\\' {
+      match parse_word_escape(&mut provider) {
+        Some(c) => decoded.push(c),
+        None => return Cow::Borrowed(text),
+      }
+    } else {
+      decoded.push(c);
+    }
+    provider.move_next_char();
+  }
+  Cow::Owned(decoded)
+}
+
+fn is_line_terminator(c: char) -> bool {
+  matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn parse_two_hex_digit_char<'a, T: CharProvider<'a>>(chars: &mut T) -> Result<char, ParseStringErrorKind> {
+  let mut value = 0;
+  for _ in 0..2 {
+    match chars.move_next_char().and_then(|c| c.to_digit(16)) {
+      Some(digit) => value = value * 16 + digit,
+      None => return Err(ParseStringErrorKind::ExpectedTwoHexDigits),
+    }
+  }
+  // two hex digits are always a valid char
+  Ok(char::from_u32(value).unwrap())
+}
+
 fn read_four_hex_digits<'a, T: CharProvider<'a>>(
   chars: &mut T,
   buf: &mut [u8; 4],
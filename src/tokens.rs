@@ -44,6 +44,29 @@ impl<'a> Token<'a> {
       Token::CommentBlock(value) => value,
     }
   }
+
+  /// Gets if this token can be an object property name, which for tokens
+  /// other than strings requires loose property names to be allowed.
+  pub(crate) fn is_object_key(&self) -> bool {
+    matches!(
+      self,
+      Token::String(_) | Token::Word(_) | Token::Number(_) | Token::Boolean(_) | Token::Null
+    )
+  }
+
+  /// Gets if this token can start a value.
+  pub(crate) fn is_value_start(&self) -> bool {
+    matches!(
+      self,
+      Token::OpenBrace
+        | Token::OpenBracket
+        | Token::String(_)
+        | Token::Word(_)
+        | Token::Boolean(_)
+        | Token::Number(_)
+        | Token::Null
+    )
+  }
 }
 
 /// A token with positional information.
@@ -2,7 +2,11 @@ use core::slice::Iter;
 use std::borrow::Cow;
 
 use crate::map::IntoIter as MapIntoIter;
+use crate::map::Iter as MapIter;
 use crate::map::Map;
+use crate::number::impl_number_conversions;
+use crate::pointer::parse_array_index;
+use crate::pointer::parse_pointer;
 
 /// A JSON value.
 #[derive(Clone, PartialEq, Debug)]
@@ -15,6 +19,69 @@ pub enum JsonValue<'a> {
   Null,
 }
 
+// the conversions error with `NumberConversionError::Invalid` when not a number
+impl_number_conversions!(JsonValue<'_>, |self| match self {
+  JsonValue::Number(raw) => raw,
+  _ => "",
+});
+
+impl<'a> JsonValue<'a> {
+  /// Gets the value at the JSON Pointer (RFC 6901) such as `/compilerOptions/paths/0`.
+  ///
+  /// Returns `None` when the pointer is malformed or doesn't refer to a value.
+  pub fn pointer(&self, pointer: &str) -> Option<&JsonValue<'a>> {
+    parse_pointer(pointer)?
+      .iter()
+      .try_fold(self, |value, token| match value {
+        JsonValue::Object(obj) => obj.get(token),
+        JsonValue::Array(arr) => arr.get(parse_array_index(token)?),
+        _ => None,
+      })
+  }
+
+  /// Gets a mutable reference to the value at the JSON Pointer (RFC 6901).
+  ///
+  /// Returns `None` when the pointer is malformed or doesn't refer to a value.
+  pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue<'a>> {
+    parse_pointer(pointer)?
+      .iter()
+      .try_fold(self, |value, token| match value {
+        JsonValue::Object(obj) => obj.get_mut(token),
+        JsonValue::Array(arr) => arr.get_mut(parse_array_index(token)?),
+        _ => None,
+      })
+  }
+
+  /// Applies a JSON Merge Patch (RFC 7396) to the value.
+  ///
+  /// Properties with a `null` value in the patch are removed, objects are
+  /// merged recursively, and any other value replaces the existing value.
+  pub fn apply_merge_patch(&mut self, patch: JsonValue<'a>) {
+    let JsonValue::Object(patch) = patch else {
+      *self = patch;
+      return;
+    };
+    if !matches!(self, JsonValue::Object(_)) {
+      *self = JsonValue::Object(JsonObject::with_capacity(patch.len()));
+    }
+    let JsonValue::Object(target) = self else {
+      unreachable!();
+    };
+    for (name, value) in patch {
+      if value == JsonValue::Null {
+        target.take(&name);
+      } else if let Some(existing) = target.get_mut(&name) {
+        existing.apply_merge_patch(value);
+      } else {
+        // merge into null in order to remove any nested null properties
+        let mut new_value = JsonValue::Null;
+        new_value.apply_merge_patch(value);
+        target.0.insert(name, new_value);
+      }
+    }
+  }
+}
+
 /// A JSON object.
 #[derive(Clone, PartialEq, Debug)]
 pub struct JsonObject<'a>(Map<Cow<'a, str>, JsonValue<'a>>);
@@ -83,11 +150,21 @@ impl<'a> JsonObject<'a> {
     self.0.is_empty()
   }
 
+  /// Iterates over the properties.
+  pub fn iter(&self) -> MapIter<'_, Cow<'a, str>, JsonValue<'a>> {
+    self.0.iter()
+  }
+
   /// Gets a value in the object by its name.
   pub fn get(&self, name: &str) -> Option<&JsonValue<'a>> {
     self.0.get(name)
   }
 
+  /// Gets a mutable reference to a value in the object by its name.
+  pub fn get_mut(&mut self, name: &str) -> Option<&mut JsonValue<'a>> {
+    self.0.get_mut(name)
+  }
+
   /// Gets a string property value from the object by name.
   /// Returns `None` when not a string or it doesn't exist.
   pub fn get_string(&self, name: &str) -> Option<&Cow<'a, str>> {
@@ -196,6 +273,11 @@ impl<'a> JsonArray<'a> {
     self.0.get(index)
   }
 
+  /// Gets a mutable reference to a value in the array by index.
+  pub fn get_mut(&mut self, index: usize) -> Option<&mut JsonValue<'a>> {
+    self.0.get_mut(index)
+  }
+
   /// Gets the number of elements.
   pub fn len(&self) -> usize {
     self.0.len()
@@ -244,4 +326,64 @@ mod test {
     assert_eq!(obj.get("asdf"), None);
     assert_eq!(obj.len(), 1);
   }
+
+  #[test]
+  fn it_should_get_by_pointer() {
+    let text = r#"{ "compilerOptions": { "paths": { "a/b": ["x", "y"], "m~n": 1 } }, "": 2 }"#;
+    let mut value = crate::parse_to_value(text, &Default::default()).unwrap().unwrap();
+
+    assert_eq!(value.pointer(""), Some(&value.clone()));
+    assert_eq!(
+      value.pointer("/compilerOptions/paths/a~1b/1"),
+      Some(&JsonValue::String(Cow::Borrowed("y")))
+    );
+    assert_eq!(
+      value.pointer("/compilerOptions/paths/m~0n"),
+      Some(&JsonValue::Number("1"))
+    );
+    assert_eq!(value.pointer("/"), Some(&JsonValue::Number("2")));
+    assert_eq!(value.pointer("/compilerOptions/paths/a~1b/01"), None);
+    assert_eq!(value.pointer("/compilerOptions/paths/a~1b/2"), None);
+    assert_eq!(value.pointer("/compilerOptions/missing"), None);
+    assert_eq!(value.pointer("compilerOptions"), None);
+
+    *value.pointer_mut("/compilerOptions/paths/a~1b/0").unwrap() = JsonValue::Boolean(true);
+    assert_eq!(
+      value.pointer("/compilerOptions/paths/a~1b/0"),
+      Some(&JsonValue::Boolean(true))
+    );
+  }
+
+  #[test]
+  fn it_should_apply_merge_patch() {
+    // examples from RFC 7396
+    let cases = [
+      (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+      (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+      (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+      (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+      (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+      (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+      (
+        r#"{"a":{"b":"c"}}"#,
+        r#"{"a":{"b":"d","c":null}}"#,
+        r#"{"a":{"b":"d"}}"#,
+      ),
+      (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+      (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+      (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+      (r#"{"a":"foo"}"#, r#"null"#, r#"null"#),
+      (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+      (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+      (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+      (r#"{}"#, r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#),
+    ];
+    for (target, patch, expected) in cases {
+      let mut value = crate::parse_to_value(target, &Default::default()).unwrap().unwrap();
+      let patch = crate::parse_to_value(patch, &Default::default()).unwrap().unwrap();
+      let expected = crate::parse_to_value(expected, &Default::default()).unwrap().unwrap();
+      value.apply_merge_patch(patch);
+      assert_eq!(value, expected, "Target: {}", target);
+    }
+  }
 }
@@ -105,6 +105,7 @@ fn value_to_test_str(value: &Value) -> String {
     Value::Object(obj) => object_to_test_str(obj),
     Value::Array(arr) => array_to_test_str(arr),
     Value::NullKeyword(keyword) => null_keyword_to_test_str(keyword),
+    _ => unreachable!("only created when recovering from errors"),
   }
 }
 
@@ -122,7 +123,7 @@ fn string_lit_to_test_str(lit: &StringLit) -> String {
 }
 
 fn word_lit_to_test_str(lit: &WordLit) -> String {
-  lit_to_test_str("word", lit.value, lit.range)
+  lit_to_test_str("word", &lit.value, lit.range)
 }
 
 fn number_lit_to_test_str(lit: &NumberLit) -> String {